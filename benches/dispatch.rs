@@ -0,0 +1,85 @@
+//! Frame classification throughput on a combined-stream capture.
+//!
+//! `cargo bench --bench dispatch` compares the old trial-parse chain (every parser
+//! re-deserializes the frame until one matches) with the single-pass dispatcher.
+//! Set `BENCH_CAPTURE` to a file recorded with `MARKET_CAPTURE_PATH` to measure real
+//! traffic; otherwise the synthetic `benches/data/stream_capture.jsonl` is used.
+
+use std::time::Duration;
+
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use feeder_service::binance::parse_agg_trade;
+use feeder_service::binance_depth::parse_depth_update;
+use feeder_service::binance_funding::parse_funding_rate_update;
+use feeder_service::binance_kline::parse_kline_event;
+use feeder_service::correlation::model::parse_news_event;
+use feeder_service::dispatch::{StreamMessage, dispatch_message};
+
+const SYNTHETIC_CAPTURE: &str = include_str!("data/stream_capture.jsonl");
+
+fn load_capture() -> String {
+    match std::env::var("BENCH_CAPTURE") {
+        Ok(path) => std::fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("cannot read BENCH_CAPTURE {path}: {err}")),
+        Err(_) => SYNTHETIC_CAPTURE.to_string(),
+    }
+}
+
+/// The pre-dispatcher order used by the ingest loop.
+fn trial_parse(frame: &str) -> u8 {
+    if parse_agg_trade(frame).is_some() {
+        return 1;
+    }
+    if parse_depth_update(frame).is_some() {
+        return 2;
+    }
+    if parse_kline_event(frame).is_some() {
+        return 3;
+    }
+    if parse_news_event(frame).is_some() {
+        return 4;
+    }
+    if parse_funding_rate_update(frame).is_some() {
+        return 5;
+    }
+    0
+}
+
+fn single_pass(frame: &str) -> u8 {
+    match dispatch_message(frame) {
+        StreamMessage::Market(_) => 1,
+        StreamMessage::News(_) => 4,
+        StreamMessage::MiniTickers(_) => 6,
+        StreamMessage::Unhandled | StreamMessage::Invalid(_) => 0,
+    }
+}
+
+fn bench_dispatch(c: &mut Criterion) {
+    let capture = load_capture();
+    let frames: Vec<&str> = capture.lines().filter(|l| !l.is_empty()).collect();
+
+    let mut group = c.benchmark_group("classify_capture");
+    group
+        .sample_size(30)
+        .warm_up_time(Duration::from_secs(1))
+        .measurement_time(Duration::from_secs(3));
+    group.throughput(Throughput::Elements(frames.len() as u64));
+    group.bench_function("trial_parse_chain", |b| {
+        b.iter(|| {
+            for frame in &frames {
+                black_box(trial_parse(black_box(frame)));
+            }
+        })
+    });
+    group.bench_function("single_pass_dispatch", |b| {
+        b.iter(|| {
+            for frame in &frames {
+                black_box(single_pass(black_box(frame)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_dispatch);
+criterion_main!(benches);
@@ -0,0 +1,852 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, params};
+use serde::Serialize;
+
+use crate::binance::AggTrade;
+use crate::binance_depth::{
+    DepthUpdate, collect_big_levels, is_big_depth_update, passes_pressure_filter,
+};
+use crate::binance_funding::{funding_rate_pct, is_high_funding_rate};
+use crate::binance_kline::build_quant_signal_from_kline;
+use crate::config::{Config, SymbolConfig};
+use crate::correlation::engine::{CorrelationEngine, CorrelationWeights};
+use crate::correlation::model::{MarketEvent, MarketEventKind, NewsEvent};
+use crate::dispatch::{StreamMessage, dispatch_message};
+use crate::outcomes::{HORIZONS, OutcomeStats, OutcomeStore, OutcomeTracker, TrackedSignal};
+use crate::refactor::big_move_detector::{BigMoveDetector, BigMoveProfile, DepthSnapshot};
+use crate::shard::MarketMessage;
+use crate::spike_window::SpikeWindowDetector;
+
+/// Signal types counted per parameter set, in report order.
+pub const SIGNAL_TYPES: [&str; 5] = ["big_move", "funding", "news_corr", "price_spike", "quant4h"];
+
+/// One historical input, replayed in timestamp order.
+#[derive(Debug)]
+pub enum ReplayEvent {
+    Market(MarketMessage),
+    News(NewsEvent),
+}
+
+impl ReplayEvent {
+    /// Exchange event time; the simulated clock.
+    pub fn timestamp_ms(&self) -> u64 {
+        match self {
+            ReplayEvent::Market(MarketMessage::AggTrade(agg)) => agg.t,
+            ReplayEvent::Market(MarketMessage::Depth(depth)) => depth.event_time,
+            ReplayEvent::Market(MarketMessage::Kline(kline)) => kline.event_time,
+            ReplayEvent::Market(MarketMessage::Funding(funding)) => funding.event_time,
+            ReplayEvent::News(news) => news.timestamp_ms,
+        }
+    }
+}
+
+/// Reads trades for `symbols` from the `agg_trades` table written by `fetch_agg`.
+pub fn load_agg_trades(
+    db_path: &str,
+    symbols: &[String],
+    from_ms: u64,
+    to_ms: u64,
+) -> Result<Vec<ReplayEvent>> {
+    let conn = Connection::open(db_path).with_context(|| format!("open {db_path}"))?;
+    let mut stmt = conn.prepare(
+        "SELECT symbol, price, qty, timestamp, is_buyer_maker FROM agg_trades
+         WHERE (symbol = ?1 OR symbol = ?2) AND timestamp BETWEEN ?3 AND ?4
+         ORDER BY timestamp, trade_id",
+    )?;
+    let mut events = Vec::new();
+    for symbol in symbols {
+        let rows = stmt.query_map(
+            params![
+                symbol.to_uppercase(),
+                symbol.to_lowercase(),
+                from_ms as i64,
+                to_ms.min(i64::MAX as u64) as i64
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, f64>(1)?,
+                    row.get::<_, f64>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                ))
+            },
+        )?;
+        for row in rows {
+            let (s, price, qty, ts, is_buyer_maker) = row?;
+            let (Ok(p), Ok(q)) = (price.to_string().parse(), qty.to_string().parse()) else {
+                continue;
+            };
+            events.push(ReplayEvent::Market(MarketMessage::AggTrade(AggTrade {
+                s,
+                p,
+                q,
+                t: ts as u64,
+                m: is_buyer_maker != 0,
+            })));
+        }
+    }
+    Ok(events)
+}
+
+/// Reads a capture of raw stream frames, one per line, as written with
+/// `MARKET_CAPTURE_PATH`. Mini tickers and frames for other symbols are skipped.
+pub fn load_capture(
+    path: &str,
+    symbols: &[String],
+    from_ms: u64,
+    to_ms: u64,
+) -> Result<Vec<ReplayEvent>> {
+    let file = std::fs::File::open(path).with_context(|| format!("open {path}"))?;
+    let wanted: HashSet<String> = symbols.iter().map(|s| s.to_lowercase()).collect();
+    let mut events = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = match dispatch_message(&line) {
+            StreamMessage::Market(message) if wanted.contains(&message.symbol().to_lowercase()) => {
+                ReplayEvent::Market(message)
+            }
+            StreamMessage::News(news) if wanted.contains(&news.symbol.to_lowercase()) => {
+                ReplayEvent::News(news)
+            }
+            _ => continue,
+        };
+        if (from_ms..=to_ms).contains(&event.timestamp_ms()) {
+            events.push(event);
+        }
+    }
+    Ok(events)
+}
+
+/// Orders events on the simulated clock; ties keep their load order.
+pub fn sort_events(events: &mut [ReplayEvent]) {
+    events.sort_by_key(ReplayEvent::timestamp_ms);
+}
+
+/// Tunable settings for one backtest run, applied to every replayed symbol.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BacktestParams {
+    pub spike_pct: f64,
+    pub big_move_window: usize,
+    pub big_move_pressure: f64,
+    pub big_move_consecutive: usize,
+    pub big_move_min_notional: f64,
+    pub funding_rate_pct: f64,
+    pub corr_min_move_pct: f64,
+    pub corr_max_lag_secs: u64,
+    pub corr_min_confidence: f64,
+    pub corr_weights: CorrelationWeights,
+}
+
+/// Grid keys accepted by `BacktestParams::set`, in CSV column order.
+pub const PARAM_KEYS: [&str; 13] = [
+    "spike_pct",
+    "big_move_window",
+    "big_move_pressure",
+    "big_move_consecutive",
+    "big_move_min_notional",
+    "funding_rate_pct",
+    "corr_min_move_pct",
+    "corr_max_lag_secs",
+    "corr_min_confidence",
+    "corr_w_magnitude",
+    "corr_w_notional",
+    "corr_w_recency",
+    "corr_w_sentiment",
+];
+
+impl BacktestParams {
+    /// Live settings: the first symbol's spike threshold and big-move profile, plus the
+    /// global funding and correlation thresholds.
+    pub fn from_config(config: &Config) -> Self {
+        let first = config.symbols.first();
+        let profile = first
+            .and_then(|cfg| cfg.big_move_profiles.first().cloned())
+            .unwrap_or_default();
+        Self {
+            spike_pct: first.map_or(0.5, |cfg| cfg.spike_pct),
+            big_move_window: profile.window_size,
+            big_move_pressure: profile.pressure_threshold,
+            big_move_consecutive: profile.min_consecutive,
+            big_move_min_notional: profile.min_total_notional,
+            funding_rate_pct: config.funding_rate_alert_pct,
+            corr_min_move_pct: config.corr_min_move_pct,
+            corr_max_lag_secs: config.corr_max_lag_seconds,
+            corr_min_confidence: config.corr_min_confidence,
+            corr_weights: CorrelationWeights::default(),
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: f64) -> Result<(), String> {
+        if !value.is_finite() || value < 0.0 {
+            return Err(format!("{key}: invalid value {value}"));
+        }
+        match key {
+            "spike_pct" => self.spike_pct = value,
+            "big_move_window" => self.big_move_window = (value as usize).max(1),
+            "big_move_pressure" => self.big_move_pressure = value.clamp(50.0, 100.0),
+            "big_move_consecutive" => self.big_move_consecutive = (value as usize).max(1),
+            "big_move_min_notional" => self.big_move_min_notional = value,
+            "funding_rate_pct" => self.funding_rate_pct = value,
+            "corr_min_move_pct" => self.corr_min_move_pct = value,
+            "corr_max_lag_secs" => self.corr_max_lag_secs = value as u64,
+            "corr_min_confidence" => self.corr_min_confidence = value.min(1.0),
+            "corr_w_magnitude" => self.corr_weights.magnitude = value,
+            "corr_w_notional" => self.corr_weights.notional = value,
+            "corr_w_recency" => self.corr_weights.recency = value,
+            "corr_w_sentiment" => self.corr_weights.sentiment = value,
+            _ => return Err(format!("unknown grid parameter '{key}'")),
+        }
+        Ok(())
+    }
+
+    /// Values in `PARAM_KEYS` order.
+    pub fn values(&self) -> [f64; 13] {
+        [
+            self.spike_pct,
+            self.big_move_window as f64,
+            self.big_move_pressure,
+            self.big_move_consecutive as f64,
+            self.big_move_min_notional,
+            self.funding_rate_pct,
+            self.corr_min_move_pct,
+            self.corr_max_lag_secs as f64,
+            self.corr_min_confidence,
+            self.corr_weights.magnitude,
+            self.corr_weights.notional,
+            self.corr_weights.recency,
+            self.corr_weights.sentiment,
+        ]
+    }
+
+    fn big_move_profile(&self, base: &BigMoveProfile) -> BigMoveProfile {
+        BigMoveProfile {
+            name: "backtest".to_string(),
+            window_size: self.big_move_window,
+            pressure_threshold: self.big_move_pressure,
+            min_total_notional: self.big_move_min_notional,
+            min_consecutive: self.big_move_consecutive.min(self.big_move_window),
+            ..base.clone()
+        }
+    }
+}
+
+/// Parses `key=v1,v2;key2=v3` into the values to sweep per key.
+pub fn parse_grid(raw: &str) -> Result<Vec<(String, Vec<f64>)>, String> {
+    let mut grid = Vec::new();
+    for entry in raw.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let (key, values) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("grid entry '{entry}' is not key=values"))?;
+        let key = key.trim().to_lowercase();
+        if !PARAM_KEYS.contains(&key.as_str()) {
+            return Err(format!("unknown grid parameter '{key}'"));
+        }
+        let values = values
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|v| {
+                v.parse::<f64>()
+                    .map_err(|_| format!("{key}: invalid value '{v}'"))
+            })
+            .collect::<Result<Vec<f64>, String>>()?;
+        if values.is_empty() {
+            return Err(format!("{key}: no values"));
+        }
+        grid.push((key, values));
+    }
+    Ok(grid)
+}
+
+/// Every combination of the grid values applied on top of `base`; an empty grid runs
+/// `base` alone.
+pub fn expand_grid(
+    base: &BacktestParams,
+    grid: &[(String, Vec<f64>)],
+) -> Result<Vec<BacktestParams>, String> {
+    let mut sets = vec![base.clone()];
+    for (key, values) in grid {
+        let mut next = Vec::with_capacity(sets.len() * values.len());
+        for params in &sets {
+            for value in values {
+                let mut params = params.clone();
+                params.set(key, *value)?;
+                next.push(params);
+            }
+        }
+        sets = next;
+    }
+    Ok(sets)
+}
+
+/// Signal counts and forward-return statistics for one parameter set.
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    pub set: usize,
+    pub params: BacktestParams,
+    pub signal_counts: BTreeMap<String, u64>,
+    /// Per signal type over all symbols; `news_corr` is counted but not tracked.
+    pub outcomes: Vec<OutcomeStats>,
+}
+
+/// Bid pressure and total notional of the big levels, with the live depth filters.
+fn depth_pressure(depth: &DepthUpdate, cfg: &SymbolConfig, config: &Config) -> Option<(f64, f64)> {
+    let matched_bids = collect_big_levels(&depth.bids, cfg.big_trade_qty, 3);
+    let matched_asks = collect_big_levels(&depth.asks, cfg.big_trade_qty, 3);
+    if !is_big_depth_update(&matched_bids, &matched_asks) {
+        return None;
+    }
+
+    let (min_qty, min_notional) = (config.big_depth_min_qty, config.big_depth_min_notional);
+    let side_notional = |levels: &[[crate::decimal::Decimal; 2]]| -> (f64, usize) {
+        levels
+            .iter()
+            .map(|level| (level[0].to_f64(), level[1].to_f64()))
+            .filter(|(price, qty)| {
+                price.is_finite() && qty.is_finite() && *price > 0.0 && *qty > 0.0
+            })
+            .filter(|(price, qty)| {
+                (min_qty <= 0.0 && min_notional <= 0.0)
+                    || (min_qty > 0.0 && *qty >= min_qty)
+                    || (min_notional > 0.0 && price * qty >= min_notional)
+            })
+            .fold((0.0, 0), |(sum, n), (price, qty)| {
+                (sum + price * qty, n + 1)
+            })
+    };
+    let (bid_notional, bid_levels) = side_notional(&depth.bids);
+    let (ask_notional, ask_levels) = side_notional(&depth.asks);
+    if bid_levels == 0 && ask_levels == 0 {
+        return None;
+    }
+
+    let total_notional = bid_notional + ask_notional;
+    let bid_pressure_pct = if total_notional > 0.0 {
+        (bid_notional / total_notional * 100.0).clamp(0.0, 100.0)
+    } else {
+        0.0
+    };
+    passes_pressure_filter(
+        bid_pressure_pct,
+        100.0 - bid_pressure_pct,
+        config.big_depth_min_pressure_pct,
+    )
+    .then_some((bid_pressure_pct, total_notional))
+}
+
+fn sign(value: f64) -> i8 {
+    if value > 0.0 {
+        1
+    } else if value < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+fn record_signal(
+    counts: &mut BTreeMap<String, u64>,
+    tracker: &mut OutcomeTracker,
+    signal_type: &'static str,
+    symbol: &str,
+    direction: i8,
+    timestamp_ms: u64,
+) {
+    *counts.entry(signal_type.to_string()).or_default() += 1;
+    tracker.track(TrackedSignal {
+        signal_type,
+        symbol: symbol.to_string(),
+        direction,
+        timestamp_ms,
+    });
+}
+
+/// Replays `events` (already sorted) through the spike, big-move, funding, quant and
+/// correlation detectors with `params`, tracking forward returns of directional signals.
+pub fn run_backtest(
+    config: &Config,
+    params: &BacktestParams,
+    events: &[ReplayEvent],
+    set: usize,
+) -> Result<BacktestReport> {
+    let symbols: HashMap<String, &SymbolConfig> = config
+        .symbols
+        .iter()
+        .map(|cfg| (cfg.symbol.to_lowercase(), cfg))
+        .collect();
+    let mut spike_detector = SpikeWindowDetector::new(&config.spike_windows_secs);
+    let mut big_moves: HashMap<String, BigMoveDetector> = HashMap::new();
+    let mut last_funding_ms: HashMap<String, u64> = HashMap::new();
+    let mut engine = CorrelationEngine::new(
+        params.corr_min_move_pct,
+        params.corr_max_lag_secs,
+        params.corr_min_confidence,
+    )
+    .with_weights(params.corr_weights);
+    let mut tracker = OutcomeTracker::default();
+    let mut completed = Vec::new();
+    let mut signal_counts: BTreeMap<String, u64> =
+        SIGNAL_TYPES.iter().map(|t| (t.to_string(), 0)).collect();
+
+    for event in events {
+        let message = match event {
+            ReplayEvent::News(news) => {
+                engine.ingest_news(news.clone());
+                continue;
+            }
+            ReplayEvent::Market(message) => message,
+        };
+        let symbol = message.symbol().to_lowercase();
+        let Some(cfg) = symbols.get(&symbol) else {
+            continue;
+        };
+
+        let market_event = match message {
+            MarketMessage::AggTrade(agg) => {
+                let price = agg.p.to_f64();
+                let qty = agg.q.to_f64();
+                completed.extend(tracker.on_trade(&symbol, agg.t, price));
+                let evaluation = spike_detector.push(&symbol, agg.t, price, qty, params.spike_pct);
+                for spike in &evaluation.spikes {
+                    let direction = sign(spike.move_pct);
+                    record_signal(
+                        &mut signal_counts,
+                        &mut tracker,
+                        "price_spike",
+                        &symbol,
+                        direction,
+                        agg.t,
+                    );
+                }
+                MarketEvent {
+                    symbol: symbol.clone(),
+                    timestamp_ms: agg.t,
+                    kind: MarketEventKind::AggTrade,
+                    move_pct: evaluation
+                        .spikes
+                        .iter()
+                        .map(|s| s.move_pct.abs())
+                        .fold(0.0, f64::max),
+                    notional: price * qty,
+                    price,
+                    direction: sign(evaluation.strongest_move_pct),
+                }
+            }
+            MarketMessage::Depth(depth) => {
+                let Some((bid_pressure_pct, total_notional)) = depth_pressure(depth, cfg, config)
+                else {
+                    continue;
+                };
+                let detector = big_moves.entry(symbol.clone()).or_insert_with(|| {
+                    let base = cfg.big_move_profiles.first().cloned().unwrap_or_default();
+                    BigMoveDetector::from_profile(&params.big_move_profile(&base))
+                });
+                let big_move = detector.push(DepthSnapshot {
+                    bid_pressure_pct,
+                    total_notional,
+                    timestamp_ms: depth.event_time,
+                });
+                if let Some(direction) = big_move.direction().filter(|_| big_move.is_start()) {
+                    let direction = direction.sign();
+                    record_signal(
+                        &mut signal_counts,
+                        &mut tracker,
+                        "big_move",
+                        &symbol,
+                        direction,
+                        depth.event_time,
+                    );
+                }
+                MarketEvent {
+                    symbol: symbol.clone(),
+                    timestamp_ms: depth.event_time,
+                    kind: MarketEventKind::DepthPressure,
+                    move_pct: ((bid_pressure_pct - 50.0).abs() / 50.0) * 100.0,
+                    notional: total_notional,
+                    price: 0.0,
+                    direction: sign(bid_pressure_pct - 50.0),
+                }
+            }
+            MarketMessage::Kline(kline) => {
+                let Some(quant) = build_quant_signal_from_kline(kline) else {
+                    continue;
+                };
+                let direction = sign(quant.return_pct);
+                if direction != 0 {
+                    record_signal(
+                        &mut signal_counts,
+                        &mut tracker,
+                        "quant4h",
+                        &symbol,
+                        direction,
+                        quant.interval_end_ms,
+                    );
+                }
+                MarketEvent {
+                    symbol: symbol.clone(),
+                    timestamp_ms: quant.interval_end_ms,
+                    kind: MarketEventKind::KlineClose,
+                    move_pct: quant.return_pct.abs(),
+                    notional: quant.quote_volume,
+                    price: quant.close,
+                    direction,
+                }
+            }
+            MarketMessage::Funding(funding) => {
+                let rate_pct = funding_rate_pct(&funding.funding_rate);
+                if !is_high_funding_rate(rate_pct, params.funding_rate_pct) {
+                    continue;
+                }
+                let cooldown_ms = config.funding_rate_cooldown_secs.saturating_mul(1_000);
+                if last_funding_ms
+                    .get(&symbol)
+                    .is_some_and(|last| funding.event_time.saturating_sub(*last) < cooldown_ms)
+                {
+                    continue;
+                }
+                last_funding_ms.insert(symbol.clone(), funding.event_time);
+                let direction = if rate_pct >= 0.0 { 1 } else { -1 };
+                record_signal(
+                    &mut signal_counts,
+                    &mut tracker,
+                    "funding",
+                    &symbol,
+                    direction,
+                    funding.event_time,
+                );
+                MarketEvent {
+                    symbol: symbol.clone(),
+                    timestamp_ms: funding.event_time,
+                    kind: MarketEventKind::FundingRate,
+                    move_pct: rate_pct.abs(),
+                    notional: 0.0,
+                    price: 0.0,
+                    direction,
+                }
+            }
+        };
+
+        if engine.on_market_event(market_event).is_some() {
+            *signal_counts.entry("news_corr".to_string()).or_default() += 1;
+        }
+    }
+
+    // Signals near the end of the data keep the horizons they reached.
+    completed.extend(tracker.expire(u64::MAX));
+    let store = OutcomeStore::open(":memory:")?;
+    store.insert_many(&completed)?;
+    Ok(BacktestReport {
+        set,
+        params: params.clone(),
+        signal_counts,
+        outcomes: store.stats(None, None, false)?,
+    })
+}
+
+/// One CSV row per parameter set and signal type.
+pub fn reports_to_csv(reports: &[BacktestReport]) -> String {
+    let mut header: Vec<String> = vec!["set".to_string()];
+    header.extend(PARAM_KEYS.iter().map(|k| k.to_string()));
+    header.extend(["signal_type".to_string(), "signals".to_string()]);
+    for (label, _) in HORIZONS {
+        header.extend([
+            format!("samples_{label}"),
+            format!("hit_rate_{label}"),
+            format!("avg_return_{label}"),
+        ]);
+    }
+    header.extend(["avg_mfe_pct".to_string(), "avg_mae_pct".to_string()]);
+
+    let opt = |v: Option<f64>| v.map(|v| format!("{v:.4}")).unwrap_or_default();
+    let mut out = header.join(",");
+    out.push('\n');
+    for report in reports {
+        let params: Vec<String> = report
+            .params
+            .values()
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        for (signal_type, count) in &report.signal_counts {
+            let stats = report
+                .outcomes
+                .iter()
+                .find(|s| &s.signal_type == signal_type);
+            let mut row = vec![report.set.to_string()];
+            row.extend(params.iter().cloned());
+            row.extend([signal_type.clone(), count.to_string()]);
+            for i in 0..HORIZONS.len() {
+                let horizon = stats.map(|s| &s.horizons[i]);
+                row.extend([
+                    horizon.map_or(0, |h| h.samples).to_string(),
+                    opt(horizon.and_then(|h| h.hit_rate_pct)),
+                    opt(horizon.and_then(|h| h.avg_return_pct)),
+                ]);
+            }
+            row.extend([
+                opt(stats.and_then(|s| s.avg_max_favorable_pct)),
+                opt(stats.and_then(|s| s.avg_max_adverse_pct)),
+            ]);
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decimal::Decimal;
+
+    fn d(v: f64) -> Decimal {
+        v.to_string().parse().expect("decimal")
+    }
+
+    fn trade(t: u64, price: f64) -> ReplayEvent {
+        ReplayEvent::Market(MarketMessage::AggTrade(AggTrade {
+            s: "BTCUSDT".to_string(),
+            p: d(price),
+            q: d(1.0),
+            t,
+            m: false,
+        }))
+    }
+
+    fn depth(t: u64, bid_qty: f64, ask_qty: f64) -> ReplayEvent {
+        ReplayEvent::Market(MarketMessage::Depth(DepthUpdate {
+            symbol: "BTCUSDT".to_string(),
+            bids: vec![[d(100.0), d(bid_qty)]],
+            asks: vec![[d(101.0), d(ask_qty)]],
+            event_time: t,
+            first_update_id: 0,
+            final_update_id: 0,
+        }))
+    }
+
+    fn config() -> Config {
+        use crate::config::*;
+        Config {
+            symbols: vec![SymbolConfig {
+                symbol: "btcusdt".to_string(),
+                big_trade_qty: 1.0,
+                spike_pct: 0.5,
+                whale_notional: 1_000_000.0,
+                big_trade_rule: crate::threshold::BigTradeRule::FixedQty,
+                big_move_profiles: vec![BigMoveProfile::default()],
+                ..Default::default()
+            }],
+            port: 9001,
+            broadcast_capacity: 32,
+            spike_windows_secs: vec![10, 60, 300],
+            funding_rate_alert_pct: 0.1,
+            funding_rate_cooldown_secs: 300,
+            corr_min_move_pct: 0.25,
+            corr_max_lag_seconds: 300,
+            corr_min_confidence: 0.6,
+            news: NewsConfig {
+                poll_interval_secs: 60,
+                retention_hours: 24,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn grid_expands_to_every_combination() {
+        let base = BacktestParams::from_config(&config());
+        let grid = parse_grid("spike_pct=0.2,0.5; big_move_pressure=70,80,90").expect("grid");
+        let sets = expand_grid(&base, &grid).expect("sets");
+        assert_eq!(sets.len(), 6);
+        assert_eq!((sets[5].spike_pct, sets[5].big_move_pressure), (0.5, 90.0));
+        assert_eq!(sets[0].funding_rate_pct, base.funding_rate_pct);
+        assert!(parse_grid("nope=1").is_err());
+        assert!(parse_grid("spike_pct=abc").is_err());
+        assert_eq!(expand_grid(&base, &[]).expect("base").len(), 1);
+    }
+
+    /// A bullish depth breakout, then a rally that keeps going past the 4h horizon.
+    fn breakout_events() -> Vec<ReplayEvent> {
+        let mut events = vec![trade(0, 100.0)];
+        for i in 0..4u64 {
+            events.push(depth(1_000 + i * 1_000, 20.0, 2.0));
+        }
+        for minute in 1..=250u64 {
+            events.push(trade(minute * 60_000, 100.0 + minute as f64 * 0.01));
+        }
+        sort_events(&mut events);
+        events
+    }
+
+    #[test]
+    fn replays_big_moves_with_forward_returns() {
+        let config = config();
+        let events = breakout_events();
+
+        let loose = BacktestParams {
+            spike_pct: 0.5,
+            ..BacktestParams::from_config(&config)
+        };
+        let report = run_backtest(&config, &loose, &events, 0).expect("run");
+        assert_eq!(report.signal_counts["big_move"], 1);
+        let big_move = report
+            .outcomes
+            .iter()
+            .find(|s| s.signal_type == "big_move")
+            .expect("big move outcome");
+        assert_eq!(big_move.signals, 1);
+        assert_eq!(big_move.horizons[4].hit_rate_pct, Some(100.0));
+
+        let strict = BacktestParams {
+            big_move_pressure: 95.0,
+            ..loose.clone()
+        };
+        let report = run_backtest(&config, &strict, &events, 1).expect("run");
+        assert_eq!(report.signal_counts["big_move"], 0);
+
+        let csv = reports_to_csv(&[report]);
+        let mut lines = csv.lines();
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .starts_with("set,spike_pct,big_move_window")
+        );
+        assert_eq!(lines.count(), SIGNAL_TYPES.len());
+    }
+
+    #[test]
+    fn grid_rejects_unknown_keys_and_bad_values() {
+        assert_eq!(
+            parse_grid("spike_pct=0.2; spike=0.5"),
+            Err("unknown grid parameter 'spike'".to_string())
+        );
+        assert_eq!(
+            parse_grid("spike_pct"),
+            Err("grid entry 'spike_pct' is not key=values".to_string())
+        );
+        assert_eq!(
+            parse_grid("spike_pct= , "),
+            Err("spike_pct: no values".to_string())
+        );
+        assert_eq!(parse_grid(" SPIKE_PCT = 0.2 ").unwrap()[0].0, "spike_pct");
+
+        let base = BacktestParams::from_config(&config());
+        let grid = parse_grid("funding_rate_pct=-0.1").expect("parses");
+        assert_eq!(
+            expand_grid(&base, &grid),
+            Err("funding_rate_pct: invalid value -0.1".to_string())
+        );
+        let mut params = base.clone();
+        assert!(params.set("nope", 1.0).is_err());
+        assert_eq!(params, base);
+    }
+
+    #[test]
+    fn load_capture_keeps_wanted_symbols_within_the_range() {
+        let path = std::env::temp_dir().join(format!(
+            "feeder-service-capture-{}.jsonl",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("valid time")
+                .as_nanos()
+        ));
+        let frames = [
+            r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1,"s":"BTCUSDT","p":"100","q":"1","T":2000,"m":false}}"#,
+            "",
+            r#"{"stream":"ethusdt@aggTrade","data":{"e":"aggTrade","E":1,"s":"ETHUSDT","p":"50","q":"1","T":2500,"m":false}}"#,
+            r#"{"stream":"!miniTicker@arr","data":[{"e":"24hrMiniTicker","E":1,"s":"BTCUSDT","c":"100","o":"99","h":"101","l":"98","v":"1","q":"100"}]}"#,
+            "not json",
+            r#"{"e":"news","s":"BTCUSDT","headline":"ETF approved","T":3000}"#,
+            r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":1500,"s":"BTCUSDT","U":1,"u":2,"b":[["100","2"]],"a":[]}}"#,
+            r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1,"s":"BTCUSDT","p":"101","q":"1","T":99000,"m":false}}"#,
+        ];
+        std::fs::write(&path, frames.join("\n")).expect("write capture");
+
+        let mut events = load_capture(
+            &path.to_string_lossy(),
+            &["BTCUSDT".to_string()],
+            1_000,
+            10_000,
+        )
+        .expect("load");
+        sort_events(&mut events);
+        let kinds: Vec<(&str, u64)> = events
+            .iter()
+            .map(|event| {
+                let kind = match event {
+                    ReplayEvent::Market(MarketMessage::AggTrade(_)) => "trade",
+                    ReplayEvent::Market(MarketMessage::Depth(_)) => "depth",
+                    ReplayEvent::Market(_) => "market",
+                    ReplayEvent::News(_) => "news",
+                };
+                (kind, event.timestamp_ms())
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![("depth", 1_500), ("trade", 2_000), ("news", 3_000)]
+        );
+        assert!(load_capture("/nonexistent/capture.jsonl", &[], 0, u64::MAX).is_err());
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn csv_and_json_reports_have_one_shape_for_every_set() {
+        let config = config();
+        let events = breakout_events();
+        let base = BacktestParams::from_config(&config);
+        let reports: Vec<BacktestReport> =
+            expand_grid(&base, &parse_grid("big_move_pressure=70,95").expect("grid"))
+                .expect("sets")
+                .iter()
+                .enumerate()
+                .map(|(set, params)| run_backtest(&config, params, &events, set).expect("run"))
+                .collect();
+
+        let csv = reports_to_csv(&reports);
+        let rows: Vec<Vec<&str>> = csv.lines().map(|l| l.split(',').collect()).collect();
+        let columns = 1 + PARAM_KEYS.len() + 2 + HORIZONS.len() * 3 + 2;
+        assert_eq!(rows[0].len(), columns);
+        assert_eq!(
+            rows[0][columns - 3..],
+            ["avg_return_4h", "avg_mfe_pct", "avg_mae_pct"]
+        );
+        assert_eq!(rows.len(), 1 + reports.len() * SIGNAL_TYPES.len());
+        assert!(rows.iter().all(|row| row.len() == columns));
+        let signal_col = 1 + PARAM_KEYS.len();
+        let big_moves: Vec<(&str, &str, &str)> = rows[1..]
+            .iter()
+            .filter(|row| row[signal_col] == "big_move")
+            .map(|row| (row[0], row[signal_col + 1], row[signal_col + 2]))
+            .collect();
+        assert_eq!(big_moves, vec![("0", "1", "1"), ("1", "0", "0")]);
+
+        let json = serde_json::to_value(&reports).expect("json");
+        for (set, report) in json.as_array().expect("array").iter().enumerate() {
+            assert_eq!(report["set"], set);
+            let params = report["params"].as_object().expect("params");
+            assert!(params.contains_key("spike_pct") && params.contains_key("corr_weights"));
+            let counts = report["signal_counts"].as_object().expect("counts");
+            assert_eq!(
+                counts.keys().collect::<Vec<_>>(),
+                SIGNAL_TYPES.iter().collect::<Vec<_>>()
+            );
+        }
+        let outcome = &json[0]["outcomes"][0];
+        assert_eq!(outcome["signal_type"], "big_move");
+        assert_eq!(outcome["symbol"], "*");
+        assert_eq!(outcome["horizons"][4]["horizon"], "4h");
+        assert_eq!(outcome["horizons"][4]["hit_rate_pct"], 100.0);
+        assert_eq!(json[1]["outcomes"].as_array().map(Vec::len), Some(0));
+    }
+}
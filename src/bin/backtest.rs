@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use feeder_service::backtest::{
+    BacktestParams, expand_grid, load_agg_trades, load_capture, parse_grid, reports_to_csv,
+    run_backtest, sort_events,
+};
+use feeder_service::config::{Config, LogConfig};
+use feeder_service::logging;
+
+fn env_u64(key: &str) -> Option<u64> {
+    std::env::var(key).ok().and_then(|v| v.trim().parse().ok())
+}
+
+fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+    logging::init(&LogConfig::load());
+
+    let mut config = Config::load();
+    if let Ok(raw) = std::env::var("BACKTEST_SYMBOLS") {
+        config.symbols = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(Config::load_symbol)
+            .collect();
+    }
+    if config.symbols.is_empty() {
+        return Err(anyhow!(
+            "no symbols configured (SYMBOLS or BACKTEST_SYMBOLS)"
+        ));
+    }
+    let symbols: Vec<String> = config.symbols.iter().map(|s| s.symbol.clone()).collect();
+
+    let from_ms = env_u64("BACKTEST_FROM_MS").unwrap_or(0);
+    let to_ms = env_u64("BACKTEST_TO_MS").unwrap_or(u64::MAX);
+    let trades_db = std::env::var("BACKTEST_TRADES_DB").unwrap_or_else(|_| "trades.db".to_string());
+    let output =
+        std::env::var("BACKTEST_OUTPUT").unwrap_or_else(|_| "backtest_report.csv".to_string());
+
+    let mut events = Vec::new();
+    if Path::new(&trades_db).exists() {
+        let trades = load_agg_trades(&trades_db, &symbols, from_ms, to_ms)?;
+        println!("[backtest] {} agg trades from {}", trades.len(), trades_db);
+        events.extend(trades);
+    } else {
+        println!("[backtest] trades db {} not found, skipping", trades_db);
+    }
+    for path in std::env::var("BACKTEST_CAPTURE")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        let captured = load_capture(path, &symbols, from_ms, to_ms)?;
+        println!(
+            "[backtest] {} captured events from {}",
+            captured.len(),
+            path
+        );
+        events.extend(captured);
+    }
+    if events.is_empty() {
+        return Err(anyhow!("no historical events to replay"));
+    }
+    sort_events(&mut events);
+
+    let grid = parse_grid(&std::env::var("BACKTEST_GRID").unwrap_or_default())
+        .map_err(|err| anyhow!("BACKTEST_GRID: {err}"))?;
+    let sets = expand_grid(&BacktestParams::from_config(&config), &grid)
+        .map_err(|err| anyhow!("BACKTEST_GRID: {err}"))?;
+    println!(
+        "[backtest] replaying {} events for {} symbols across {} parameter sets",
+        events.len(),
+        symbols.len(),
+        sets.len()
+    );
+
+    let mut reports = Vec::with_capacity(sets.len());
+    for (index, params) in sets.iter().enumerate() {
+        let report = run_backtest(&config, params, &events, index)?;
+        let counts: Vec<String> = report
+            .signal_counts
+            .iter()
+            .map(|(signal_type, count)| format!("{signal_type}={count}"))
+            .collect();
+        println!("[backtest] set {} {}", index, counts.join(" "));
+        reports.push(report);
+    }
+
+    let body = if output.ends_with(".json") {
+        serde_json::to_string_pretty(&reports)?
+    } else {
+        reports_to_csv(&reports)
+    };
+    std::fs::write(&output, body).with_context(|| format!("write {output}"))?;
+    println!("[backtest] report written to {}", output);
+    Ok(())
+}
@@ -1,25 +1,70 @@
-use feeder_service::binance::*;
-use feeder_service::binance_depth::*;
-use feeder_service::config::Config;
+use feeder_service::breadth::{BreadthTracker, format_breadth_log};
+use feeder_service::config::{Config, LogConfig};
+use feeder_service::dispatch::{StreamMessage, dispatch_message};
+use feeder_service::logging::{self, LogContext, log_filter_route};
+use feeder_service::outcomes::outcomes_route;
 use feeder_service::refactor::AppState;
+use feeder_service::rules::spawn_rule_reload;
+use feeder_service::shard::MarketMessage;
+use feeder_service::shutdown::{
+    Shutdown, ShutdownCause, ShutdownReport, format_shutdown_log, wait_for_signal,
+};
+use feeder_service::snapshot::{StateStore, load_state, spawn_state_writer};
+use feeder_service::symbols::init_registry;
+use feeder_service::universe::{
+    MINI_TICKER_STREAM, StreamSet, UniverseRanker, format_universe_log, subscription_frame,
+};
 use feeder_service::ws_helpers::*;
-use futures_util::StreamExt;
+use futures_util::{SinkExt, StreamExt};
 use local_ip_address::local_ip;
-use std::sync::{Arc, Mutex};
-use tokio::sync::broadcast;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, broadcast};
+use tokio::time::timeout_at;
 use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
 use warp::Filter;
 
 #[tokio::main]
 async fn main() {
     dotenv::dotenv().ok();
+    let log_config = LogConfig::load();
+    logging::init(&log_config);
 
-    let config = Config::load();
+    let mut config = Config::load();
+    let registry = init_registry(&mut config).await;
     if config.symbols.is_empty() {
         return;
     }
 
-    let app_state = Arc::new(Mutex::new(AppState::new(config.clone())));
+    let mut app_state = AppState::new(config.clone());
+    let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+    if let Some(snapshot) = load_state(&config, now_ms) {
+        app_state.restore_state(snapshot.symbols, now_ms);
+    }
+    let outcome_store = app_state.outcome_store();
+    if let Some(rules) = app_state.alert_rules() {
+        spawn_rule_reload(config.rules.clone(), rules);
+    }
+    let app_state = Arc::new(Mutex::new(app_state));
+
+    let state_store = config.state.enabled.then(|| {
+        let store = StateStore::new(&config.state.path);
+        spawn_state_writer(store.clone(), config.state.interval_secs);
+        let (exporter, app_state) = (store.clone(), app_state.clone());
+        let period = Duration::from_secs(config.state.interval_secs);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            loop {
+                ticker.tick().await;
+                let symbols = app_state.lock().await.export_state();
+                exporter.update_symbols(0, symbols);
+            }
+        });
+        store
+    });
 
     let symbols: Vec<String> = config
         .symbols
@@ -29,12 +74,16 @@ async fn main() {
 
     let (tx, _rx) = broadcast::channel(config.broadcast_capacity);
 
-    // Spawn Warp server for websocket clients
+    let shutdown = Shutdown::new();
+
+    // Spawn Warp server for websocket clients and the outcome stats API
     let ws_route = warp::path("aggTrade").and(warp::ws()).map({
         let tx = tx.clone();
+        let shutdown = shutdown.clone();
         move |ws: warp::ws::Ws| {
             let tx_inner = tx.clone();
-            ws.on_upgrade(move |socket| handle_client(socket, tx_inner))
+            let shutdown = shutdown.clone();
+            ws.on_upgrade(move |socket| handle_client_with_shutdown(socket, tx_inner, shutdown))
         }
     });
 
@@ -42,35 +91,84 @@ async fn main() {
         .map(|ip| ip.to_string())
         .unwrap_or_else(|_| "127.0.0.1".to_string());
 
-    println!(
+    log::info!(
+        target: "server",
         "WebSocket server running on ws://{}:{}/aggTrade",
         ip_display, config.port
     );
 
-    println!(
+    log::info!(
+        target: "config",
         "Depth filters => min_qty: {}, min_notional: {}, min_pressure: {}",
         config.big_depth_min_qty, config.big_depth_min_notional, config.big_depth_min_pressure_pct
     );
 
-    tokio::spawn(warp::serve(ws_route).run(([0, 0, 0, 0], config.port)));
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", config.port))
+        .await
+        .expect("Failed to bind websocket server port");
+    let server = tokio::spawn({
+        let shutdown = shutdown.clone();
+        warp::serve(
+            ws_route
+                .or(outcomes_route(outcome_store))
+                .or(log_filter_route(log_config.admin_token.clone())),
+        )
+        .incoming(listener)
+        .graceful(async move { shutdown.wait().await })
+        .run()
+    });
 
     // Build Binance streams: aggTrade for each symbol + diff depth streams
-    let mut streams: Vec<String> = symbols.iter().map(|s| format!("{}@aggTrade", s)).collect();
-    streams.extend(build_diff_depth_streams(&symbols, 100));
+    let stream_set = StreamSet {
+        depth: true,
+        ..StreamSet::default()
+    };
+    let mut streams = stream_set.streams_for_all(&symbols);
+
+    let mut universe = config.universe.enabled.then(|| {
+        let ranker = UniverseRanker::new(&config.universe, &symbols);
+        match registry.as_ref() {
+            Some(registry) => ranker.with_registry(registry),
+            None => ranker,
+        }
+    });
+    let mut breadth = config
+        .breadth
+        .enabled
+        .then(|| BreadthTracker::new(&config.breadth));
+    if universe.is_some() || breadth.is_some() {
+        streams.push(MINI_TICKER_STREAM.to_string());
+    }
 
     let url = format!(
         "wss://data-stream.binance.vision/stream?streams={}",
         streams.join("/")
     );
-    println!("Connecting to Binance: {}", url);
+    log::info!(target: "stream", "Connecting to Binance: {}", url);
 
     let (ws_stream, _) = connect_async(&url)
         .await
         .expect("Failed to connect to Binance");
-    let mut read = ws_stream;
+    let (mut write, mut read) = ws_stream.split();
+    let mut next_request_id: u64 = 1;
 
     // Main loop: read messages from Binance websocket
-    while let Some(msg) = read.next().await {
+    let signal = wait_for_signal();
+    tokio::pin!(signal);
+    let cause = loop {
+        let msg = tokio::select! {
+            msg = read.next() => match msg {
+                Some(msg) => msg,
+                None => {
+                    log::warn!(target: "shutdown", "upstream stream ended");
+                    break ShutdownCause::UpstreamClosed;
+                }
+            },
+            name = &mut signal => {
+                log::info!(target: "shutdown", "{name} received, draining");
+                break ShutdownCause::Signal(name);
+            }
+        };
         if let Ok(msg) = msg {
             if !msg.is_text() {
                 continue;
@@ -81,30 +179,141 @@ async fn main() {
                 Err(_) => continue,
             };
 
-            // 1) aggTrade messages
-            if let Some(agg) = parse_agg_trade(payload) {
-                let mut state = app_state.lock().unwrap();
-                state.process_agg_trade(&agg, &tx).await;
-                continue;
-            }
+            match dispatch_message(payload) {
+                // 1) aggTrade messages
+                StreamMessage::Market(MarketMessage::AggTrade(agg)) => {
+                    let mut state = app_state.lock().await;
+                    let log_context = LogContext::market(&agg.s, "agg_trade");
+                    logging::with_context(log_context, state.process_agg_trade(&agg, &tx)).await;
+                    continue;
+                }
+                // 2) depth updates
+                StreamMessage::Market(MarketMessage::Depth(depth)) => {
+                    let mut state = app_state.lock().await;
+                    let log_context = LogContext::market(&depth.symbol, "depth");
+                    logging::with_context(log_context, state.process_depth_update(&depth, &tx))
+                        .await;
+                    continue;
+                }
+                // 3) all-market tickers driving market breadth and the dynamic symbol universe
+                StreamMessage::MiniTickers(tickers) => {
+                    let now_ms = tickers.iter().map(|t| t.event_time).max().unwrap_or(0);
+                    if let Some(tracker) = breadth.as_mut() {
+                        tracker.observe(&tickers);
+                        if let Some(snapshot) = tracker.snapshot_if_due(now_ms) {
+                            let msg = format_breadth_log(&snapshot);
+                            log::info!(target: "breadth", "{msg}");
+                            let _ = tx.send(msg);
+                            let _ = tx.send(snapshot.to_event_json());
+                        }
+                    }
+                    let Some(ranker) = universe.as_mut() else {
+                        continue;
+                    };
+                    ranker.observe(&tickers);
+                    let Some(change) = ranker.rebalance_if_due(now_ms) else {
+                        continue;
+                    };
+                    if change.is_empty() {
+                        continue;
+                    }
 
-            // 2) depth updates
-            if let Some(depth) = parse_depth_update(payload) {
-                let mut state = app_state.lock().unwrap();
-                state.process_depth_update(&depth, &tx).await;
-                continue;
+                    let mut state = app_state.lock().await;
+                    for symbol in &change.added {
+                        let mut cfg = Config::load_symbol(symbol);
+                        if let Some(info) = registry.as_ref().and_then(|r| r.get(symbol)) {
+                            cfg.precision.tick_size = cfg.precision.tick_size.or(info.tick_size);
+                            cfg.precision.step_size = cfg.precision.step_size.or(info.step_size);
+                        }
+                        state.add_symbol(cfg);
+                    }
+                    for (method, symbols) in [
+                        ("SUBSCRIBE", &change.added),
+                        ("UNSUBSCRIBE", &change.removed),
+                    ] {
+                        if symbols.is_empty() {
+                            continue;
+                        }
+                        let frame = subscription_frame(
+                            method,
+                            &stream_set.streams_for_all(symbols),
+                            next_request_id,
+                        );
+                        next_request_id += 1;
+                        if write.send(Message::Text(frame)).await.is_err() {
+                            log::warn!(target: "universe", "failed to send {method} request");
+                        }
+                    }
+                    for symbol in &change.removed {
+                        state.remove_symbol(symbol);
+                    }
+
+                    let msg = format_universe_log(&change, ranker.members().count());
+                    log::info!(target: "universe", "{msg}");
+                    let _ = tx.send(msg);
+                    continue;
+                }
+                StreamMessage::Invalid(err) => {
+                    log::warn!(target: "stream", rate_limited = true; "invalid JSON frame: {err}");
+                    continue;
+                }
+                _ => {}
             }
 
-            // Unknown / unhandled stream messages (optional logging controlled by env)
-            if std::env::var_os("LOG_UNKNOWN_STREAM_MESSAGES").is_some() {
+            // Unknown / unhandled stream messages, visible with `LOG_FILTER=stream=debug`
+            if log::log_enabled!(target: "stream", log::Level::Debug) {
                 let snippet: String = payload.chars().take(180).collect();
                 let suffix = if payload.chars().count() > 180 {
                     "..."
                 } else {
                     ""
                 };
-                eprintln!("[stream] unhandled text message: '{}{}'", snippet, suffix);
+                log::debug!(target: "stream", "unhandled text message: '{}{}'", snippet, suffix);
             }
         }
+    };
+
+    let deadline =
+        (Instant::now() + Duration::from_secs(config.shutdown.drain_timeout_secs)).into();
+    let mut report = ShutdownReport::new(cause);
+    if cause != ShutdownCause::UpstreamClosed {
+        let close = Message::Close(Some(CloseFrame {
+            code: CloseCode::Normal,
+            reason: "feeder shutting down".into(),
+        }));
+        if timeout_at(deadline, write.send(close)).await.is_err() {
+            report.timed_out.push("upstream");
+        }
+    }
+    let drain = async {
+        app_state
+            .lock()
+            .await
+            .drain_notifications(&tx, deadline)
+            .await
+    };
+    match timeout_at(deadline, drain).await {
+        Ok(undelivered) => report.undelivered = undelivered,
+        Err(_) => report.timed_out.push("notifier"),
     }
+    shutdown.trigger();
+    if timeout_at(deadline, server).await.is_err() {
+        report.timed_out.push("server");
+    }
+
+    if let Some(store) = state_store {
+        store.update_symbols(0, app_state.lock().await.export_state());
+        match store
+            .save_async(chrono::Utc::now().timestamp_millis() as u64)
+            .await
+        {
+            Ok(count) => {
+                log::info!(target: "state", "saved {} symbols to {}", count, config.state.path)
+            }
+            Err(err) => log::error!(target: "state", "snapshot failed: {err:#}"),
+        }
+    }
+
+    log::info!(target: "shutdown", "{}", format_shutdown_log(&report));
+    std::process::exit(report.exit_code());
 }
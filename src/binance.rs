@@ -1,14 +1,15 @@
 // File: src/binance.rs
-use crate::config::SymbolConfig;
+use crate::decimal::Decimal;
 use crate::json_helpers::parse_combined_data;
+use crate::logging;
 use chrono::Utc;
 use tokio::sync::broadcast;
 
 #[derive(Debug, serde::Deserialize)]
 pub struct AggTrade {
     pub s: String,
-    pub p: String,
-    pub q: String,
+    pub p: Decimal,
+    pub q: Decimal,
     #[serde(rename = "T")]
     pub t: u64,
     pub m: bool,
@@ -34,25 +35,25 @@ pub async fn log_and_broadcast(
     tx: &broadcast::Sender<String>,
     agg: &AggTrade,
     spike: f64,
-    cfg: &SymbolConfig,
+    big_trade_qty: f64,
+    spike_pct: f64,
 ) {
-    let price: f64 = agg.p.parse().unwrap_or(0.0);
-    let qty: f64 = agg.q.parse().unwrap_or(0.0);
+    let qty = agg.q.to_f64();
 
-    if qty >= cfg.big_trade_qty || spike >= cfg.spike_pct {
+    if qty >= big_trade_qty || spike >= spike_pct {
         let delay_ms = compute_delay_ms(Utc::now().timestamp_millis(), agg.t);
 
         let log_msg = format!(
-            "[AGG_TRADE] {} - Price: {:.2}, Qty: {:.4}, Spike: {:.4}%, BuyerMaker: {}, Delay: {} ms",
+            "[AGG_TRADE] {} - Price: {}, Qty: {}, Spike: {:.4}%, BuyerMaker: {}, Delay: {} ms",
             agg.s.to_uppercase(),
-            price,
-            qty,
+            agg.p,
+            agg.q,
             spike,
             agg.m,
             delay_ms
         );
 
-        println!("{}", log_msg);
+        logging::signal("agg_trade", &log_msg);
         let _ = tx.send(log_msg);
     }
 }
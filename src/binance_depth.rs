@@ -1,14 +1,15 @@
 // File: src/binance_depth.rs
+use crate::decimal::Decimal;
 use crate::json_helpers::parse_combined_data;
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct DepthUpdate {
     #[serde(rename = "s")]
     pub symbol: String,
-    #[serde(rename = "b")]
-    pub bids: Vec<[String; 2]>,
-    #[serde(rename = "a")]
-    pub asks: Vec<[String; 2]>,
+    #[serde(rename = "b", deserialize_with = "deserialize_levels")]
+    pub bids: Vec<[Decimal; 2]>,
+    #[serde(rename = "a", deserialize_with = "deserialize_levels")]
+    pub asks: Vec<[Decimal; 2]>,
     #[serde(rename = "E")]
     pub event_time: u64,
     #[serde(rename = "U")]
@@ -22,15 +23,30 @@ pub struct ParsedDepthLevel {
     pub price: f64,
     pub qty: f64,
     pub notional: f64,
+    /// Price and quantity exactly as sent, used for display.
+    pub raw: [Decimal; 2],
+}
+
+/// Levels whose price or quantity is not a plain decimal are dropped rather than failing the
+/// whole update.
+fn deserialize_levels<'de, D>(deserializer: D) -> Result<Vec<[Decimal; 2]>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw: Vec<[String; 2]> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(raw
+        .iter()
+        .filter_map(|[price, qty]| Some([price.parse().ok()?, qty.parse().ok()?]))
+        .collect())
 }
 
 pub fn parse_depth_update(msg: &str) -> Option<DepthUpdate> {
     parse_combined_data(msg)
 }
 
-pub fn parse_depth_level(level: &[String; 2]) -> Option<ParsedDepthLevel> {
-    let price = level[0].parse::<f64>().ok()?;
-    let qty = level[1].parse::<f64>().ok()?;
+pub fn parse_depth_level(level: &[Decimal; 2]) -> Option<ParsedDepthLevel> {
+    let price = level[0].to_f64();
+    let qty = level[1].to_f64();
 
     if !price.is_finite() || !qty.is_finite() || price <= 0.0 || qty <= 0.0 {
         return None;
@@ -40,11 +56,12 @@ pub fn parse_depth_level(level: &[String; 2]) -> Option<ParsedDepthLevel> {
         price,
         qty,
         notional: price * qty,
+        raw: *level,
     })
 }
 
 pub fn collect_big_levels(
-    levels: &[[String; 2]],
+    levels: &[[Decimal; 2]],
     min_qty: f64,
     max_matches: usize,
 ) -> Vec<ParsedDepthLevel> {
@@ -86,7 +103,7 @@ pub fn format_depth_levels(levels: &[ParsedDepthLevel]) -> String {
 
     levels
         .iter()
-        .map(|level| format!("{:.2} x {:.4}", level.price, level.qty))
+        .map(|level| format!("{} x {}", level.raw[0], level.raw[1]))
         .collect::<Vec<_>>()
         .join(",")
 }
@@ -1,5 +1,9 @@
 use super::*;
 
+fn level(price: &str, qty: &str) -> [Decimal; 2] {
+    [price.parse().unwrap(), qty.parse().unwrap()]
+}
+
 #[test]
 fn parse_depth_update_from_combined_stream() {
     let msg = r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":1672515782136,"s":"BTCUSDT","U":157,"u":160,"b":[["24100.10","1.20"]],"a":[["24100.20","0.80"]]}}"#;
@@ -9,29 +13,26 @@ fn parse_depth_update_from_combined_stream() {
     assert_eq!(depth.event_time, 1672515782136);
     assert_eq!(depth.first_update_id, 157);
     assert_eq!(depth.final_update_id, 160);
-    assert_eq!(depth.bids[0], ["24100.10".to_string(), "1.20".to_string()]);
-    assert_eq!(depth.asks[0], ["24100.20".to_string(), "0.80".to_string()]);
+    assert_eq!(depth.bids[0], level("24100.10", "1.20"));
+    assert_eq!(depth.asks[0][1].to_string(), "0.80");
 }
 
 #[test]
-fn parse_depth_level_rejects_non_finite_and_non_positive_values() {
-    let invalid_nan = ["NaN".to_string(), "5.0".to_string()];
-    let invalid_inf = ["24100.10".to_string(), "inf".to_string()];
-    let invalid_zero = ["24100.10".to_string(), "0".to_string()];
-    let invalid_negative = ["-1".to_string(), "1.0".to_string()];
-
-    assert!(parse_depth_level(&invalid_nan).is_none());
-    assert!(parse_depth_level(&invalid_inf).is_none());
-    assert!(parse_depth_level(&invalid_zero).is_none());
-    assert!(parse_depth_level(&invalid_negative).is_none());
+fn parse_depth_level_rejects_non_positive_values() {
+    assert!(parse_depth_level(&level("24100.10", "0")).is_none());
+    assert!(parse_depth_level(&level("-1", "1.0")).is_none());
+    assert!(parse_depth_level(&level("0.00000000", "3")).is_none());
+
+    let parsed = parse_depth_level(&level("0.00001234", "1000000")).expect("valid level");
+    assert_eq!(parsed.raw[0].to_string(), "0.00001234");
 }
 
 #[test]
 fn collect_big_levels_orders_by_notional_and_limits_matches() {
     let levels = vec![
-        ["24100.10".to_string(), "1.0".to_string()],
-        ["24100.00".to_string(), "2.0".to_string()],
-        ["24000.00".to_string(), "5.0".to_string()],
+        level("24100.10", "1.0"),
+        level("24100.00", "2.0"),
+        level("24000.00", "5.0"),
     ];
 
     let matches = collect_big_levels(&levels, 1.0, 2);
@@ -44,13 +45,12 @@ fn collect_big_levels_orders_by_notional_and_limits_matches() {
 }
 
 #[test]
-fn collect_big_levels_skips_malformed_levels() {
-    let levels = vec![
-        ["oops".to_string(), "2.0".to_string()],
-        ["24100.00".to_string(), "bad".to_string()],
-        ["24100.50".to_string(), "2.5".to_string()],
-    ];
+fn parse_depth_update_drops_malformed_levels() {
+    let msg = r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":1,"s":"BTCUSDT","U":1,"u":2,"b":[["24100.00","bad"],["1e5","1"],["24100.00","3"]],"a":[]}}"#;
+    let depth = parse_depth_update(msg).expect("depth should parse");
+    assert_eq!(depth.bids, vec![level("24100.00", "3")]);
 
+    let levels = vec![level("24100.00", "0"), level("24100.50", "2.5")];
     let matches = collect_big_levels(&levels, 2.0, 10);
 
     assert_eq!(matches.len(), 1);
@@ -65,6 +65,7 @@ fn is_big_depth_update_requires_any_side_match() {
         price: 24100.10,
         qty: 3.0,
         notional: 72300.3,
+        raw: level("24100.10", "3.0"),
     }];
 
     assert!(!is_big_depth_update(&none, &none));
@@ -96,17 +97,19 @@ fn format_depth_levels_uses_compact_representation() {
             price: 24100.10,
             qty: 12.5,
             notional: 301251.25,
+            raw: level("24100.10", "12.5"),
         },
         ParsedDepthLevel {
             price: 24100.20,
             qty: 10.0,
             notional: 241002.0,
+            raw: level("24100.20", "10.000"),
         },
     ];
 
     assert_eq!(
         format_depth_levels(&levels),
-        "24100.10 x 12.5000,24100.20 x 10.0000"
+        "24100.10 x 12.5,24100.20 x 10.000"
     );
     assert_eq!(format_depth_levels(&[]), "-");
 }
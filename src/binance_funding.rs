@@ -1,3 +1,4 @@
+use crate::decimal::Decimal;
 use crate::json_helpers::parse_combined_data;
 
 #[derive(Debug, serde::Deserialize)]
@@ -7,7 +8,7 @@ pub struct FundingRateUpdate {
     #[serde(rename = "s")]
     pub symbol: String,
     #[serde(rename = "r")]
-    pub funding_rate: String,
+    pub funding_rate: Decimal,
     #[serde(rename = "T")]
     pub next_funding_time: u64,
 }
@@ -16,8 +17,8 @@ pub fn parse_funding_rate_update(msg: &str) -> Option<FundingRateUpdate> {
     parse_combined_data(msg)
 }
 
-pub fn funding_rate_pct(raw_rate: &str) -> Option<f64> {
-    raw_rate.parse::<f64>().ok().map(|v| v * 100.0)
+pub fn funding_rate_pct(rate: &Decimal) -> f64 {
+    rate.to_f64() * 100.0
 }
 
 pub fn is_high_funding_rate(rate_pct: f64, threshold_pct: f64) -> bool {
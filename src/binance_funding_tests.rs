@@ -7,7 +7,7 @@ fn parse_funding_rate_from_combined_stream() {
 
     assert_eq!(funding.symbol, "BTCUSDT");
     assert_eq!(funding.event_time, 1710000000100);
-    assert_eq!(funding.funding_rate, "0.00120000");
+    assert_eq!(funding.funding_rate.to_string(), "0.00120000");
     assert_eq!(funding.next_funding_time, 1710003600000);
 }
 
@@ -20,8 +20,9 @@ fn parse_funding_rate_rejects_other_payload_type() {
 
 #[test]
 fn funding_rate_pct_converts_decimal_to_percent() {
-    assert_eq!(funding_rate_pct("0.001"), Some(0.1));
-    assert_eq!(funding_rate_pct("-0.0025"), Some(-0.25));
+    let rate = |raw: &str| funding_rate_pct(&raw.parse().unwrap());
+    assert_eq!(rate("0.001"), 0.1);
+    assert_eq!(rate("-0.0025"), -0.25);
 }
 
 #[test]
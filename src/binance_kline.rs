@@ -1,3 +1,4 @@
+use crate::decimal::{Decimal, SymbolPrecision};
 use crate::json_helpers::parse_combined_data;
 
 #[derive(Debug, serde::Deserialize, Clone)]
@@ -19,25 +20,25 @@ pub struct Kline {
     #[serde(rename = "i")]
     pub interval: String,
     #[serde(rename = "o")]
-    pub open: String,
+    pub open: Decimal,
     #[serde(rename = "c")]
-    pub close: String,
+    pub close: Decimal,
     #[serde(rename = "h")]
-    pub high: String,
+    pub high: Decimal,
     #[serde(rename = "l")]
-    pub low: String,
+    pub low: Decimal,
     #[serde(rename = "v")]
-    pub volume: String,
+    pub volume: Decimal,
     #[serde(rename = "q")]
-    pub quote_volume: String,
+    pub quote_volume: Decimal,
     #[serde(rename = "n")]
     pub trade_count: u64,
     #[serde(rename = "x")]
     pub is_closed: bool,
     #[serde(rename = "V")]
-    pub taker_buy_base_volume: String,
+    pub taker_buy_base_volume: Decimal,
     #[serde(rename = "Q")]
-    pub taker_buy_quote_volume: String,
+    pub taker_buy_quote_volume: Decimal,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -73,13 +74,13 @@ pub fn build_quant_signal_from_kline(event: &KlineEvent) -> Option<QuantKlineSig
         return None;
     }
 
-    let open = event.kline.open.parse::<f64>().ok()?;
-    let close = event.kline.close.parse::<f64>().ok()?;
-    let high = event.kline.high.parse::<f64>().ok()?;
-    let low = event.kline.low.parse::<f64>().ok()?;
-    let volume = event.kline.volume.parse::<f64>().ok()?;
-    let quote_volume = event.kline.quote_volume.parse::<f64>().ok()?;
-    let taker_buy_quote_volume = event.kline.taker_buy_quote_volume.parse::<f64>().ok()?;
+    let open = event.kline.open.to_f64();
+    let close = event.kline.close.to_f64();
+    let high = event.kline.high.to_f64();
+    let low = event.kline.low.to_f64();
+    let volume = event.kline.volume.to_f64();
+    let quote_volume = event.kline.quote_volume.to_f64();
+    let taker_buy_quote_volume = event.kline.taker_buy_quote_volume.to_f64();
 
     if !open.is_finite()
         || !close.is_finite()
@@ -120,6 +121,33 @@ pub fn build_quant_signal_from_kline(event: &KlineEvent) -> Option<QuantKlineSig
     })
 }
 
+/// `[QUANT4H]` line with O/C/H/L at the symbol's tick size.
+pub fn format_quant_log(signal: &QuantKlineSignal, precision: &SymbolPrecision) -> String {
+    let direction = if signal.return_pct > 0.0 {
+        "BULLISH"
+    } else if signal.return_pct < 0.0 {
+        "BEARISH"
+    } else {
+        "FLAT"
+    };
+    format!(
+        "[QUANT4H] {} {} | window={}..{} | O:{} C:{} H:{} L:{} ret={:+.2}% range={:.2}% taker_buy={:.1}% qvol={:.0} trades={}",
+        signal.symbol.to_uppercase(),
+        direction,
+        signal.interval_start_ms,
+        signal.interval_end_ms,
+        precision.format_price(signal.open),
+        precision.format_price(signal.close),
+        precision.format_price(signal.high),
+        precision.format_price(signal.low),
+        signal.return_pct,
+        signal.range_pct,
+        signal.taker_buy_ratio_pct,
+        signal.quote_volume,
+        signal.trade_count
+    )
+}
+
 #[cfg(test)]
 #[path = "binance_kline_tests.rs"]
 mod tests;
@@ -1,4 +1,7 @@
-use crate::binance_kline::{build_kline_streams, build_quant_signal_from_kline, parse_kline_event};
+use crate::binance_kline::{
+    build_kline_streams, build_quant_signal_from_kline, format_quant_log, parse_kline_event,
+};
+use crate::decimal::SymbolPrecision;
 
 #[test]
 fn parses_combined_kline_payload() {
@@ -39,3 +42,22 @@ fn kline_stream_names_are_lowercased() {
     let streams = build_kline_streams(&["BTCUSDT".into(), "EthUsdt".into()], "4h");
     assert_eq!(streams, vec!["btcusdt@kline_4h", "ethusdt@kline_4h"]);
 }
+
+#[test]
+fn quant_log_prints_sub_cent_prices_at_tick_size() {
+    let payload = r#"{"stream":"1000pepeusdt@kline_4h","data":{"e":"kline","E":1710000000000,"s":"PEPEUSDT","k":{"t":1710000000000,"T":1710014399999,"i":"4h","o":"0.0000081","c":"0.0000089","h":"0.0000092","l":"0.0000079","v":"1000000000","q":"8500.0","n":420,"x":true,"V":"600000000","Q":"5100.0"}}}"#;
+    let signal = build_quant_signal_from_kline(&parse_kline_event(payload).expect("parse"))
+        .expect("closed 4h kline");
+    let precision = SymbolPrecision {
+        tick_size: Some("0.0000001".parse().unwrap()),
+        step_size: None,
+    };
+
+    let line = format_quant_log(&signal, &precision);
+    assert!(
+        line.contains("O:0.0000081 C:0.0000089 H:0.0000092 L:0.0000079"),
+        "{line}"
+    );
+    assert!(line.starts_with("[QUANT4H] PEPEUSDT BULLISH | window=1710000000000..1710014399999"));
+    assert!(!line.contains("0.00 "));
+}
@@ -6,8 +6,8 @@ fn parse_agg_trade_from_combined_stream() {
     let agg = parse_agg_trade(msg).expect("agg trade should parse");
 
     assert_eq!(agg.s, "BTCUSDT");
-    assert_eq!(agg.p, "43000.50");
-    assert_eq!(agg.q, "0.1200");
+    assert_eq!(agg.p.to_string(), "43000.50");
+    assert_eq!(agg.q.to_string(), "0.1200");
     assert_eq!(agg.t, 1710000000010);
     assert!(agg.m);
 }
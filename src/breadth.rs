@@ -0,0 +1,362 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+
+use crate::config::BreadthConfig;
+use crate::universe::{MiniTicker, STALE_TICKER_MS};
+
+/// Rolling windows reported as advance/decline counts, shortest first.
+const WINDOWS: [(&str, u64); 3] = [
+    ("5m", 5 * 60 * 1_000),
+    ("15m", 15 * 60 * 1_000),
+    ("1h", 60 * 60 * 1_000),
+];
+
+/// Minimum spacing of stored closes per symbol; the stream pushes about once a second.
+const SAMPLE_MS: u64 = 10_000;
+
+/// Share of pairs up on the day at or above which the market counts as risk-on
+/// (and at or below `100 - RISK_ON_PCT_UP` as risk-off).
+const RISK_ON_PCT_UP: f64 = 65.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreadthRegime {
+    RiskOn,
+    Mixed,
+    RiskOff,
+}
+
+impl BreadthRegime {
+    pub fn from_pct_up(pct_up: f64) -> Self {
+        if pct_up >= RISK_ON_PCT_UP {
+            Self::RiskOn
+        } else if pct_up <= 100.0 - RISK_ON_PCT_UP {
+            Self::RiskOff
+        } else {
+            Self::Mixed
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RiskOn => "risk_on",
+            Self::Mixed => "mixed",
+            Self::RiskOff => "risk_off",
+        }
+    }
+}
+
+/// Advancers and decliners over one rolling window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BreadthWindow {
+    pub window: &'static str,
+    pub advancers: usize,
+    pub decliners: usize,
+    /// `advancers / decliners`, with zero decliners counted as one.
+    pub ad_ratio: f64,
+}
+
+/// One `market_breadth` event.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MarketBreadth {
+    pub timestamp_ms: u64,
+    pub symbol_count: usize,
+    pub advancers_24h: usize,
+    pub decliners_24h: usize,
+    /// Percent of pairs trading above their price 24h ago.
+    pub pct_up_24h: f64,
+    pub median_return_24h_pct: f64,
+    /// Only windows with enough price history are included.
+    pub windows: Vec<BreadthWindow>,
+    pub dominance_symbol: String,
+    /// Dominance pair's share of total 24h quote volume, in percent.
+    pub dominance_pct: Option<f64>,
+    pub regime: BreadthRegime,
+}
+
+impl MarketBreadth {
+    pub fn window(&self, label: &str) -> Option<&BreadthWindow> {
+        self.windows.iter().find(|w| w.window == label)
+    }
+
+    /// JSON broadcast to websocket clients.
+    pub fn to_event_json(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("event".to_string(), "market_breadth".into());
+        }
+        value.to_string()
+    }
+}
+
+#[derive(Debug, Default)]
+struct TickerState {
+    open: f64,
+    close: f64,
+    quote_volume: f64,
+    event_ms: u64,
+    /// `(event_ms, close)` samples covering the longest window.
+    history: VecDeque<(u64, f64)>,
+}
+
+/// Aggregates all-market mini tickers into periodic breadth snapshots.
+#[derive(Debug)]
+pub struct BreadthTracker {
+    quote_suffix: String,
+    dominance_symbol: String,
+    interval_ms: u64,
+    next_due_ms: Option<u64>,
+    tickers: HashMap<String, TickerState>,
+}
+
+impl BreadthTracker {
+    pub fn new(config: &BreadthConfig) -> Self {
+        Self {
+            quote_suffix: config.quote_asset.to_lowercase(),
+            dominance_symbol: config.dominance_symbol.to_lowercase(),
+            interval_ms: config.interval_secs.max(1) * 1_000,
+            next_due_ms: None,
+            tickers: HashMap::new(),
+        }
+    }
+
+    pub fn observe(&mut self, tickers: &[MiniTicker]) {
+        let max_window_ms = WINDOWS[WINDOWS.len() - 1].1;
+        for ticker in tickers {
+            let symbol = ticker.symbol.to_lowercase();
+            if symbol.len() <= self.quote_suffix.len() || !symbol.ends_with(&self.quote_suffix) {
+                continue;
+            }
+            let close = ticker.close.to_f64();
+            if close <= 0.0 {
+                continue;
+            }
+
+            let state = self.tickers.entry(symbol).or_default();
+            state.open = ticker.open.to_f64();
+            state.close = close;
+            state.quote_volume = ticker.quote_volume.to_f64();
+            state.event_ms = ticker.event_time;
+
+            let due = state
+                .history
+                .back()
+                .is_none_or(|(ts, _)| ticker.event_time >= ts + SAMPLE_MS);
+            if due {
+                state.history.push_back((ticker.event_time, close));
+            }
+            // Keep one sample at or before the start of the longest window as its baseline.
+            let cutoff = ticker.event_time.saturating_sub(max_window_ms);
+            while state.history.get(1).is_some_and(|(ts, _)| *ts <= cutoff) {
+                state.history.pop_front();
+            }
+        }
+    }
+
+    pub fn snapshot(&self) -> Option<MarketBreadth> {
+        let now_ms = self.tickers.values().map(|t| t.event_ms).max()?;
+        let active: Vec<(&String, &TickerState)> = self
+            .tickers
+            .iter()
+            .filter(|(_, t)| now_ms.saturating_sub(t.event_ms) <= STALE_TICKER_MS)
+            .collect();
+
+        let mut returns: Vec<f64> = active
+            .iter()
+            .filter(|(_, t)| t.open > 0.0)
+            .map(|(_, t)| (t.close - t.open) / t.open * 100.0)
+            .collect();
+        if returns.is_empty() {
+            return None;
+        }
+        let advancers_24h = returns.iter().filter(|r| **r > 0.0).count();
+        let decliners_24h = returns.iter().filter(|r| **r < 0.0).count();
+        let pct_up_24h = advancers_24h as f64 / returns.len() as f64 * 100.0;
+        returns.sort_by(f64::total_cmp);
+        let mid = returns.len() / 2;
+        let median_return_24h_pct = if returns.len().is_multiple_of(2) {
+            (returns[mid - 1] + returns[mid]) / 2.0
+        } else {
+            returns[mid]
+        };
+
+        let windows = WINDOWS
+            .iter()
+            .filter_map(|(label, window_ms)| window_counts(&active, now_ms, *window_ms, label))
+            .collect();
+
+        let total_volume: f64 = active.iter().map(|(_, t)| t.quote_volume).sum();
+        let dominance_pct = active
+            .iter()
+            .find(|(symbol, _)| **symbol == self.dominance_symbol)
+            .filter(|_| total_volume > 0.0)
+            .map(|(_, t)| t.quote_volume / total_volume * 100.0);
+
+        Some(MarketBreadth {
+            timestamp_ms: now_ms,
+            symbol_count: returns.len(),
+            advancers_24h,
+            decliners_24h,
+            pct_up_24h,
+            median_return_24h_pct,
+            windows,
+            dominance_symbol: self.dominance_symbol.clone(),
+            dominance_pct,
+            regime: BreadthRegime::from_pct_up(pct_up_24h),
+        })
+    }
+
+    /// Returns a snapshot once every interval; the first one is due an interval after the
+    /// first ticker batch.
+    pub fn snapshot_if_due(&mut self, now_ms: u64) -> Option<MarketBreadth> {
+        let due_ms = *self.next_due_ms.get_or_insert(now_ms + self.interval_ms);
+        if now_ms < due_ms {
+            return None;
+        }
+        self.next_due_ms = Some(now_ms + self.interval_ms);
+        self.snapshot()
+    }
+}
+
+fn window_counts(
+    active: &[(&String, &TickerState)],
+    now_ms: u64,
+    window_ms: u64,
+    label: &'static str,
+) -> Option<BreadthWindow> {
+    let start_ms = now_ms.checked_sub(window_ms)?;
+    let mut advancers = 0;
+    let mut decliners = 0;
+    let mut covered = 0;
+    for (_, state) in active {
+        let Some((_, baseline)) = state.history.iter().rev().find(|(ts, _)| *ts <= start_ms) else {
+            continue;
+        };
+        covered += 1;
+        if state.close > *baseline {
+            advancers += 1;
+        } else if state.close < *baseline {
+            decliners += 1;
+        }
+    }
+    (covered > 0).then(|| BreadthWindow {
+        window: label,
+        advancers,
+        decliners,
+        ad_ratio: advancers as f64 / decliners.max(1) as f64,
+    })
+}
+
+pub fn format_breadth_log(breadth: &MarketBreadth) -> String {
+    let windows: Vec<String> = breadth
+        .windows
+        .iter()
+        .map(|w| format!("{}={:.2}", w.window, w.ad_ratio))
+        .collect();
+    let dominance = breadth
+        .dominance_pct
+        .map(|pct| format!("{:.1}%", pct))
+        .unwrap_or_else(|| "n/a".to_string());
+    format!(
+        "[BREADTH] up={:.1}% ({}/{}) median={:+.2}% A/D {} {}_share={} regime={}",
+        breadth.pct_up_24h,
+        breadth.advancers_24h,
+        breadth.symbol_count,
+        breadth.median_return_24h_pct,
+        if windows.is_empty() {
+            "warming".to_string()
+        } else {
+            windows.join(" ")
+        },
+        breadth.dominance_symbol.to_uppercase(),
+        dominance,
+        breadth.regime.as_str(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> BreadthConfig {
+        BreadthConfig {
+            enabled: true,
+            interval_secs: 60,
+            quote_asset: "USDT".to_string(),
+            dominance_symbol: "btcusdt".to_string(),
+        }
+    }
+
+    fn ticker(symbol: &str, open: &str, close: &str, quote_volume: &str, ts: u64) -> MiniTicker {
+        MiniTicker {
+            symbol: symbol.to_string(),
+            event_time: ts,
+            close: close.parse().unwrap(),
+            open: open.parse().unwrap(),
+            quote_volume: quote_volume.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn computes_daily_breadth_median_and_dominance() {
+        let mut tracker = BreadthTracker::new(&config());
+        tracker.observe(&[
+            ticker("BTCUSDT", "100", "110", "600", 1_000),
+            ticker("ETHUSDT", "100", "95", "300", 1_000),
+            ticker("SOLUSDT", "100", "102", "100", 1_000),
+            ticker("ETHBTC", "1", "2", "999999", 1_000),
+        ]);
+
+        let breadth = tracker.snapshot().expect("snapshot");
+        assert_eq!(breadth.symbol_count, 3);
+        assert_eq!((breadth.advancers_24h, breadth.decliners_24h), (2, 1));
+        assert!((breadth.pct_up_24h - 66.666).abs() < 0.01);
+        assert!((breadth.median_return_24h_pct - 2.0).abs() < 1e-9);
+        assert!((breadth.dominance_pct.unwrap() - 60.0).abs() < 1e-9);
+        assert_eq!(breadth.regime, BreadthRegime::RiskOn);
+        // No history yet, so no rolling windows.
+        assert!(breadth.windows.is_empty());
+        assert!(
+            breadth
+                .to_event_json()
+                .contains("\"event\":\"market_breadth\"")
+        );
+    }
+
+    #[test]
+    fn rolling_windows_compare_against_window_start() {
+        let mut tracker = BreadthTracker::new(&config());
+        let t0 = 10_000_000;
+        tracker.observe(&[
+            ticker("BTCUSDT", "100", "100", "1", t0),
+            ticker("ETHUSDT", "100", "100", "1", t0),
+        ]);
+        let t1 = t0 + 6 * 60 * 1_000;
+        tracker.observe(&[
+            ticker("BTCUSDT", "100", "105", "1", t1),
+            ticker("ETHUSDT", "100", "97", "1", t1),
+        ]);
+        let t2 = t1 + 60 * 1_000;
+        tracker.observe(&[
+            ticker("BTCUSDT", "100", "106", "1", t2),
+            ticker("ETHUSDT", "100", "98", "1", t2),
+        ]);
+
+        let breadth = tracker.snapshot().expect("snapshot");
+        let five = breadth.window("5m").expect("5m window covered");
+        assert_eq!((five.advancers, five.decliners), (1, 1));
+        assert_eq!(five.ad_ratio, 1.0);
+        assert!(breadth.window("15m").is_none());
+    }
+
+    #[test]
+    fn snapshot_is_emitted_once_per_interval() {
+        let mut tracker = BreadthTracker::new(&config());
+        tracker.observe(&[ticker("BTCUSDT", "100", "101", "1", 0)]);
+        assert!(tracker.snapshot_if_due(0).is_none());
+        assert!(tracker.snapshot_if_due(59_999).is_none());
+        assert!(tracker.snapshot_if_due(60_000).is_some());
+        assert!(tracker.snapshot_if_due(61_000).is_none());
+    }
+}
@@ -1,13 +1,30 @@
 use std::env;
 
-#[derive(Debug, Clone)]
+use crate::conviction::ConvictionWeights;
+use crate::decimal::{Decimal, SymbolPrecision};
+use crate::notify::queue::OverflowPolicy;
+use crate::paper::PaperStrategy;
+use crate::refactor::big_move_detector::BigMoveProfile;
+use crate::spike_window::parse_window_list;
+use crate::symbols::RegistryMode;
+use crate::threshold::BigTradeRule;
+
+#[derive(Debug, Clone, Default)]
 pub struct SymbolConfig {
     pub symbol: String,
     pub big_trade_qty: f64,
     pub spike_pct: f64,
+    /// Cluster notional (quote) at which same-side aggressive trades are reported as a whale.
+    pub whale_notional: f64,
+    /// How the big-trade quantity threshold is derived; `FixedQty` uses `big_trade_qty`.
+    pub big_trade_rule: BigTradeRule,
+    /// Tick/step sizes used to format computed prices and quantities for this symbol.
+    pub precision: SymbolPrecision,
+    /// One `BigMoveDetector` runs per profile.
+    pub big_move_profiles: Vec<BigMoveProfile>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Config {
     pub symbols: Vec<SymbolConfig>,
     pub port: u16,
@@ -15,6 +32,8 @@ pub struct Config {
     pub big_depth_min_qty: f64,
     pub big_depth_min_notional: f64,
     pub big_depth_min_pressure_pct: f64,
+    /// Time windows (seconds) over which high/low price spikes are measured.
+    pub spike_windows_secs: Vec<u64>,
     /// When `true`, depth streams are not subscribed and depth messages are not processed.
     pub disable_depth_stream: bool,
     pub enable_funding_rate: bool,
@@ -27,9 +46,26 @@ pub struct Config {
     pub news_streams: Vec<String>,
     pub news: NewsConfig,
     pub telegram: TelegramConfig,
+    pub digest: DigestConfig,
+    pub flow: FlowConfig,
+    pub whale: WhaleConfig,
+    pub threshold: ThresholdConfig,
+    pub delivery: DeliveryConfig,
+    pub sharding: ShardConfig,
+    pub registry: RegistryConfig,
+    pub universe: UniverseConfig,
+    pub breadth: BreadthConfig,
+    pub cross_asset: CrossAssetConfig,
+    pub volatility: VolatilityConfig,
+    pub outcomes: OutcomeConfig,
+    pub paper: PaperConfig,
+    pub conviction: ConvictionConfig,
+    pub rules: RuleConfig,
+    pub state: StateConfig,
+    pub shutdown: ShutdownConfig,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct NewsConfig {
     pub enabled: bool,
     pub db_path: String,
@@ -73,33 +109,274 @@ impl TelegramConfig {
     }
 }
 
-impl Config {
+/// Alert batching: quiet symbols alert immediately, stormy symbols roll up into digests.
+#[derive(Debug, Clone, Default)]
+pub struct DigestConfig {
+    pub enabled: bool,
+    /// Length of the per-symbol batching window.
+    pub window_secs: u64,
+    /// Signals per symbol per window delivered immediately before batching kicks in.
+    pub immediate_limit: usize,
+    /// Escalate once a symbol has been batching continuously for this long (0 disables).
+    pub escalation_after_secs: u64,
+    /// Secondary Telegram chat used for escalations.
+    pub escalation_chat_id: Option<String>,
+    pub max_headlines: usize,
+}
+
+/// Rolling taker trade-flow metrics (CVD, buy/sell notional, imbalance).
+#[derive(Debug, Clone, Default)]
+pub struct FlowConfig {
+    pub enabled: bool,
+    pub emit_interval_secs: u64,
+    /// Absolute taker imbalance (%) that flags a window as strongly one-sided.
+    pub imbalance_alert_pct: f64,
+    /// Minimum window notional before an imbalance is considered meaningful.
+    pub imbalance_min_notional: f64,
+}
+
+/// Whale trade clustering and iceberg detection.
+#[derive(Debug, Clone, Default)]
+pub struct WhaleConfig {
+    /// Max time from the first to the last trade of a cluster.
+    pub cluster_window_ms: u64,
+    /// Max price distance (basis points) between consecutive trades of a cluster.
+    pub adjacent_bps: f64,
+    pub iceberg_window_secs: u64,
+    pub iceberg_min_refills: usize,
+    /// Fraction of the level's peak size a refill must restore (and a depletion must remove).
+    pub iceberg_refill_ratio: f64,
+}
+
+/// Rolling trade-size sample used by percentile and volume-multiple big-trade rules.
+#[derive(Debug, Clone, Default)]
+pub struct ThresholdConfig {
+    pub window_secs: u64,
+    /// How often percentile thresholds are recomputed from the window.
+    pub recalc_interval_secs: u64,
+    /// Trades required in the window before adaptive rules replace `big_trade_qty`.
+    pub min_samples: usize,
+}
+
+/// Outbound notification queue between signal dispatch and Telegram.
+#[derive(Debug, Clone, Default)]
+pub struct DeliveryConfig {
+    /// Max queued Telegram messages; 0 delivers inline.
+    pub queue_capacity: usize,
+    pub workers: usize,
+    pub overflow: OverflowPolicy,
+    /// How often queue depth metrics are logged; 0 disables the log line.
+    pub metrics_interval_secs: u64,
+}
+
+/// Per-symbol worker tasks fed by the upstream reader.
+#[derive(Debug, Clone, Default)]
+pub struct ShardConfig {
+    pub shards: usize,
+    /// Bounded queue length per shard and for the cross-symbol merge stage.
+    pub queue_capacity: usize,
+    /// What a full shard queue does with a new message; routing never waits.
+    pub overflow: OverflowPolicy,
+}
+
+/// Symbol metadata from Binance `exchangeInfo`.
+#[derive(Debug, Clone, Default)]
+pub struct RegistryConfig {
+    pub mode: RegistryMode,
+    pub exchange_info_url: String,
+    /// Raw `exchangeInfo` JSON written after each fetch; read directly in offline mode.
+    pub cache_path: String,
+    /// Cache age under which live mode skips the fetch.
+    pub cache_ttl_secs: u64,
+}
+
+/// Symbols added and removed at runtime from 24h quote-volume rankings.
+#[derive(Debug, Clone, Default)]
+pub struct UniverseConfig {
+    pub enabled: bool,
+    /// Rank a pair must reach to be added.
+    pub top_n: usize,
+    /// Rank a dynamic member must fall past before it is removed (`>= top_n`).
+    pub exit_rank: usize,
+    pub quote_asset: String,
+    pub rebalance_interval_secs: u64,
+    /// Ticker collection time before the first ranking.
+    pub warmup_secs: u64,
+    /// Lowercase pairs never added (e.g. stablecoin pairs).
+    pub exclude: Vec<String>,
+}
+
+/// Market-wide breadth computed from the all-market mini ticker stream.
+#[derive(Debug, Clone, Default)]
+pub struct BreadthConfig {
+    pub enabled: bool,
+    /// Seconds between `market_breadth` events.
+    pub interval_secs: u64,
+    /// Only pairs quoted in this asset are counted, so quote volumes are comparable.
+    pub quote_asset: String,
+    /// Pair whose share of total quote volume is reported as dominance.
+    pub dominance_symbol: String,
+}
+
+/// Rolling return correlations and lead-lag against a reference symbol.
+#[derive(Debug, Clone, Default)]
+pub struct CrossAssetConfig {
+    pub enabled: bool,
+    /// Symbol other moves are measured against; must be in the watched set.
+    pub reference_symbol: String,
+    /// Return bar size.
+    pub bar_secs: u64,
+    /// Returns per correlation / beta estimate.
+    pub window_bars: usize,
+    /// Largest lead or lag searched, in bars.
+    pub max_lag_bars: usize,
+    /// Seconds between `correlation_matrix` events.
+    pub matrix_interval_secs: u64,
+    /// Period measured for big-move events, which carry no price window of their own.
+    pub big_move_lookback_secs: u64,
+}
+
+/// Per-symbol realized volatility and regime detection.
+#[derive(Debug, Clone, Default)]
+pub struct VolatilityConfig {
+    pub enabled: bool,
+    /// Size of the OHLC bars built from trades.
+    pub bar_secs: u64,
+    /// Bars in the current-volatility window.
+    pub window_bars: usize,
+    /// Bars retained for the baseline the current window is compared against.
+    pub baseline_bars: usize,
+    /// Scale `spike_pct` and the big-move pressure threshold with the regime.
+    pub scale_thresholds: bool,
+}
+
+/// Forward-return tracking of emitted signals.
+#[derive(Debug, Clone, Default)]
+pub struct OutcomeConfig {
+    pub enabled: bool,
+    /// SQLite file completed outcomes are written to.
+    pub db_path: String,
+    /// Seconds between `[OUTCOME_REPORT]` summaries (0 disables the report).
+    pub report_interval_secs: u64,
+}
+
+/// Simulated trading on tracked signals.
+#[derive(Debug, Clone, Default)]
+pub struct PaperConfig {
+    pub enabled: bool,
+    pub strategies: Vec<PaperStrategy>,
+    /// Taker fee per fill in basis points.
+    pub fee_bps: f64,
+    /// Slippage per fill when the local order book is empty or stale.
+    pub fallback_slippage_bps: f64,
+    /// Seconds between `[PAPER_REPORT]` summaries (0 disables the report).
+    pub report_interval_secs: u64,
+}
+
+/// Per-symbol conviction score over depth, flow, funding, quant and news readings.
+#[derive(Debug, Clone, Default)]
+pub struct ConvictionConfig {
+    pub enabled: bool,
+    pub weights: ConvictionWeights,
+    /// Absolute score levels (0-100) whose crossing is emitted.
+    pub thresholds: Vec<f64>,
+    /// Points below a level before the score drops out of it.
+    pub hysteresis: f64,
+    /// Seconds over which a reading fades to zero.
+    pub decay_secs: u64,
+}
+
+/// User-defined alert rules, see `rules`.
+#[derive(Debug, Clone, Default)]
+pub struct RuleConfig {
+    /// Rule file, one `name: expression` per line.
+    pub path: Option<String>,
+    /// Rules given inline, separated by `;`.
+    pub inline: Option<String>,
+    /// Seconds between checks of the rule file for changes (0 disables reloading).
+    pub reload_secs: u64,
+    /// Minimum seconds between two alerts of one rule for one symbol.
+    pub cooldown_secs: u64,
+}
+
+/// Snapshot of in-memory detector state restored on startup.
+#[derive(Debug, Clone, Default)]
+pub struct StateConfig {
+    pub enabled: bool,
+    /// JSON file the state is written to.
+    pub path: String,
+    /// Seconds between snapshots; one is also written on shutdown.
+    pub interval_secs: u64,
+    /// Last prices and big-move windows older than this are dropped on restore.
+    pub max_age_secs: u64,
+}
+
+/// Graceful shutdown on SIGTERM / Ctrl-C.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownConfig {
+    /// Seconds allowed for draining the pipeline and queued alerts before exiting anyway.
+    pub drain_timeout_secs: u64,
+}
+
+/// Log line format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Levelled logging, loaded on its own before `Config::load` so config warnings are logged.
+#[derive(Debug, Clone, Default)]
+pub struct LogConfig {
+    /// Level directives such as `info,news=debug,notify=warn`.
+    pub filter: String,
+    pub format: LogFormat,
+    /// Call sites that opt in with `rate_limited = true` log once per this many seconds.
+    pub rate_limit_secs: u64,
+    /// Bearer token for `GET`/`PUT /log/filter`; the route is off without one.
+    pub admin_token: Option<String>,
+}
+
+impl LogConfig {
     pub fn load() -> Self {
-        let default_qty = env::var("BIG_TRADE_QTY")
-            .ok()
-            .and_then(|v| v.parse::<f64>().ok())
-            .unwrap_or(20.0);
+        let mut filter = Config::load_optional_string("LOG_FILTER")
+            .or_else(|| Config::load_optional_string("RUST_LOG"))
+            .unwrap_or_else(|| "info".to_string());
+        // Older switch for the unhandled-frame dump, now a debug log on the `stream` target.
+        if env::var_os("LOG_UNKNOWN_STREAM_MESSAGES").is_some() {
+            filter.push_str(",stream=debug");
+        }
+        Self {
+            filter,
+            format: match env::var("LOG_FORMAT").map(|v| v.trim().to_lowercase()) {
+                Ok(v) if v == "json" => LogFormat::Json,
+                _ => LogFormat::Text,
+            },
+            rate_limit_secs: env::var("LOG_RATE_LIMIT_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(10),
+            admin_token: Config::load_optional_string("LOG_ADMIN_TOKEN"),
+        }
+    }
+}
 
-        let default_spike = env::var("SPIKE_PCT")
-            .ok()
-            .and_then(|v| v.parse::<f64>().ok())
-            .unwrap_or(0.4);
+impl RuleConfig {
+    pub fn enabled(&self) -> bool {
+        self.path.is_some() || self.inline.is_some()
+    }
+}
 
+impl Config {
+    pub fn load() -> Self {
         let symbols_str = env::var("SYMBOLS").unwrap_or_else(|_| "btcusdt".to_string());
 
         let symbols: Vec<SymbolConfig> = symbols_str
             .split(',')
             .map(|s| s.trim().to_lowercase())
             .filter(|s| !s.is_empty())
-            .map(|symbol| {
-                let big_trade_qty = Self::load_symbol_env(&symbol, "BIG_TRADE_QTY", default_qty);
-                let spike_pct = Self::load_symbol_env(&symbol, "SPIKE_PCT", default_spike);
-                SymbolConfig {
-                    symbol,
-                    big_trade_qty,
-                    spike_pct,
-                }
-            })
+            .map(|symbol| Self::load_symbol(&symbol))
             .collect();
 
         let port = env::var("PORT")
@@ -127,6 +404,12 @@ impl Config {
             .and_then(|v| v.parse::<f64>().ok())
             .unwrap_or(0.0);
 
+        let spike_windows_secs = env::var("SPIKE_WINDOWS")
+            .ok()
+            .map(|raw| parse_window_list(&raw))
+            .filter(|windows| !windows.is_empty())
+            .unwrap_or_else(|| vec![10, 60, 300]);
+
         let disable_depth_stream = Self::load_bool("DISABLE_DEPTH_STREAM", false);
         let enable_funding_rate = Self::load_bool("ENABLE_FUNDING_RATE", true);
         let funding_rate_alert_pct = env::var("FUNDING_RATE_ALERT_PCT")
@@ -211,6 +494,332 @@ impl Config {
                 .unwrap_or_else(|| "https://api.telegram.org".to_string()),
         };
 
+        let digest = DigestConfig {
+            enabled: Self::load_bool("ENABLE_ALERT_DIGEST", false),
+            window_secs: env::var("ALERT_DIGEST_WINDOW_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(300)
+                .max(1),
+            immediate_limit: env::var("ALERT_DIGEST_IMMEDIATE_LIMIT")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(3),
+            escalation_after_secs: env::var("ALERT_ESCALATION_AFTER_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(900),
+            escalation_chat_id: Self::load_optional_string("TELEGRAM_ESCALATION_CHAT_ID"),
+            max_headlines: env::var("ALERT_DIGEST_MAX_HEADLINES")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(3),
+        };
+
+        let flow = FlowConfig {
+            enabled: Self::load_bool("ENABLE_TRADE_FLOW", false),
+            emit_interval_secs: env::var("TRADE_FLOW_EMIT_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(60)
+                .max(1),
+            imbalance_alert_pct: env::var("TRADE_FLOW_IMBALANCE_PCT")
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(60.0)
+                .clamp(0.0, 100.0),
+            imbalance_min_notional: env::var("TRADE_FLOW_IMBALANCE_MIN_NOTIONAL")
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(250_000.0)
+                .max(0.0),
+        };
+
+        let whale = WhaleConfig {
+            cluster_window_ms: env::var("WHALE_CLUSTER_WINDOW_MS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(3_000),
+            adjacent_bps: env::var("WHALE_ADJACENT_BPS")
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(5.0)
+                .max(0.0),
+            iceberg_window_secs: env::var("ICEBERG_WINDOW_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(60),
+            iceberg_min_refills: env::var("ICEBERG_MIN_REFILLS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(3),
+            iceberg_refill_ratio: env::var("ICEBERG_REFILL_RATIO")
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(0.8),
+        };
+
+        let threshold = ThresholdConfig {
+            window_secs: env::var("BIG_TRADE_WINDOW_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(900)
+                .max(1),
+            recalc_interval_secs: env::var("BIG_TRADE_RECALC_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(30),
+            min_samples: env::var("BIG_TRADE_MIN_SAMPLES")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(200),
+        };
+
+        let delivery = DeliveryConfig {
+            queue_capacity: env::var("NOTIFY_QUEUE_CAPACITY")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(256),
+            workers: env::var("NOTIFY_QUEUE_WORKERS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(1)
+                .max(1),
+            overflow: env::var("NOTIFY_QUEUE_OVERFLOW")
+                .ok()
+                .and_then(|v| OverflowPolicy::parse(&v))
+                .unwrap_or_default(),
+            metrics_interval_secs: env::var("NOTIFY_QUEUE_METRICS_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(60),
+        };
+
+        let universe_top_n = env::var("UNIVERSE_TOP_N")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(30)
+            .max(1);
+        let universe = UniverseConfig {
+            enabled: Self::load_bool("DYNAMIC_UNIVERSE", false),
+            top_n: universe_top_n,
+            exit_rank: env::var("UNIVERSE_EXIT_RANK")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(universe_top_n + universe_top_n / 3)
+                .max(universe_top_n),
+            quote_asset: Self::load_optional_string("UNIVERSE_QUOTE_ASSET")
+                .unwrap_or_else(|| "USDT".to_string())
+                .to_uppercase(),
+            rebalance_interval_secs: env::var("UNIVERSE_REBALANCE_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(300)
+                .max(1),
+            warmup_secs: env::var("UNIVERSE_WARMUP_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(10),
+            exclude: env::var("UNIVERSE_EXCLUDE")
+                .unwrap_or_else(|_| "usdcusdt,fdusdusdt,tusdusdt,eurusdt".to_string())
+                .split(',')
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect(),
+        };
+        let breadth_quote_asset = Self::load_optional_string("MARKET_BREADTH_QUOTE_ASSET")
+            .unwrap_or_else(|| "USDT".to_string())
+            .to_uppercase();
+        let breadth = BreadthConfig {
+            enabled: Self::load_bool("MARKET_BREADTH", false),
+            interval_secs: env::var("MARKET_BREADTH_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(60)
+                .max(1),
+            dominance_symbol: Self::load_optional_string("MARKET_BREADTH_DOMINANCE_SYMBOL")
+                .map(|s| s.to_lowercase())
+                .unwrap_or_else(|| format!("btc{}", breadth_quote_asset.to_lowercase())),
+            quote_asset: breadth_quote_asset,
+        };
+        let cross_asset = CrossAssetConfig {
+            enabled: Self::load_bool("CROSS_ASSET", false),
+            reference_symbol: Self::load_optional_string("CROSS_ASSET_REFERENCE")
+                .map(|s| s.to_lowercase())
+                .unwrap_or_else(|| "btcusdt".to_string()),
+            bar_secs: env::var("CROSS_ASSET_BAR_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(10)
+                .max(1),
+            window_bars: env::var("CROSS_ASSET_WINDOW_BARS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(180),
+            max_lag_bars: env::var("CROSS_ASSET_MAX_LAG_BARS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(6),
+            matrix_interval_secs: env::var("CROSS_ASSET_MATRIX_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(300)
+                .max(1),
+            big_move_lookback_secs: env::var("CROSS_ASSET_BIG_MOVE_LOOKBACK_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(300),
+        };
+        let volatility = VolatilityConfig {
+            enabled: Self::load_bool("VOLATILITY_REGIMES", false),
+            bar_secs: env::var("VOL_BAR_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(60)
+                .max(1),
+            window_bars: env::var("VOL_WINDOW_BARS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(30),
+            baseline_bars: env::var("VOL_BASELINE_BARS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(1_440),
+            scale_thresholds: Self::load_bool("VOL_SCALE_THRESHOLDS", false),
+        };
+        let outcomes = OutcomeConfig {
+            enabled: Self::load_bool("SIGNAL_OUTCOMES", false),
+            db_path: Self::load_optional_string("SIGNAL_OUTCOMES_DB_PATH")
+                .unwrap_or_else(|| "signal_outcomes.sqlite".to_string()),
+            report_interval_secs: env::var("SIGNAL_OUTCOMES_REPORT_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(3_600),
+        };
+        let paper = PaperConfig {
+            enabled: Self::load_bool("PAPER_TRADING", false),
+            strategies: Self::load_paper_strategies(),
+            fee_bps: env::var("PAPER_FEE_BPS")
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|v| *v >= 0.0)
+                .unwrap_or(4.0),
+            fallback_slippage_bps: env::var("PAPER_SLIPPAGE_BPS")
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|v| *v >= 0.0)
+                .unwrap_or(2.0),
+            report_interval_secs: env::var("PAPER_REPORT_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(3_600),
+        };
+        let conviction = ConvictionConfig {
+            enabled: Self::load_bool("CONVICTION", false),
+            weights: match env::var("CONVICTION_WEIGHTS") {
+                Ok(raw) => ConvictionWeights::parse(&raw, ConvictionWeights::default())
+                    .unwrap_or_else(|| {
+                        log::warn!(
+                            target: "config",
+                            "invalid CONVICTION_WEIGHTS '{raw}', using defaults",
+                        );
+                        ConvictionWeights::default()
+                    }),
+                Err(_) => ConvictionWeights::default(),
+            },
+            thresholds: env::var("CONVICTION_THRESHOLDS")
+                .ok()
+                .map(|raw| {
+                    raw.split(',')
+                        .filter_map(|v| v.trim().parse::<f64>().ok())
+                        .filter(|v| *v > 0.0 && *v <= 100.0)
+                        .collect::<Vec<f64>>()
+                })
+                .filter(|levels| !levels.is_empty())
+                .unwrap_or_else(|| vec![40.0, 70.0]),
+            hysteresis: env::var("CONVICTION_HYSTERESIS")
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|v| *v >= 0.0)
+                .unwrap_or(5.0),
+            decay_secs: env::var("CONVICTION_DECAY_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(900),
+        };
+        let rules = RuleConfig {
+            path: Self::load_optional_string("ALERT_RULES_PATH"),
+            inline: Self::load_optional_string("ALERT_RULES"),
+            reload_secs: env::var("ALERT_RULES_RELOAD_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(5),
+            cooldown_secs: env::var("ALERT_RULES_COOLDOWN_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(300),
+        };
+        let state = StateConfig {
+            enabled: Self::load_bool("STATE_SNAPSHOT", false),
+            path: Self::load_optional_string("STATE_SNAPSHOT_PATH")
+                .unwrap_or_else(|| "state_snapshot.json".to_string()),
+            interval_secs: env::var("STATE_SNAPSHOT_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(60)
+                .max(1),
+            max_age_secs: env::var("STATE_SNAPSHOT_MAX_AGE_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(300),
+        };
+        let shutdown = ShutdownConfig {
+            drain_timeout_secs: env::var("SHUTDOWN_DRAIN_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(10),
+        };
+        let max_symbols = symbols.len()
+            + if universe.enabled {
+                universe.exit_rank
+            } else {
+                0
+            };
+
+        let sharding = ShardConfig {
+            shards: env::var("SHARD_COUNT")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(4)
+                .clamp(1, max_symbols.max(1)),
+            queue_capacity: env::var("SHARD_QUEUE_CAPACITY")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(4_096)
+                .max(1),
+            overflow: env::var("SHARD_QUEUE_OVERFLOW")
+                .ok()
+                .and_then(|v| OverflowPolicy::parse(&v))
+                .unwrap_or_default(),
+        };
+
+        let registry = RegistryConfig {
+            mode: env::var("SYMBOL_REGISTRY_MODE")
+                .ok()
+                .and_then(|v| RegistryMode::parse(&v))
+                .unwrap_or_default(),
+            exchange_info_url: Self::load_optional_string("SYMBOL_REGISTRY_URL").unwrap_or_else(
+                || "https://data-api.binance.vision/api/v3/exchangeInfo".to_string(),
+            ),
+            cache_path: Self::load_optional_string("SYMBOL_REGISTRY_CACHE_PATH")
+                .unwrap_or_else(|| "exchange_info.json".to_string()),
+            cache_ttl_secs: env::var("SYMBOL_REGISTRY_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(86_400),
+        };
+
         Config {
             symbols,
             port,
@@ -218,6 +827,7 @@ impl Config {
             big_depth_min_qty,
             big_depth_min_notional,
             big_depth_min_pressure_pct,
+            spike_windows_secs,
             disable_depth_stream,
             enable_funding_rate,
             funding_rate_alert_pct,
@@ -228,9 +838,210 @@ impl Config {
             news_streams,
             news,
             telegram,
+            digest,
+            flow,
+            whale,
+            threshold,
+            delivery,
+            sharding,
+            registry,
+            universe,
+            breadth,
+            cross_asset,
+            volatility,
+            outcomes,
+            paper,
+            conviction,
+            rules,
+            state,
+            shutdown,
         }
     }
 
+    /// Upper bound on concurrently watched symbols, used to size the shard pool.
+    pub fn max_symbol_count(&self) -> usize {
+        let dynamic = if self.universe.enabled {
+            self.universe.exit_rank
+        } else {
+            0
+        };
+        (self.symbols.len() + dynamic).max(1)
+    }
+
+    /// Builds a symbol's config from the global defaults and its `<SYMBOL>_*` overrides.
+    /// Also used for symbols added at runtime by the dynamic universe.
+    pub fn load_symbol(symbol: &str) -> SymbolConfig {
+        let default_qty = env::var("BIG_TRADE_QTY")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(20.0);
+
+        let default_spike = env::var("SPIKE_PCT")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(0.4);
+
+        let default_whale_notional = env::var("WHALE_NOTIONAL")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(1_000_000.0);
+
+        let default_big_trade_rule = env::var("BIG_TRADE_THRESHOLD")
+            .ok()
+            .and_then(|v| BigTradeRule::parse(&v))
+            .unwrap_or_default();
+
+        let symbol = symbol.trim().to_lowercase();
+        let big_trade_qty = Self::load_symbol_env(&symbol, "BIG_TRADE_QTY", default_qty);
+        let spike_pct = Self::load_symbol_env(&symbol, "SPIKE_PCT", default_spike);
+        let whale_notional =
+            Self::load_symbol_env(&symbol, "WHALE_NOTIONAL", default_whale_notional);
+        let big_trade_rule = env::var(format!("{}_BIG_TRADE_THRESHOLD", symbol.to_uppercase()))
+            .ok()
+            .and_then(|v| BigTradeRule::parse(&v))
+            .unwrap_or(default_big_trade_rule);
+        let precision = SymbolPrecision {
+            tick_size: Self::load_symbol_decimal(&symbol, "TICK_SIZE"),
+            step_size: Self::load_symbol_decimal(&symbol, "STEP_SIZE"),
+        };
+        let big_move_profiles = Self::load_big_move_profiles(&symbol);
+        SymbolConfig {
+            symbol,
+            big_trade_qty,
+            spike_pct,
+            whale_notional,
+            big_trade_rule,
+            precision,
+            big_move_profiles,
+        }
+    }
+
+    /// `BIG_MOVE_*` defaults with `<SYMBOL>_BIG_MOVE_*` overrides form the base profile;
+    /// `[<SYMBOL>_]BIG_MOVE_PROFILES` lists named variants of it, otherwise the base runs
+    /// alone as `default`.
+    fn load_big_move_profiles(symbol: &str) -> Vec<BigMoveProfile> {
+        let fallback = BigMoveProfile::default();
+        let global = |key: &str, default: f64| {
+            env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(default)
+        };
+        let mut base = BigMoveProfile {
+            name: fallback.name.clone(),
+            window_size: Self::load_symbol_env(
+                symbol,
+                "BIG_MOVE_WINDOW",
+                global("BIG_MOVE_WINDOW", fallback.window_size as f64),
+            )
+            .max(1.0) as usize,
+            pressure_threshold: Self::load_symbol_env(
+                symbol,
+                "BIG_MOVE_PRESSURE_PCT",
+                global("BIG_MOVE_PRESSURE_PCT", fallback.pressure_threshold),
+            )
+            .clamp(50.0, 100.0),
+            min_total_notional: Self::load_symbol_env(
+                symbol,
+                "BIG_MOVE_MIN_NOTIONAL",
+                global("BIG_MOVE_MIN_NOTIONAL", fallback.min_total_notional),
+            )
+            .max(0.0),
+            min_consecutive: Self::load_symbol_env(
+                symbol,
+                "BIG_MOVE_MIN_CONSECUTIVE",
+                global("BIG_MOVE_MIN_CONSECUTIVE", fallback.min_consecutive as f64),
+            )
+            .max(1.0) as usize,
+            exit_gap_pct: Self::load_symbol_env(
+                symbol,
+                "BIG_MOVE_EXIT_GAP_PCT",
+                global("BIG_MOVE_EXIT_GAP_PCT", fallback.exit_gap_pct),
+            )
+            .max(0.0),
+            cooldown_secs: Self::load_symbol_env(
+                symbol,
+                "BIG_MOVE_COOLDOWN_SECS",
+                global("BIG_MOVE_COOLDOWN_SECS", fallback.cooldown_secs as f64),
+            )
+            .max(0.0) as u64,
+            update_every: Self::load_symbol_env(
+                symbol,
+                "BIG_MOVE_UPDATE_EVERY",
+                global("BIG_MOVE_UPDATE_EVERY", fallback.update_every as f64),
+            )
+            .max(0.0) as usize,
+        };
+        base.min_consecutive = base.min_consecutive.min(base.window_size);
+
+        let raw = env::var(format!("{}_BIG_MOVE_PROFILES", symbol.to_uppercase()))
+            .or_else(|_| env::var("BIG_MOVE_PROFILES"))
+            .unwrap_or_default();
+        let mut profiles: Vec<BigMoveProfile> = Vec::new();
+        for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match BigMoveProfile::parse(entry, &base) {
+                Some(profile) if !profiles.iter().any(|p| p.name == profile.name) => {
+                    profiles.push(profile)
+                }
+                Some(profile) => {
+                    log::warn!(
+                        target: "config",
+                        "duplicate big-move profile '{}' ignored",
+                        profile.name
+                    )
+                }
+                None => log::warn!(target: "config", "invalid big-move profile '{entry}' ignored"),
+            }
+        }
+        if profiles.is_empty() {
+            profiles.push(base);
+        }
+        profiles
+    }
+
+    /// `PAPER_NOTIONAL`, `PAPER_STOP_LOSS_PCT`, `PAPER_TAKE_PROFIT_PCT` and
+    /// `PAPER_MAX_HOLD_SECS` form the base strategy; `PAPER_STRATEGIES` lists strategies
+    /// built on it, otherwise the base trades big moves alone.
+    fn load_paper_strategies() -> Vec<PaperStrategy> {
+        let fallback = PaperStrategy::default();
+        let number = |key: &str, default: f64| {
+            env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|v| *v >= 0.0)
+                .unwrap_or(default)
+        };
+        let base = PaperStrategy {
+            notional: number("PAPER_NOTIONAL", fallback.notional).max(1.0),
+            stop_loss_pct: number("PAPER_STOP_LOSS_PCT", fallback.stop_loss_pct),
+            take_profit_pct: number("PAPER_TAKE_PROFIT_PCT", fallback.take_profit_pct),
+            max_hold_secs: number("PAPER_MAX_HOLD_SECS", fallback.max_hold_secs as f64) as u64,
+            ..fallback
+        };
+
+        let raw = env::var("PAPER_STRATEGIES").unwrap_or_default();
+        let mut strategies: Vec<PaperStrategy> = Vec::new();
+        for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match PaperStrategy::parse(entry, &base) {
+                Some(strategy) if !strategies.iter().any(|s| s.name == strategy.name) => {
+                    strategies.push(strategy)
+                }
+                Some(strategy) => {
+                    log::warn!(
+                        target: "config",
+                        "duplicate paper strategy '{}' ignored",
+                        strategy.name
+                    )
+                }
+                None => log::warn!(target: "config", "invalid paper strategy '{entry}' ignored"),
+            }
+        }
+        if strategies.is_empty() {
+            strategies.push(base);
+        }
+        strategies
+    }
+
     fn load_bool(key: &str, default: bool) -> bool {
         env::var(key)
             .map(|v| {
@@ -255,11 +1066,19 @@ impl Config {
             .and_then(|v| v.parse::<f64>().ok())
             .unwrap_or(default)
     }
+
+    fn load_symbol_decimal(symbol: &str, key: &str) -> Option<Decimal> {
+        env::var(format!("{}_{}", symbol.to_uppercase(), key))
+            .ok()
+            .and_then(|v| v.parse::<Decimal>().ok())
+            .filter(|v| !v.is_zero() && !v.is_sign_negative())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Config;
+    use crate::threshold::BigTradeRule;
     use std::sync::{Mutex, OnceLock};
 
     fn env_lock() -> &'static Mutex<()> {
@@ -378,6 +1197,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn digest_env_values_are_loaded_with_defaults() {
+        let _guard = env_lock().lock().expect("env lock poisoned");
+
+        unsafe {
+            std::env::set_var("ENABLE_ALERT_DIGEST", "true");
+            std::env::set_var("ALERT_DIGEST_WINDOW_SECS", "0");
+            std::env::set_var("TELEGRAM_ESCALATION_CHAT_ID", " -100999 ");
+        }
+
+        let config = Config::load();
+
+        assert!(config.digest.enabled);
+        assert_eq!(config.digest.window_secs, 1);
+        assert_eq!(config.digest.immediate_limit, 3);
+        assert_eq!(config.digest.escalation_after_secs, 900);
+        assert_eq!(config.digest.escalation_chat_id.as_deref(), Some("-100999"));
+
+        unsafe {
+            std::env::remove_var("ENABLE_ALERT_DIGEST");
+            std::env::remove_var("ALERT_DIGEST_WINDOW_SECS");
+            std::env::remove_var("TELEGRAM_ESCALATION_CHAT_ID");
+        }
+    }
+
     #[test]
     fn news_config_reports_when_provider_keys_exist() {
         let no_keys = super::NewsConfig {
@@ -396,4 +1240,78 @@ mod tests {
         };
         assert!(one_key.has_provider_api_key());
     }
+
+    #[test]
+    fn big_trade_threshold_rules_are_loaded_per_symbol() {
+        let _guard = env_lock().lock().expect("env lock poisoned");
+
+        unsafe {
+            std::env::set_var("SYMBOLS", "btcusdt,ethusdt,solusdt");
+            std::env::set_var("BIG_TRADE_THRESHOLD", "notional:250000");
+            std::env::set_var("ETHUSDT_BIG_TRADE_THRESHOLD", "percentile:99");
+            std::env::set_var("SOLUSDT_BIG_TRADE_THRESHOLD", "unknown");
+            std::env::set_var("BIG_TRADE_MIN_SAMPLES", "50");
+        }
+
+        let config = Config::load();
+        let rules: Vec<BigTradeRule> = config.symbols.iter().map(|s| s.big_trade_rule).collect();
+        assert_eq!(
+            rules,
+            vec![
+                BigTradeRule::Notional(250_000.0),
+                BigTradeRule::Percentile(99.0),
+                BigTradeRule::Notional(250_000.0),
+            ]
+        );
+        assert_eq!(config.threshold.min_samples, 50);
+        assert_eq!(config.threshold.window_secs, 900);
+
+        unsafe {
+            std::env::remove_var("SYMBOLS");
+            std::env::remove_var("BIG_TRADE_THRESHOLD");
+            std::env::remove_var("ETHUSDT_BIG_TRADE_THRESHOLD");
+            std::env::remove_var("SOLUSDT_BIG_TRADE_THRESHOLD");
+            std::env::remove_var("BIG_TRADE_MIN_SAMPLES");
+        }
+    }
+
+    #[test]
+    fn big_move_profiles_load_per_symbol() {
+        let _guard = env_lock().lock().expect("env lock poisoned");
+
+        unsafe {
+            std::env::set_var("BIG_MOVE_MIN_NOTIONAL", "100000");
+            std::env::set_var("PEPEUSDT_BIG_MOVE_PRESSURE_PCT", "85");
+            std::env::set_var("PEPEUSDT_BIG_MOVE_PROFILES", "fast:3::,slow:10:70,bad:x");
+        }
+
+        let btc = Config::load_symbol("btcusdt");
+        assert_eq!(btc.big_move_profiles.len(), 1);
+        assert_eq!(btc.big_move_profiles[0].name, "default");
+        assert_eq!(btc.big_move_profiles[0].min_total_notional, 100_000.0);
+
+        let pepe = Config::load_symbol("PEPEUSDT");
+        let names: Vec<&str> = pepe
+            .big_move_profiles
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["slow"]);
+        assert_eq!(pepe.big_move_profiles[0].pressure_threshold, 70.0);
+        assert_eq!(pepe.big_move_profiles[0].min_total_notional, 100_000.0);
+
+        unsafe {
+            std::env::set_var("PEPEUSDT_BIG_MOVE_PROFILES", "fast:3,slow:10:70");
+        }
+        let pepe = Config::load_symbol("pepeusdt");
+        assert_eq!(pepe.big_move_profiles[0].name, "fast");
+        assert_eq!(pepe.big_move_profiles[0].pressure_threshold, 85.0);
+        assert_eq!(pepe.big_move_profiles[0].min_consecutive, 3);
+
+        unsafe {
+            std::env::remove_var("BIG_MOVE_MIN_NOTIONAL");
+            std::env::remove_var("PEPEUSDT_BIG_MOVE_PRESSURE_PCT");
+            std::env::remove_var("PEPEUSDT_BIG_MOVE_PROFILES");
+        }
+    }
 }
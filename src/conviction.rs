@@ -0,0 +1,564 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::config::ConvictionConfig;
+use crate::correlation::model::{CorrelationSignal, MarketEvent, MarketEventKind};
+use crate::logging;
+use crate::trade_flow::FlowSnapshot;
+
+/// A 4h candle return of this size (%) counts as a full-strength quant reading.
+const QUANT_FULL_SCALE_PCT: f64 = 2.0;
+
+/// Funding at this multiple of the alert threshold counts as full-strength.
+const FUNDING_FULL_SCALE_MULTIPLE: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvictionComponent {
+    Depth,
+    Flow,
+    Funding,
+    Quant,
+    News,
+}
+
+impl ConvictionComponent {
+    pub const ALL: [ConvictionComponent; 5] = [
+        ConvictionComponent::Depth,
+        ConvictionComponent::Flow,
+        ConvictionComponent::Funding,
+        ConvictionComponent::Quant,
+        ConvictionComponent::News,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConvictionComponent::Depth => "depth",
+            ConvictionComponent::Flow => "flow",
+            ConvictionComponent::Funding => "funding",
+            ConvictionComponent::Quant => "quant",
+            ConvictionComponent::News => "news",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Weight of each component in the score. Negative weights invert a component, e.g. to
+/// read crowded funding as contrarian.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConvictionWeights {
+    pub depth: f64,
+    pub flow: f64,
+    pub funding: f64,
+    pub quant: f64,
+    pub news: f64,
+}
+
+impl Default for ConvictionWeights {
+    fn default() -> Self {
+        Self {
+            depth: 0.30,
+            flow: 0.25,
+            funding: 0.15,
+            quant: 0.15,
+            news: 0.15,
+        }
+    }
+}
+
+impl ConvictionWeights {
+    /// Parses `depth=0.3,flow=0.25,...`; components not listed keep their `base` weight.
+    pub fn parse(raw: &str, base: ConvictionWeights) -> Option<Self> {
+        let mut weights = base;
+        for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (key, value) = entry.split_once('=')?;
+            let value: f64 = value.trim().parse().ok().filter(|v: &f64| v.is_finite())?;
+            match key.trim().to_lowercase().as_str() {
+                "depth" => weights.depth = value,
+                "flow" => weights.flow = value,
+                "funding" => weights.funding = value,
+                "quant" => weights.quant = value,
+                "news" => weights.news = value,
+                _ => return None,
+            }
+        }
+        (weights.total_abs() > 0.0).then_some(weights)
+    }
+
+    pub fn get(&self, component: ConvictionComponent) -> f64 {
+        match component {
+            ConvictionComponent::Depth => self.depth,
+            ConvictionComponent::Flow => self.flow,
+            ConvictionComponent::Funding => self.funding,
+            ConvictionComponent::Quant => self.quant,
+            ConvictionComponent::News => self.news,
+        }
+    }
+
+    fn total_abs(&self) -> f64 {
+        ConvictionComponent::ALL
+            .iter()
+            .map(|c| self.get(*c).abs())
+            .sum()
+    }
+}
+
+/// Latest reading of one component: `value` in [-1, 1], positive when bullish.
+#[derive(Debug, Clone, PartialEq)]
+struct Reading {
+    value: f64,
+    timestamp_ms: u64,
+    detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentContribution {
+    pub component: &'static str,
+    /// Reading in [-1, 1] before decay.
+    pub value: f64,
+    pub weight: f64,
+    pub age_ms: u64,
+    /// Points added to the score, after weighting and decay.
+    pub contribution: f64,
+    pub detail: String,
+}
+
+/// Emitted when a symbol's score enters a different threshold band.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConvictionUpdate {
+    pub symbol: String,
+    pub timestamp_ms: u64,
+    /// Weighted score in [-100, 100], positive when bullish.
+    pub score: f64,
+    pub previous_score: f64,
+    /// Highest threshold reached, signed with the score; 0 below the first threshold.
+    pub level: f64,
+    pub previous_level: f64,
+    pub components: Vec<ComponentContribution>,
+}
+
+impl ConvictionUpdate {
+    pub fn to_event_json(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("event".to_string(), "conviction".into());
+        }
+        value.to_string()
+    }
+}
+
+#[derive(Debug, Default)]
+struct SymbolConviction {
+    readings: [Option<Reading>; 5],
+    /// Index of the current band, signed; 0 below the first threshold.
+    band: i32,
+    score: f64,
+}
+
+/// Combines the latest depth pressure, trade-flow imbalance, funding, quant kline and news
+/// correlation readings per symbol into one score. Readings fade linearly to zero over
+/// the decay window.
+#[derive(Debug)]
+pub struct ConvictionScorer {
+    weights: ConvictionWeights,
+    /// Ascending absolute score levels.
+    thresholds: Vec<f64>,
+    hysteresis: f64,
+    decay_ms: u64,
+    funding_full_scale_pct: f64,
+    flow_min_notional: f64,
+    symbols: HashMap<String, SymbolConviction>,
+}
+
+impl ConvictionScorer {
+    pub fn new(config: &ConvictionConfig, funding_alert_pct: f64, flow_min_notional: f64) -> Self {
+        let mut thresholds = config.thresholds.clone();
+        thresholds.sort_by(f64::total_cmp);
+        Self {
+            weights: config.weights,
+            thresholds,
+            hysteresis: config.hysteresis,
+            decay_ms: config.decay_secs.max(1) * 1_000,
+            funding_full_scale_pct: (funding_alert_pct * FUNDING_FULL_SCALE_MULTIPLE).max(1e-6),
+            flow_min_notional,
+            symbols: HashMap::new(),
+        }
+    }
+
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        self.symbols.remove(symbol);
+    }
+
+    /// Depth pressure, kline close and funding events; trades are ignored.
+    pub fn observe_market_event(&mut self, event: &MarketEvent) -> Option<ConvictionUpdate> {
+        let signed = event.direction.signum() as f64 * event.move_pct.abs();
+        match event.kind {
+            MarketEventKind::DepthPressure => {
+                // move_pct is the distance from a balanced book, in % of the 50 point range.
+                self.observe_depth(&event.symbol, event.timestamp_ms, 50.0 + signed / 2.0)
+            }
+            MarketEventKind::KlineClose => {
+                self.observe_quant(&event.symbol, event.timestamp_ms, signed)
+            }
+            MarketEventKind::FundingRate => {
+                self.observe_funding(&event.symbol, event.timestamp_ms, signed)
+            }
+            MarketEventKind::AggTrade => None,
+        }
+    }
+
+    pub fn observe_depth(
+        &mut self,
+        symbol: &str,
+        timestamp_ms: u64,
+        bid_pressure_pct: f64,
+    ) -> Option<ConvictionUpdate> {
+        let reading = Reading {
+            value: ((bid_pressure_pct - 50.0) / 50.0).clamp(-1.0, 1.0),
+            timestamp_ms,
+            detail: format!("bid pressure {:.1}%", bid_pressure_pct),
+        };
+        self.observe(symbol, ConvictionComponent::Depth, reading)
+    }
+
+    /// Uses the strongest flow window with enough notional.
+    pub fn observe_flow(&mut self, snapshot: &FlowSnapshot) -> Option<ConvictionUpdate> {
+        let imbalance = snapshot.strongest_imbalance(0.0, self.flow_min_notional)?;
+        let reading = Reading {
+            value: (imbalance.imbalance_pct / 100.0).clamp(-1.0, 1.0),
+            timestamp_ms: snapshot.timestamp_ms,
+            detail: format!(
+                "{} taker imbalance {:+.1}%",
+                imbalance.label, imbalance.imbalance_pct
+            ),
+        };
+        self.observe(&snapshot.symbol, ConvictionComponent::Flow, reading)
+    }
+
+    /// Positive funding (longs paying) reads bullish, the direction of the crowd.
+    pub fn observe_funding(
+        &mut self,
+        symbol: &str,
+        timestamp_ms: u64,
+        rate_pct: f64,
+    ) -> Option<ConvictionUpdate> {
+        let reading = Reading {
+            value: (rate_pct / self.funding_full_scale_pct).clamp(-1.0, 1.0),
+            timestamp_ms,
+            detail: format!("funding {:+.4}%", rate_pct),
+        };
+        self.observe(symbol, ConvictionComponent::Funding, reading)
+    }
+
+    pub fn observe_quant(
+        &mut self,
+        symbol: &str,
+        timestamp_ms: u64,
+        return_pct: f64,
+    ) -> Option<ConvictionUpdate> {
+        let reading = Reading {
+            value: (return_pct / QUANT_FULL_SCALE_PCT).clamp(-1.0, 1.0),
+            timestamp_ms,
+            detail: format!("4h return {:+.2}%", return_pct),
+        };
+        self.observe(symbol, ConvictionComponent::Quant, reading)
+    }
+
+    /// A news-correlated move, signed by the move's direction and scaled by confidence.
+    pub fn observe_correlation(&mut self, signal: &CorrelationSignal) -> Option<ConvictionUpdate> {
+        let headline: String = signal.news_headline.chars().take(60).collect();
+        let reading = Reading {
+            value: (signal.direction.signum() as f64 * signal.confidence).clamp(-1.0, 1.0),
+            timestamp_ms: signal.timestamp_ms,
+            detail: format!("news conf {:.2} \"{}\"", signal.confidence, headline),
+        };
+        self.observe(&signal.symbol, ConvictionComponent::News, reading)
+    }
+
+    fn observe(
+        &mut self,
+        symbol: &str,
+        component: ConvictionComponent,
+        reading: Reading,
+    ) -> Option<ConvictionUpdate> {
+        let symbol = symbol.to_lowercase();
+        let now_ms = reading.timestamp_ms;
+        let state = self.symbols.entry(symbol.clone()).or_default();
+        state.readings[component.index()] = Some(reading);
+
+        let components = Self::contributions(&self.weights, self.decay_ms, state, now_ms);
+        let score = components.iter().map(|c| c.contribution).sum::<f64>();
+        let band = Self::band(&self.thresholds, self.hysteresis, state.band, score);
+        let previous_score = std::mem::replace(&mut state.score, score);
+        if band == state.band {
+            return None;
+        }
+        let previous_band = std::mem::replace(&mut state.band, band);
+        let level = |band: i32| match band {
+            0 => 0.0,
+            b => self.thresholds[b.unsigned_abs() as usize - 1] * b.signum() as f64,
+        };
+        Some(ConvictionUpdate {
+            symbol,
+            timestamp_ms: now_ms,
+            score,
+            previous_score,
+            level: level(band),
+            previous_level: level(previous_band),
+            components,
+        })
+    }
+
+    fn contributions(
+        weights: &ConvictionWeights,
+        decay_ms: u64,
+        state: &SymbolConviction,
+        now_ms: u64,
+    ) -> Vec<ComponentContribution> {
+        let total = weights.total_abs().max(1e-9);
+        ConvictionComponent::ALL
+            .iter()
+            .filter_map(|component| {
+                let reading = state.readings[component.index()].as_ref()?;
+                let age_ms = now_ms.saturating_sub(reading.timestamp_ms);
+                if age_ms >= decay_ms {
+                    return None;
+                }
+                let decay = 1.0 - age_ms as f64 / decay_ms as f64;
+                let weight = weights.get(*component);
+                Some(ComponentContribution {
+                    component: component.as_str(),
+                    value: reading.value,
+                    weight,
+                    age_ms,
+                    contribution: weight / total * reading.value * decay * 100.0,
+                    detail: reading.detail.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Band for `score`: the count of thresholds reached, signed. A band is kept until the
+    /// score falls `hysteresis` points below its threshold or flips sign.
+    fn band(thresholds: &[f64], hysteresis: f64, current: i32, score: f64) -> i32 {
+        let reached = thresholds.iter().filter(|t| score.abs() >= **t).count() as i32;
+        let raw = if score < 0.0 { -reached } else { reached };
+        if current != 0 && raw.signum() != -current.signum() && raw.abs() < current.abs() {
+            let held = thresholds[current.unsigned_abs() as usize - 1] - hysteresis;
+            if score * current.signum() as f64 >= held {
+                return current;
+            }
+        }
+        raw
+    }
+}
+
+fn direction_label(level: f64) -> &'static str {
+    if level > 0.0 {
+        "BULLISH"
+    } else if level < 0.0 {
+        "BEARISH"
+    } else {
+        "NEUTRAL"
+    }
+}
+
+fn format_level(level: f64) -> String {
+    if level == 0.0 {
+        "none".to_string()
+    } else {
+        format!("{:.0}", level.abs())
+    }
+}
+
+pub fn format_conviction_log(update: &ConvictionUpdate) -> String {
+    let components: Vec<String> = update
+        .components
+        .iter()
+        .map(|c| format!("{}={:+.1}", c.component, c.contribution))
+        .collect();
+    format!(
+        "[CONVICTION] {} {} score={:+.1} level={} (was {:+.1} level={}) {}",
+        update.symbol.to_uppercase(),
+        direction_label(update.level),
+        update.score,
+        format_level(update.level),
+        update.previous_score,
+        format_level(update.previous_level),
+        components.join(" "),
+    )
+}
+
+/// Per-component breakdown in the style of the big-move self explanation.
+pub fn format_conviction_explain(update: &ConvictionUpdate) -> String {
+    let parts: Vec<String> = ConvictionComponent::ALL
+        .iter()
+        .map(|component| {
+            match update
+                .components
+                .iter()
+                .find(|c| c.component == component.as_str())
+            {
+                Some(c) => format!(
+                    "{}: {} -> {:+.2} x {:.2} age {}s = {:+.1}",
+                    c.component,
+                    c.detail,
+                    c.value,
+                    c.weight,
+                    c.age_ms / 1_000,
+                    c.contribution
+                ),
+                None => format!("{}: no recent reading", component.as_str()),
+            }
+        })
+        .collect();
+    format!(
+        "[CONVICTION][SELF_EXPLAIN][{}] score {:+.1} crossed into level {}: {}",
+        update.symbol.to_uppercase(),
+        update.score,
+        format_level(update.level),
+        parts.join("; ")
+    )
+}
+
+/// Broadcasts a threshold crossing with its JSON event; the explanation is logged only.
+pub fn emit_conviction(update: Option<ConvictionUpdate>, tx: &broadcast::Sender<String>) {
+    let Some(update) = update else {
+        return;
+    };
+    log::debug!(target: "conviction", "{}", format_conviction_explain(&update));
+    let msg = format_conviction_log(&update);
+    logging::signal("conviction", &msg);
+    let _ = tx.send(msg);
+    let _ = tx.send(update.to_event_json());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scorer() -> ConvictionScorer {
+        ConvictionScorer::new(
+            &ConvictionConfig {
+                enabled: true,
+                weights: ConvictionWeights::default(),
+                thresholds: vec![40.0, 20.0],
+                hysteresis: 5.0,
+                decay_secs: 100,
+            },
+            0.1,
+            0.0,
+        )
+    }
+
+    #[test]
+    fn combines_components_and_emits_on_band_changes() {
+        let mut scorer = scorer();
+        // Full bid pressure alone: 0.30 of the weight -> +30, crossing 20.
+        let update = scorer
+            .observe_depth("BTCUSDT", 0, 100.0)
+            .expect("crossed 20");
+        assert!((update.score - 30.0).abs() < 1e-9);
+        assert_eq!((update.level, update.previous_level), (20.0, 0.0));
+        assert!(format_conviction_log(&update).starts_with(
+            "[CONVICTION] BTCUSDT BULLISH score=+30.0 level=20 (was +0.0 level=none) depth=+30.0"
+        ));
+        let explain = format_conviction_explain(&update);
+        assert!(explain.starts_with("[CONVICTION][SELF_EXPLAIN][BTCUSDT] score +30.0"));
+        assert!(explain.contains("depth: bid pressure 100.0% -> +1.00 x 0.30 age 0s = +30.0"));
+        assert!(explain.contains("news: no recent reading"));
+
+        // A 2% 4h candle adds +15 and crosses 40.
+        let update = scorer
+            .observe_quant("btcusdt", 10_000, 2.0)
+            .expect("crossed 40");
+        assert!(
+            (update.score - 42.0).abs() < 1e-9,
+            "depth decayed 10%: {}",
+            update.score
+        );
+        assert_eq!(update.level, 40.0);
+
+        // Within the hysteresis band the level holds, below it the score drops a level.
+        assert!(scorer.observe_funding("btcusdt", 10_000, -0.04).is_none());
+        let update = scorer
+            .observe_funding("btcusdt", 10_000, -0.2)
+            .expect("dropped below 35");
+        assert_eq!(update.level, 20.0);
+
+        // Once everything decays the symbol is neutral again.
+        let update = scorer
+            .observe_depth("btcusdt", 200_000, 50.0)
+            .expect("neutral");
+        assert_eq!(update.level, 0.0);
+        assert!(update.components.iter().all(|c| c.component == "depth"));
+    }
+
+    #[test]
+    fn parses_weights_and_flips_on_bearish_flow() {
+        let weights =
+            ConvictionWeights::parse("flow=0.5, funding=-0.15", ConvictionWeights::default())
+                .expect("weights");
+        assert_eq!(
+            (weights.flow, weights.funding, weights.depth),
+            (0.5, -0.15, 0.30)
+        );
+        assert!(ConvictionWeights::parse("volume=1", ConvictionWeights::default()).is_none());
+
+        let mut scorer = scorer();
+        scorer.observe_depth("btcusdt", 0, 100.0);
+        let snapshot = FlowSnapshot {
+            symbol: "btcusdt".to_string(),
+            timestamp_ms: 0,
+            cvd: 0.0,
+            windows: vec![crate::trade_flow::FlowWindowStats {
+                label: "1m",
+                window_ms: 60_000,
+                buy_notional: 0.0,
+                sell_notional: 1_000.0,
+                volume_delta: -10.0,
+                trade_count: 5,
+                avg_trade_notional: 200.0,
+                imbalance_pct: -100.0,
+            }],
+        };
+        // +30 depth and -25 flow nets +5: back under every threshold.
+        let update = scorer.observe_flow(&snapshot).expect("left band");
+        assert_eq!(update.level, 0.0);
+        assert!((update.score - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hysteresis_holds_a_band_until_the_score_clears_it() {
+        let mut scorer = scorer();
+        // Depth alone carries 0.30 of the weight, so the score is 0.6 x (pressure - 50).
+        let mut levels = Vec::new();
+        for score in [21.0, 19.0, 16.0, 22.0, 14.0, 19.0, 23.0] {
+            let update = scorer.observe_depth("btcusdt", 0, 50.0 + score / 0.6);
+            levels.push(update.map(|u| u.level));
+        }
+        // Hovering just under 20 neither drops the level nor re-emits it on the way back.
+        assert_eq!(
+            levels,
+            vec![Some(20.0), None, None, None, Some(0.0), None, Some(20.0)]
+        );
+
+        let band = |current, score| ConvictionScorer::band(&[20.0, 40.0], 5.0, current, score);
+        assert_eq!(band(2, 36.0), 2);
+        assert_eq!(band(2, 34.0), 1);
+        assert_eq!(band(2, 10.0), 0);
+        assert_eq!(band(-1, -16.0), -1);
+        assert_eq!(band(-1, -14.0), 0);
+        // A sign flip is never held back.
+        assert_eq!(band(1, -21.0), -1);
+        assert_eq!(band(1, -1.0), 0);
+        // Rising into a band needs the threshold itself.
+        assert_eq!(band(0, 19.0), 0);
+        assert_eq!(band(1, 39.0), 1);
+        assert_eq!(band(1, 40.0), 2);
+    }
+}
@@ -1,18 +1,51 @@
 use std::collections::{HashMap, VecDeque};
 
+use serde::{Deserialize, Serialize};
+
 use super::model::{CorrelationSignal, MarketEvent, NewsEvent};
+use crate::breadth::MarketBreadth;
 
 const WINDOW_5M_MS: u64 = 5 * 60 * 1000;
 const WINDOW_15M_MS: u64 = 15 * 60 * 1000;
 const WINDOW_1H_MS: u64 = 60 * 60 * 1000;
 
+/// Weights of the confidence components. They are applied as given, so keeping their sum
+/// at 1.0 keeps confidence within 0..1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CorrelationWeights {
+    pub magnitude: f64,
+    pub notional: f64,
+    pub recency: f64,
+    pub sentiment: f64,
+}
+
+impl Default for CorrelationWeights {
+    fn default() -> Self {
+        Self {
+            magnitude: 0.35,
+            notional: 0.30,
+            recency: 0.25,
+            sentiment: 0.10,
+        }
+    }
+}
+
+/// Market and news queues persisted across restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CorrelationState {
+    pub market: HashMap<String, Vec<MarketEvent>>,
+    pub news: HashMap<String, Vec<NewsEvent>>,
+}
+
 #[derive(Debug, Clone)]
 pub struct CorrelationEngine {
     min_move_pct: f64,
     max_lag_ms: i64,
     min_confidence: f64,
+    weights: CorrelationWeights,
     market_by_symbol: HashMap<String, VecDeque<MarketEvent>>,
     news_by_symbol: HashMap<String, VecDeque<NewsEvent>>,
+    market_breadth: Option<MarketBreadth>,
 }
 
 impl CorrelationEngine {
@@ -21,11 +54,66 @@ impl CorrelationEngine {
             min_move_pct: min_move_pct.max(0.0),
             max_lag_ms: (max_lag_seconds as i64) * 1_000,
             min_confidence: min_confidence.clamp(0.0, 1.0),
+            weights: CorrelationWeights::default(),
             market_by_symbol: HashMap::new(),
             news_by_symbol: HashMap::new(),
+            market_breadth: None,
         }
     }
 
+    /// Replaces the default confidence weights (used by the backtest sweeps).
+    pub fn with_weights(mut self, weights: CorrelationWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    pub fn state(&self) -> CorrelationState {
+        CorrelationState {
+            market: self
+                .market_by_symbol
+                .iter()
+                .map(|(symbol, queue)| (symbol.clone(), queue.iter().cloned().collect()))
+                .collect(),
+            news: self
+                .news_by_symbol
+                .iter()
+                .map(|(symbol, queue)| (symbol.clone(), queue.iter().cloned().collect()))
+                .collect(),
+        }
+    }
+
+    /// Restores persisted queues, keeping only events inside the 1h correlation window.
+    pub fn restore(&mut self, state: CorrelationState, now_ms: u64) {
+        let oldest_ms = now_ms.saturating_sub(WINDOW_1H_MS);
+        for (symbol, events) in state.market {
+            let queue: VecDeque<MarketEvent> = events
+                .into_iter()
+                .filter(|e| e.timestamp_ms >= oldest_ms)
+                .collect();
+            if !queue.is_empty() {
+                self.market_by_symbol.insert(symbol, queue);
+            }
+        }
+        for (symbol, events) in state.news {
+            let queue: VecDeque<NewsEvent> = events
+                .into_iter()
+                .filter(|n| n.timestamp_ms >= oldest_ms)
+                .collect();
+            if !queue.is_empty() {
+                self.news_by_symbol.insert(symbol, queue);
+            }
+        }
+    }
+
+    /// Latest market-wide breadth; signals carry its regime as context.
+    pub fn on_market_breadth(&mut self, breadth: MarketBreadth) {
+        self.market_breadth = Some(breadth);
+    }
+
+    pub fn market_breadth(&self) -> Option<&MarketBreadth> {
+        self.market_breadth.as_ref()
+    }
+
     pub fn ingest_news(&mut self, news: NewsEvent) {
         let symbol = news.symbol.to_lowercase();
         let queue = self.news_by_symbol.entry(symbol).or_default();
@@ -54,10 +142,10 @@ impl CorrelationEngine {
         let notional_score = ((event.notional.max(0.0) + 1.0).log10() / 7.0).clamp(0.0, 1.0);
         let sentiment_score = Self::sentiment_alignment_score(event.direction, news.sentiment);
 
-        let confidence = (0.35 * magnitude_score)
-            + (0.30 * notional_score)
-            + (0.25 * recency_score)
-            + (0.10 * sentiment_score);
+        let confidence = (self.weights.magnitude * magnitude_score)
+            + (self.weights.notional * notional_score)
+            + (self.weights.recency * recency_score)
+            + (self.weights.sentiment * sentiment_score);
 
         if confidence < self.min_confidence {
             return None;
@@ -69,6 +157,8 @@ impl CorrelationEngine {
         Some(CorrelationSignal {
             symbol: event.symbol,
             market_event_kind: event.kind,
+            timestamp_ms: event.timestamp_ms,
+            direction: event.direction,
             news_headline: news.headline.clone(),
             lag_ms,
             confidence,
@@ -77,6 +167,8 @@ impl CorrelationEngine {
             window_5m_count,
             window_15m_count,
             window_1h_count,
+            market_regime: self.market_breadth.as_ref().map(|b| b.regime),
+            market_pct_up: self.market_breadth.as_ref().map(|b| b.pct_up_24h),
         })
     }
 
@@ -183,6 +275,7 @@ mod tests {
             kind: MarketEventKind::AggTrade,
             move_pct: 2.5,
             notional: 1_500_000.0,
+            price: 0.0,
             direction: 1,
         });
 
@@ -190,6 +283,7 @@ mod tests {
         let signal = signal.expect("signal should exist");
         assert_eq!(signal.lag_ms, 20);
         assert_eq!(signal.window_5m_count, 1);
+        assert!(signal.market_regime.is_none());
     }
 
     #[test]
@@ -208,6 +302,7 @@ mod tests {
             kind: MarketEventKind::DepthPressure,
             move_pct: 4.0,
             notional: 500_000.0,
+            price: 0.0,
             direction: -1,
         });
 
@@ -232,6 +327,7 @@ mod tests {
                 kind: MarketEventKind::KlineClose,
                 move_pct: 1.1,
                 notional: 10_000.0,
+                price: 0.0,
                 direction: 1,
             });
         }
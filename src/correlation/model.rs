@@ -1,6 +1,9 @@
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Debug, Clone)]
+use crate::breadth::BreadthRegime;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MarketEventKind {
     AggTrade,
     DepthPressure,
@@ -8,18 +11,20 @@ pub enum MarketEventKind {
     FundingRate,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketEvent {
     pub symbol: String,
     pub timestamp_ms: u64,
     pub kind: MarketEventKind,
     pub move_pct: f64,
     pub notional: f64,
+    /// Trade or close price behind the event; 0.0 for events without one (depth, funding).
+    pub price: f64,
     /// +1 for bullish, -1 for bearish, 0 for neutral/unknown.
     pub direction: i8,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewsEvent {
     pub symbol: String,
     pub timestamp_ms: u64,
@@ -32,6 +37,10 @@ pub struct NewsEvent {
 pub struct CorrelationSignal {
     pub symbol: String,
     pub market_event_kind: MarketEventKind,
+    /// Time of the market event that was matched.
+    pub timestamp_ms: u64,
+    /// Direction of the matched market event: +1 bullish, -1 bearish, 0 neutral.
+    pub direction: i8,
     pub news_headline: String,
     pub lag_ms: i64,
     pub confidence: f64,
@@ -40,6 +49,10 @@ pub struct CorrelationSignal {
     pub window_5m_count: usize,
     pub window_15m_count: usize,
     pub window_1h_count: usize,
+    /// Market-wide regime from the latest breadth snapshot, when breadth is enabled.
+    pub market_regime: Option<BreadthRegime>,
+    /// Percent of pairs up on the day at the time of the signal.
+    pub market_pct_up: Option<f64>,
 }
 
 pub fn parse_news_event(msg: &str) -> Option<NewsEvent> {
@@ -0,0 +1,599 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::config::CrossAssetConfig;
+
+/// Idiosyncratic share of a move above which it is not called beta-explained.
+const MAX_BETA_EXPLAINED_IDIO_SHARE: f64 = 0.5;
+
+/// Fewest return bars needed before correlations are reported.
+const MIN_RETURN_BARS: usize = 10;
+
+/// A spike or big move to split into reference-driven and symbol-specific parts.
+#[derive(Debug, Clone)]
+pub struct MoveEvent {
+    pub symbol: String,
+    /// `price_spike` or `big_move`.
+    pub source: &'static str,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Observed move in %, or `None` to measure it from recorded prices.
+    pub move_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveClass {
+    BetaExplained,
+    Idiosyncratic,
+}
+
+impl MoveClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BetaExplained => "beta-explained",
+            Self::Idiosyncratic => "idiosyncratic",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MoveDecomposition {
+    pub symbol: String,
+    pub reference: String,
+    pub source: &'static str,
+    pub move_pct: f64,
+    pub reference_move_pct: f64,
+    pub beta: f64,
+    pub correlation: f64,
+    /// `beta * reference_move_pct`.
+    pub explained_pct: f64,
+    /// `move_pct - explained_pct`.
+    pub idiosyncratic_pct: f64,
+    pub class: MoveClass,
+}
+
+impl MoveDecomposition {
+    pub fn to_event_json(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("event".to_string(), "move_decomposition".into());
+        }
+        value.to_string()
+    }
+}
+
+/// Lag (in bars) at which a symbol's returns best correlate with the reference's.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LeadLag {
+    /// Positive when the reference leads, negative when the symbol leads.
+    pub lag_ms: i64,
+    pub correlation: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SymbolStats {
+    pub symbol: String,
+    pub correlation: Option<f64>,
+    pub beta: Option<f64>,
+    pub lead_lag: Option<LeadLag>,
+}
+
+/// Pairwise return correlations plus per-symbol stats against the reference.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CorrelationMatrix {
+    pub timestamp_ms: u64,
+    pub reference: String,
+    pub bar_ms: u64,
+    pub symbols: Vec<String>,
+    /// `matrix[i][j]` correlates `symbols[i]` with `symbols[j]`.
+    pub matrix: Vec<Vec<Option<f64>>>,
+    pub vs_reference: Vec<SymbolStats>,
+}
+
+impl CorrelationMatrix {
+    pub fn to_event_json(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_else(|_| json!({}));
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("event".to_string(), "correlation_matrix".into());
+        }
+        value.to_string()
+    }
+}
+
+/// Last price per fixed-size time bar, forward-filled over bars without trades.
+#[derive(Debug, Default)]
+struct PriceBars {
+    /// `(bar index, close)`, contiguous.
+    bars: VecDeque<(u64, f64)>,
+}
+
+impl PriceBars {
+    fn record(&mut self, bar: u64, price: f64, capacity: usize) {
+        match self.bars.back_mut() {
+            Some((last, close)) if *last == bar => *close = price,
+            Some((last, _)) if *last > bar => return,
+            Some(&mut (last, close)) => {
+                let gap = (bar - last - 1).min(capacity as u64);
+                for filled in bar - gap..bar {
+                    self.bars.push_back((filled, close));
+                }
+                self.bars.push_back((bar, price));
+            }
+            None => self.bars.push_back((bar, price)),
+        }
+        while self.bars.len() > capacity {
+            self.bars.pop_front();
+        }
+    }
+
+    /// Close of the last bar at or before `bar`.
+    fn close_at(&self, bar: u64) -> Option<f64> {
+        let (first, _) = *self.bars.front()?;
+        if bar < first {
+            return None;
+        }
+        let offset = ((bar - first) as usize).min(self.bars.len() - 1);
+        Some(self.bars[offset].1)
+    }
+
+    /// `len` simple returns for the bars ending at `end_bar`.
+    fn returns(&self, end_bar: u64, len: usize) -> Option<Vec<f64>> {
+        let start = end_bar.checked_sub(len as u64)?;
+        let mut prev = self.close_at(start)?;
+        let mut returns = Vec::with_capacity(len);
+        for bar in start + 1..=end_bar {
+            let close = self.close_at(bar)?;
+            returns.push(if prev > 0.0 { close / prev - 1.0 } else { 0.0 });
+            prev = close;
+        }
+        Some(returns)
+    }
+}
+
+/// Rolling per-symbol return series used for a correlation matrix, lead-lag against a
+/// reference symbol (BTC by default) and beta decomposition of spikes and big moves.
+#[derive(Debug)]
+pub struct CrossAssetTracker {
+    reference: String,
+    bar_ms: u64,
+    window: usize,
+    max_lag: usize,
+    big_move_lookback_ms: u64,
+    matrix_interval_ms: u64,
+    next_matrix_ms: Option<u64>,
+    latest_bar: u64,
+    series: HashMap<String, PriceBars>,
+}
+
+impl CrossAssetTracker {
+    pub fn new(config: &CrossAssetConfig) -> Self {
+        Self {
+            reference: config.reference_symbol.to_lowercase(),
+            bar_ms: config.bar_secs.max(1) * 1_000,
+            window: config.window_bars.max(MIN_RETURN_BARS),
+            max_lag: config.max_lag_bars,
+            big_move_lookback_ms: config.big_move_lookback_secs * 1_000,
+            matrix_interval_ms: config.matrix_interval_secs.max(1) * 1_000,
+            next_matrix_ms: None,
+            latest_bar: 0,
+            series: HashMap::new(),
+        }
+    }
+
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    pub fn record(&mut self, symbol: &str, ts_ms: u64, price: f64) {
+        if !price.is_finite() || price <= 0.0 {
+            return;
+        }
+        let bar = ts_ms / self.bar_ms;
+        let capacity = self.window + self.max_lag + 2;
+        self.series
+            .entry(symbol.to_lowercase())
+            .or_default()
+            .record(bar, price, capacity);
+        self.latest_bar = self.latest_bar.max(bar);
+    }
+
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        self.series.remove(&symbol.to_lowercase());
+    }
+
+    /// Last complete bar across all symbols.
+    fn end_bar(&self) -> u64 {
+        self.latest_bar.saturating_sub(1)
+    }
+
+    fn returns(&self, symbol: &str, end_bar: u64) -> Option<Vec<f64>> {
+        self.series.get(symbol)?.returns(end_bar, self.window)
+    }
+
+    pub fn correlation(&self, a: &str, b: &str) -> Option<f64> {
+        let end = self.end_bar();
+        pearson(
+            &self.returns(&a.to_lowercase(), end)?,
+            &self.returns(&b.to_lowercase(), end)?,
+        )
+    }
+
+    /// Regression beta of `symbol` returns on reference returns.
+    pub fn beta(&self, symbol: &str) -> Option<f64> {
+        let end = self.end_bar();
+        beta(
+            &self.returns(&symbol.to_lowercase(), end)?,
+            &self.returns(&self.reference, end)?,
+        )
+    }
+
+    pub fn lead_lag(&self, symbol: &str) -> Option<LeadLag> {
+        let symbol = symbol.to_lowercase();
+        if symbol == self.reference {
+            return None;
+        }
+        let end = self.end_bar();
+        let max_lag = self.max_lag as i64;
+        (-max_lag..=max_lag)
+            .filter_map(|lag| {
+                // Positive lag: symbol returns follow reference returns by `lag` bars.
+                let ref_end = end.checked_sub(lag.max(0) as u64)?;
+                let sym_end = end.checked_sub((-lag).max(0) as u64)?;
+                let corr = pearson(
+                    &self.returns(&symbol, sym_end)?,
+                    &self.returns(&self.reference, ref_end)?,
+                )?;
+                Some(LeadLag {
+                    lag_ms: lag * self.bar_ms as i64,
+                    correlation: corr,
+                })
+            })
+            .max_by(|a, b| a.correlation.total_cmp(&b.correlation))
+    }
+
+    pub fn matrix(&self, timestamp_ms: u64) -> CorrelationMatrix {
+        let end = self.end_bar();
+        let mut symbols: Vec<String> = self.series.keys().cloned().collect();
+        symbols.sort();
+        let returns: Vec<Option<Vec<f64>>> = symbols.iter().map(|s| self.returns(s, end)).collect();
+        let matrix = returns
+            .iter()
+            .map(|a| {
+                returns
+                    .iter()
+                    .map(|b| pearson(a.as_ref()?, b.as_ref()?))
+                    .collect()
+            })
+            .collect();
+        let vs_reference = symbols
+            .iter()
+            .filter(|s| **s != self.reference)
+            .map(|s| SymbolStats {
+                symbol: s.clone(),
+                correlation: self.correlation(s, &self.reference),
+                beta: self.beta(s),
+                lead_lag: self.lead_lag(s),
+            })
+            .collect();
+        CorrelationMatrix {
+            timestamp_ms,
+            reference: self.reference.clone(),
+            bar_ms: self.bar_ms,
+            symbols,
+            matrix,
+            vs_reference,
+        }
+    }
+
+    /// Returns the matrix once every interval, the first one an interval after the first call.
+    pub fn matrix_if_due(&mut self, now_ms: u64) -> Option<CorrelationMatrix> {
+        let due_ms = *self
+            .next_matrix_ms
+            .get_or_insert(now_ms + self.matrix_interval_ms);
+        if now_ms < due_ms {
+            return None;
+        }
+        self.next_matrix_ms = Some(now_ms + self.matrix_interval_ms);
+        Some(self.matrix(now_ms))
+    }
+
+    fn move_pct(&self, symbol: &str, start_ms: u64, end_ms: u64) -> Option<f64> {
+        let bars = self.series.get(symbol)?;
+        let start = bars.close_at(start_ms / self.bar_ms)?;
+        let end = bars.close_at(end_ms / self.bar_ms)?;
+        (start > 0.0).then(|| (end / start - 1.0) * 100.0)
+    }
+
+    /// Splits a move into the part implied by the reference's move over the same period
+    /// (`beta * reference move`) and the remainder.
+    pub fn decompose(&self, event: &MoveEvent) -> Option<MoveDecomposition> {
+        let symbol = event.symbol.to_lowercase();
+        if symbol == self.reference {
+            return None;
+        }
+        let start_ms = if event.start_ms < event.end_ms {
+            event.start_ms
+        } else {
+            event.end_ms.saturating_sub(self.big_move_lookback_ms)
+        };
+        let move_pct = match event.move_pct {
+            Some(pct) => pct,
+            None => self.move_pct(&symbol, start_ms, event.end_ms)?,
+        };
+        let reference_move_pct = self.move_pct(&self.reference, start_ms, event.end_ms)?;
+        let beta = self.beta(&symbol)?;
+        let correlation = self.correlation(&symbol, &self.reference)?;
+
+        let explained_pct = beta * reference_move_pct;
+        let idiosyncratic_pct = move_pct - explained_pct;
+        let class = if explained_pct * move_pct > 0.0
+            && idiosyncratic_pct.abs() <= move_pct.abs() * MAX_BETA_EXPLAINED_IDIO_SHARE
+        {
+            MoveClass::BetaExplained
+        } else {
+            MoveClass::Idiosyncratic
+        };
+
+        Some(MoveDecomposition {
+            symbol,
+            reference: self.reference.clone(),
+            source: event.source,
+            move_pct,
+            reference_move_pct,
+            beta,
+            correlation,
+            explained_pct,
+            idiosyncratic_pct,
+            class,
+        })
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn covariance(a: &[f64], b: &[f64]) -> Option<(f64, f64, f64)> {
+    if a.len() != b.len() || a.len() < MIN_RETURN_BARS {
+        return None;
+    }
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    Some((cov, var_a, var_b))
+}
+
+fn pearson(a: &[f64], b: &[f64]) -> Option<f64> {
+    let (cov, var_a, var_b) = covariance(a, b)?;
+    (var_a > 0.0 && var_b > 0.0).then(|| cov / (var_a * var_b).sqrt())
+}
+
+/// Slope of `y` regressed on `x`.
+fn beta(y: &[f64], x: &[f64]) -> Option<f64> {
+    let (cov, _, var_x) = covariance(y, x)?;
+    (var_x > 0.0).then(|| cov / var_x)
+}
+
+pub fn format_decomposition_log(decomposition: &MoveDecomposition) -> String {
+    format!(
+        "[BETA] {} {} move={:+.2}% {}={:+.2}% beta={:.2} corr={:.2} explained={:+.2}% idio={:+.2}% => {}",
+        decomposition.symbol.to_uppercase(),
+        decomposition.source,
+        decomposition.move_pct,
+        decomposition.reference.to_uppercase(),
+        decomposition.reference_move_pct,
+        decomposition.beta,
+        decomposition.correlation,
+        decomposition.explained_pct,
+        decomposition.idiosyncratic_pct,
+        decomposition.class.as_str(),
+    )
+}
+
+pub fn format_matrix_log(matrix: &CorrelationMatrix) -> String {
+    let parts: Vec<String> = matrix
+        .vs_reference
+        .iter()
+        .map(|stats| {
+            let corr = stats
+                .correlation
+                .map(|c| format!("{c:.2}"))
+                .unwrap_or_else(|| "n/a".to_string());
+            let beta = stats
+                .beta
+                .map(|b| format!("{b:.2}"))
+                .unwrap_or_else(|| "n/a".to_string());
+            let lead = stats
+                .lead_lag
+                .map(|l| format!("{:+}s", l.lag_ms / 1_000))
+                .unwrap_or_else(|| "n/a".to_string());
+            format!(
+                "{} corr={} beta={} lag={}",
+                stats.symbol.to_uppercase(),
+                corr,
+                beta,
+                lead
+            )
+        })
+        .collect();
+    format!(
+        "[CORR_MATRIX] vs {}: {}",
+        matrix.reference.to_uppercase(),
+        if parts.is_empty() {
+            "no peers".to_string()
+        } else {
+            parts.join(" | ")
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> CrossAssetConfig {
+        CrossAssetConfig {
+            enabled: true,
+            reference_symbol: "btcusdt".to_string(),
+            bar_secs: 1,
+            window_bars: 20,
+            max_lag_bars: 3,
+            matrix_interval_secs: 60,
+            big_move_lookback_secs: 5,
+        }
+    }
+
+    /// Deterministic zig-zag reference path with uneven steps.
+    fn reference_path(len: usize) -> Vec<f64> {
+        let mut price = 100.0;
+        (0..len)
+            .map(|i| {
+                let step = [0.4, -0.3, 0.7, -0.2, 0.1, -0.6, 0.5][i % 7];
+                price *= 1.0 + step / 100.0;
+                price
+            })
+            .collect()
+    }
+
+    #[test]
+    fn follower_has_beta_and_detects_lead() {
+        let mut tracker = CrossAssetTracker::new(&config());
+        let btc = reference_path(40);
+        for (i, price) in btc.iter().enumerate() {
+            let ts = i as u64 * 1_000;
+            tracker.record("BTCUSDT", ts, *price);
+            // ETH follows BTC's returns one bar later with twice the amplitude.
+            let eth_ret = if i >= 1 {
+                btc[i - 1] / btc[i.saturating_sub(2)] - 1.0
+            } else {
+                0.0
+            };
+            let prev = tracker
+                .series
+                .get("ethusdt")
+                .and_then(|s| s.bars.back().map(|(_, p)| *p))
+                .unwrap_or(50.0);
+            tracker.record("ETHUSDT", ts, prev * (1.0 + 2.0 * eth_ret));
+        }
+
+        let lead = tracker.lead_lag("ethusdt").expect("lead-lag");
+        assert_eq!(lead.lag_ms, 1_000);
+        assert!(lead.correlation > 0.99);
+
+        let matrix = tracker.matrix(40_000);
+        assert_eq!(matrix.symbols, vec!["btcusdt", "ethusdt"]);
+        assert!((matrix.matrix[0][0].unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(matrix.vs_reference.len(), 1);
+        assert!(format_matrix_log(&matrix).contains("ETHUSDT"));
+    }
+
+    #[test]
+    fn decomposes_moves_into_beta_and_idiosyncratic_parts() {
+        let mut tracker = CrossAssetTracker::new(&config());
+        let btc = reference_path(40);
+        for (i, price) in btc.iter().enumerate() {
+            let ts = i as u64 * 1_000;
+            tracker.record("btcusdt", ts, *price);
+            // SOL moves 1.5x BTC, in step.
+            tracker.record("solusdt", ts, 20.0 * (price / 100.0).powf(1.5));
+        }
+        let beta = tracker.beta("solusdt").expect("beta");
+        assert!((beta - 1.5).abs() < 0.05, "beta {beta}");
+
+        let ref_move = (btc[38] / btc[30] - 1.0) * 100.0;
+        let follow = tracker
+            .decompose(&MoveEvent {
+                symbol: "SOLUSDT".to_string(),
+                source: "price_spike",
+                start_ms: 30_000,
+                end_ms: 38_000,
+                move_pct: Some(1.5 * ref_move),
+            })
+            .expect("decomposition");
+        assert_eq!(follow.class, MoveClass::BetaExplained);
+        assert!(follow.idiosyncratic_pct.abs() < 0.1);
+
+        let own = tracker
+            .decompose(&MoveEvent {
+                symbol: "solusdt".to_string(),
+                source: "price_spike",
+                start_ms: 30_000,
+                end_ms: 38_000,
+                move_pct: Some(1.5 * ref_move + 5.0),
+            })
+            .expect("decomposition");
+        assert_eq!(own.class, MoveClass::Idiosyncratic);
+        assert!((own.idiosyncratic_pct - 5.0).abs() < 0.1);
+        assert!(format_decomposition_log(&own).ends_with("=> idiosyncratic"));
+    }
+
+    #[test]
+    fn zero_variance_series_have_no_correlation_or_beta() {
+        let mut tracker = CrossAssetTracker::new(&config());
+        let path = reference_path(40);
+        for (i, price) in path.iter().enumerate() {
+            let ts = i as u64 * 1_000;
+            // A flat reference, a moving symbol and a pegged one.
+            tracker.record("btcusdt", ts, 100.0);
+            tracker.record("ethusdt", ts, *price);
+            tracker.record("usdcusdt", ts, 1.0);
+        }
+
+        assert_eq!(tracker.correlation("ethusdt", "btcusdt"), None);
+        assert_eq!(tracker.beta("ethusdt"), None);
+        assert_eq!(tracker.lead_lag("ethusdt"), None);
+        assert_eq!(tracker.correlation("usdcusdt", "ethusdt"), None);
+        assert!(
+            tracker
+                .decompose(&MoveEvent {
+                    symbol: "ethusdt".to_string(),
+                    source: "price_spike",
+                    start_ms: 30_000,
+                    end_ms: 38_000,
+                    move_pct: Some(1.0),
+                })
+                .is_none()
+        );
+
+        let matrix = tracker.matrix(40_000);
+        assert_eq!(matrix.symbols, vec!["btcusdt", "ethusdt", "usdcusdt"]);
+        assert_eq!(matrix.matrix[0][0], None);
+        assert!(matrix.matrix[1][1].is_some());
+        assert_eq!(matrix.matrix[1][2], None);
+        assert_eq!(
+            format_matrix_log(&matrix),
+            "[CORR_MATRIX] vs BTCUSDT: ETHUSDT corr=n/a beta=n/a lag=n/a | USDCUSDT corr=n/a beta=n/a lag=n/a"
+        );
+    }
+
+    #[test]
+    fn long_gaps_forward_fill_at_most_capacity_bars() {
+        let mut bars = PriceBars::default();
+        bars.record(0, 100.0, 5);
+        bars.record(1_000, 110.0, 5);
+        assert_eq!(
+            bars.bars.iter().copied().collect::<Vec<_>>(),
+            vec![
+                (996, 100.0),
+                (997, 100.0),
+                (998, 100.0),
+                (999, 100.0),
+                (1_000, 110.0)
+            ]
+        );
+        // A late trade for an older bar is ignored.
+        bars.record(998, 90.0, 5);
+        assert_eq!(bars.close_at(998), Some(100.0));
+        assert_eq!(bars.close_at(2_000), Some(110.0));
+        assert_eq!(bars.close_at(10), None);
+        assert_eq!(bars.returns(999, 3), Some(vec![0.0, 0.0, 0.0]));
+        assert_eq!(bars.returns(1_000, 5), None);
+    }
+}
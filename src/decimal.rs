@@ -0,0 +1,307 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Fixed-point decimal as sent by the exchange (`"0.00001234"`), kept exact from parse to
+/// broadcast. `Display` prints the original digits, including trailing zeros.
+///
+/// Equality, ordering and hashing compare numeric value, so `"1.50"` equals `"1.5"`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+/// Max fractional digits accepted; Binance uses at most 8.
+const MAX_SCALE: u32 = 18;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDecimalError(String);
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid decimal '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseDecimalError {}
+
+impl Decimal {
+    pub const ZERO: Decimal = Decimal {
+        mantissa: 0,
+        scale: 0,
+    };
+
+    pub fn new(mantissa: i128, scale: u32) -> Self {
+        Self { mantissa, scale }
+    }
+
+    /// Fractional digits as received.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    pub fn is_sign_negative(&self) -> bool {
+        self.mantissa < 0
+    }
+
+    /// Same value with trailing fractional zeros removed (`"0.0100"` -> `"0.01"`).
+    pub fn normalized(&self) -> Self {
+        let mut normalized = *self;
+        while normalized.scale > 0 && normalized.mantissa % 10 == 0 {
+            normalized.mantissa /= 10;
+            normalized.scale -= 1;
+        }
+        normalized
+    }
+
+    /// Decimal places implied by a tick or step size (`0.00010000` -> 4, `1.00` -> 0).
+    pub fn precision(&self) -> u32 {
+        self.normalized().scale
+    }
+
+    fn rescaled_mantissa(&self, scale: u32) -> Option<i128> {
+        10i128
+            .checked_pow(scale.checked_sub(self.scale)?)
+            .and_then(|factor| self.mantissa.checked_mul(factor))
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let err = || ParseDecimalError(raw.to_string());
+        let trimmed = raw.trim();
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+        if (int_part.is_empty() && frac_part.is_empty())
+            || !int_part.bytes().all(|b| b.is_ascii_digit())
+            || !frac_part.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(err());
+        }
+
+        let scale = frac_part.len() as u32;
+        if scale > MAX_SCALE {
+            return Err(err());
+        }
+
+        let mut mantissa: i128 = 0;
+        for b in int_part.bytes().chain(frac_part.bytes()) {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add((b - b'0') as i128))
+                .ok_or_else(err)?;
+        }
+
+        Ok(Self {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale,
+        })
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        if self.scale == 0 {
+            return write!(f, "{sign}{digits}");
+        }
+
+        let scale = self.scale as usize;
+        let padded = format!("{digits:0>width$}", width = scale + 1);
+        let (int_part, frac_part) = padded.split_at(padded.len() - scale);
+        write!(f, "{sign}{int_part}.{frac_part}")
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        match (
+            self.rescaled_mantissa(scale),
+            other.rescaled_mantissa(scale),
+        ) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => self.to_f64().total_cmp(&other.to_f64()),
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normalized = self.normalized();
+        normalized.mantissa.hash(state);
+        normalized.scale.hash(state);
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DecimalVisitor;
+
+        impl Visitor<'_> for DecimalVisitor {
+            type Value = Decimal;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a decimal string or number")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Decimal, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Decimal, E> {
+                Ok(Decimal::new(v as i128, 0))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Decimal, E> {
+                Ok(Decimal::new(v as i128, 0))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Decimal, E> {
+                if !v.is_finite() {
+                    return Err(E::custom("non-finite decimal"));
+                }
+                v.to_string().parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+}
+
+/// Tick (price) and step (quantity) sizes used to format computed values for a symbol.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SymbolPrecision {
+    pub tick_size: Option<Decimal>,
+    pub step_size: Option<Decimal>,
+}
+
+impl SymbolPrecision {
+    pub fn format_price(&self, price: f64) -> String {
+        format_with_increment(price, self.tick_size)
+    }
+
+    pub fn format_qty(&self, qty: f64) -> String {
+        format_with_increment(qty, self.step_size)
+    }
+}
+
+fn format_with_increment(value: f64, increment: Option<Decimal>) -> String {
+    match increment.filter(|inc| !inc.is_zero()) {
+        Some(inc) => format!("{:.*}", inc.precision() as usize, value),
+        None => format_significant(value, 8),
+    }
+}
+
+/// Formats with `digits` significant digits and trims trailing zeros, so `0.000012345678`
+/// stays readable instead of collapsing to `0.00`.
+pub fn format_significant(value: f64, digits: u32) -> String {
+    if !value.is_finite() || value == 0.0 {
+        return format!("{value}");
+    }
+    let magnitude = value.abs().log10().floor() as i32;
+    let decimals = (digits as i32 - 1 - magnitude).max(0) as usize;
+    let formatted = format!("{:.*}", decimals, value);
+    if formatted.contains('.') {
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    } else {
+        formatted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(raw: &str) -> Decimal {
+        raw.parse().expect("valid decimal")
+    }
+
+    #[test]
+    fn parses_and_displays_exact_digits() {
+        assert_eq!(d("0.00001234").to_string(), "0.00001234");
+        assert_eq!(d("43000.50").to_string(), "43000.50");
+        assert_eq!(d("-0.0100").to_string(), "-0.0100");
+        assert_eq!(d("7").to_string(), "7");
+        assert_eq!(d(".5").to_string(), "0.5");
+        assert!((d("0.00001234").to_f64() - 0.00001234).abs() < 1e-15);
+        assert!("1e-5".parse::<Decimal>().is_err());
+        assert!("".parse::<Decimal>().is_err());
+        assert!("1.2.3".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn compares_and_hashes_by_value() {
+        use std::collections::HashSet;
+        assert_eq!(d("1.50"), d("1.5"));
+        assert!(d("0.0001") < d("0.001"));
+        assert!(d("-2") < d("1.99"));
+        let set: HashSet<Decimal> = [d("100.00"), d("100"), d("100.0")].into_iter().collect();
+        assert_eq!(set.len(), 1);
+        assert_eq!(d("0.00100000").precision(), 3);
+    }
+
+    #[test]
+    fn serde_round_trips_as_string() {
+        let parsed: Vec<Decimal> = serde_json::from_str(r#"["0.00000123", 42, 1.25]"#).unwrap();
+        assert_eq!(parsed[0].to_string(), "0.00000123");
+        assert_eq!(parsed[1], d("42"));
+        assert_eq!(parsed[2], d("1.25"));
+        assert_eq!(serde_json::to_string(&parsed[0]).unwrap(), "\"0.00000123\"");
+    }
+
+    #[test]
+    fn formats_with_symbol_precision() {
+        let pepe = SymbolPrecision {
+            tick_size: Some(d("0.00000001")),
+            step_size: Some(d("1.00")),
+        };
+        assert_eq!(pepe.format_price(0.0000012345), "0.00000123");
+        assert_eq!(pepe.format_qty(1_234_567.4), "1234567");
+        let unknown = SymbolPrecision::default();
+        assert_eq!(unknown.format_price(0.000012345678), "0.000012345678");
+        assert_eq!(unknown.format_price(43000.5), "43000.5");
+    }
+}
@@ -0,0 +1,192 @@
+use std::borrow::Cow;
+
+use serde::Deserialize;
+use serde_json::value::RawValue;
+
+use crate::binance::AggTrade;
+use crate::binance_depth::DepthUpdate;
+use crate::binance_funding::FundingRateUpdate;
+use crate::binance_kline::KlineEvent;
+use crate::correlation::model::{NewsEvent, parse_news_event};
+use crate::shard::MarketMessage;
+use crate::universe::{MINI_TICKER_STREAM, MiniTicker};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamKind {
+    AggTrade,
+    Depth,
+    Kline,
+    Funding,
+    News,
+    /// All-market `!miniTicker@arr` batches.
+    MiniTickers,
+}
+
+/// Classifies a combined-stream name such as `btcusdt@depth@100ms`.
+pub fn classify_stream(stream: &str) -> Option<StreamKind> {
+    if stream == MINI_TICKER_STREAM {
+        return Some(StreamKind::MiniTickers);
+    }
+    let (_, channel) = stream.split_once('@')?;
+    if channel.starts_with("aggTrade") {
+        Some(StreamKind::AggTrade)
+    } else if channel.starts_with("depth") {
+        Some(StreamKind::Depth)
+    } else if channel.starts_with("kline_") {
+        Some(StreamKind::Kline)
+    } else if channel.starts_with("markPrice") {
+        Some(StreamKind::Funding)
+    } else {
+        None
+    }
+}
+
+/// Classifies a payload by its `e` event type.
+pub fn classify_event_type(event_type: &str) -> Option<StreamKind> {
+    match event_type {
+        "aggTrade" => Some(StreamKind::AggTrade),
+        "depthUpdate" => Some(StreamKind::Depth),
+        "kline" => Some(StreamKind::Kline),
+        "markPriceUpdate" => Some(StreamKind::Funding),
+        e if e.eq_ignore_ascii_case("news") => Some(StreamKind::News),
+        _ => None,
+    }
+}
+
+#[derive(Debug)]
+pub enum StreamMessage {
+    Market(MarketMessage),
+    News(NewsEvent),
+    MiniTickers(Vec<MiniTicker>),
+    /// Valid JSON that no handler recognises (or whose data did not match its stream).
+    Unhandled,
+    /// Not JSON at all.
+    Invalid(String),
+}
+
+#[derive(Deserialize)]
+struct Envelope<'a> {
+    #[serde(borrow, default)]
+    stream: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    data: Option<&'a RawValue>,
+    #[serde(borrow, default)]
+    e: Option<Cow<'a, str>>,
+}
+
+#[derive(Deserialize)]
+struct EventTag<'a> {
+    #[serde(borrow, default)]
+    e: Option<Cow<'a, str>>,
+}
+
+/// Classifies a frame once and deserializes its data straight into the matching typed struct.
+///
+/// The envelope and the stream/event names are borrowed from the frame; only the selected
+/// payload is materialised, since it is handed off to a shard worker.
+pub fn dispatch_message(msg: &str) -> StreamMessage {
+    let envelope: Envelope = match serde_json::from_str(msg) {
+        Ok(envelope) => envelope,
+        Err(err) => return StreamMessage::Invalid(err.to_string()),
+    };
+
+    // Unwrapped payloads (e.g. news pushed without a combined-stream envelope) are their own data.
+    let data = envelope.data.map(RawValue::get).unwrap_or(msg);
+    let kind = envelope
+        .stream
+        .as_deref()
+        .and_then(classify_stream)
+        .or_else(|| {
+            let tag = match envelope.data {
+                Some(raw) => serde_json::from_str::<EventTag>(raw.get()).ok()?.e,
+                None => envelope.e,
+            };
+            tag.as_deref().and_then(classify_event_type)
+        });
+
+    let Some(kind) = kind else {
+        return StreamMessage::Unhandled;
+    };
+
+    let parsed = match kind {
+        StreamKind::AggTrade => serde_json::from_str::<AggTrade>(data)
+            .ok()
+            .map(|agg| StreamMessage::Market(MarketMessage::AggTrade(agg))),
+        StreamKind::Depth => serde_json::from_str::<DepthUpdate>(data)
+            .ok()
+            .map(|depth| StreamMessage::Market(MarketMessage::Depth(depth))),
+        StreamKind::Kline => serde_json::from_str::<KlineEvent>(data)
+            .ok()
+            .map(|kline| StreamMessage::Market(MarketMessage::Kline(Box::new(kline)))),
+        StreamKind::Funding => serde_json::from_str::<FundingRateUpdate>(data)
+            .ok()
+            .map(|funding| StreamMessage::Market(MarketMessage::Funding(funding))),
+        StreamKind::News => parse_news_event(data).map(StreamMessage::News),
+        StreamKind::MiniTickers => serde_json::from_str::<Vec<MiniTicker>>(data)
+            .ok()
+            .map(StreamMessage::MiniTickers),
+    };
+
+    parsed.unwrap_or(StreamMessage::Unhandled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dispatches_by_stream_name() {
+        let agg = r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1,"s":"BTCUSDT","p":"43000.5","q":"0.12","T":2,"m":true}}"#;
+        assert!(matches!(
+            dispatch_message(agg),
+            StreamMessage::Market(MarketMessage::AggTrade(ref a)) if a.p.to_string() == "43000.5" && a.m
+        ));
+
+        let depth = r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":5,"s":"BTCUSDT","U":1,"u":2,"b":[["1.0","2.0"]],"a":[]}}"#;
+        assert!(matches!(
+            dispatch_message(depth),
+            StreamMessage::Market(MarketMessage::Depth(ref d)) if d.bids.len() == 1
+        ));
+
+        let funding = r#"{"stream":"btcusdt@markPrice@1s","data":{"e":"markPriceUpdate","E":5,"s":"BTCUSDT","p":"1","r":"0.0001","T":9}}"#;
+        assert!(matches!(
+            dispatch_message(funding),
+            StreamMessage::Market(MarketMessage::Funding(ref f)) if f.funding_rate.to_string() == "0.0001"
+        ));
+    }
+
+    #[test]
+    fn falls_back_to_event_type_and_handles_news() {
+        let news = r#"{"e":"news","s":"BTCUSDT","headline":"ETF approved","T":1710000000000}"#;
+        assert!(matches!(
+            dispatch_message(news),
+            StreamMessage::News(ref n) if n.symbol == "btcusdt"
+        ));
+
+        let wrapped = r#"{"stream":"custom-news","data":{"e":"NEWS","symbol":"ethusdt","title":"Upgrade","ts":1}}"#;
+        assert!(matches!(dispatch_message(wrapped), StreamMessage::News(_)));
+
+        let tickers = r#"{"stream":"!miniTicker@arr","data":[{"e":"24hrMiniTicker","E":1,"s":"SOLUSDT","c":"150.10","o":"140","h":"151","l":"139","v":"1000","q":"150000.5"}]}"#;
+        assert!(matches!(
+            dispatch_message(tickers),
+            StreamMessage::MiniTickers(ref t) if t.len() == 1 && t[0].symbol == "SOLUSDT"
+        ));
+    }
+
+    #[test]
+    fn reports_unhandled_and_invalid_frames() {
+        assert!(matches!(
+            dispatch_message(r#"{"result":null,"id":1}"#),
+            StreamMessage::Unhandled
+        ));
+        // Stream says aggTrade but the payload does not match.
+        assert!(matches!(
+            dispatch_message(r#"{"stream":"btcusdt@aggTrade","data":{"x":1}}"#),
+            StreamMessage::Unhandled
+        ));
+        assert!(matches!(
+            dispatch_message("not json"),
+            StreamMessage::Invalid(_)
+        ));
+    }
+}
@@ -12,9 +12,11 @@ where
         Err(err) => {
             let snippet: String = msg.chars().take(180).collect();
             let suffix = if msg.chars().count() > 180 { "..." } else { "" };
-            eprintln!(
-                "[parse_combined_data:{}] invalid JSON: {} payload='{}{}'",
-                std::any::type_name::<T>(),
+            log::warn!(
+                target: "stream",
+                rate_limited = true,
+                payload_type = std::any::type_name::<T>();
+                "invalid JSON: {} payload='{}{}'",
                 err,
                 snippet,
                 suffix
@@ -1,13 +1,33 @@
+pub mod backtest;
 pub mod binance;
 pub mod binance_depth;
 pub mod binance_funding;
 pub mod binance_kline;
+pub mod breadth;
 pub mod config;
+pub mod conviction;
 pub mod correlation;
+pub mod cross_asset;
+pub mod decimal;
+pub mod dispatch;
 pub mod json_helpers;
+pub mod logging;
 pub mod notifiers;
+pub mod outcomes;
+pub mod paper;
 pub mod refactor;
+pub mod rules;
+pub mod shard;
+pub mod shutdown;
+pub mod snapshot;
+pub mod spike_window;
+pub mod symbols;
+pub mod threshold;
 pub mod time_helpers;
+pub mod trade_flow;
+pub mod universe;
+pub mod volatility;
+pub mod whale;
 pub mod ws_helpers;
 
 pub mod news;
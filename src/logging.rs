@@ -0,0 +1,541 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::io::Write as _;
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::{SecondsFormat, Utc};
+use log::kv::{Error as KvError, Key, Value, VisitSource};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde_json::{Map, Value as JsonValue, json};
+use warp::Filter;
+use warp::http::StatusCode;
+
+use crate::config::{LogConfig, LogFormat};
+
+/// Per-target level filter, e.g. `info,news=debug,notify=warn`.
+///
+/// A directive matches its target and every target below it (`notify` covers
+/// `notify/telegram`); the longest matching directive wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFilter {
+    default: LevelFilter,
+    /// Longest target first.
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self {
+            default: LevelFilter::Info,
+            directives: Vec::new(),
+        }
+    }
+}
+
+impl LogFilter {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = Self::default();
+        for part in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            match part.split_once('=') {
+                Some((target, level)) => {
+                    let target = target.trim();
+                    if target.is_empty() {
+                        return Err(format!("missing target in '{part}'"));
+                    }
+                    filter
+                        .directives
+                        .push((target.to_string(), parse_level(level)?));
+                }
+                None => filter.default = parse_level(part)?,
+            }
+        }
+        filter
+            .directives
+            .sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        Ok(filter)
+    }
+
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .find(|(prefix, _)| target_matches(target, prefix))
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+impl fmt::Display for LogFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.default.as_str().to_lowercase())?;
+        for (target, level) in self.directives.iter().rev() {
+            write!(f, ",{}={}", target, level.as_str().to_lowercase())?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_level(raw: &str) -> Result<LevelFilter, String> {
+    raw.trim()
+        .parse()
+        .map_err(|_| format!("invalid log level '{}'", raw.trim()))
+}
+
+fn target_matches(target: &str, prefix: &str) -> bool {
+    target
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/') || rest.starts_with("::"))
+}
+
+/// Fields attached to every record logged inside `with_context`.
+#[derive(Debug, Clone, Default)]
+pub struct LogContext {
+    pub symbol: Option<String>,
+    pub stream: Option<&'static str>,
+    pub signal_type: Option<String>,
+}
+
+impl LogContext {
+    pub fn market(symbol: &str, stream: &'static str) -> Self {
+        Self {
+            symbol: Some(symbol.to_lowercase()),
+            stream: Some(stream),
+            signal_type: None,
+        }
+    }
+
+    fn merged(&self, inner: LogContext) -> Self {
+        Self {
+            symbol: inner.symbol.or_else(|| self.symbol.clone()),
+            stream: inner.stream.or(self.stream),
+            signal_type: inner.signal_type.or_else(|| self.signal_type.clone()),
+        }
+    }
+
+    fn fields(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("symbol", self.symbol.as_deref()),
+            ("stream", self.stream),
+            ("signal_type", self.signal_type.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key, value)))
+    }
+}
+
+tokio::task_local! {
+    static CONTEXT: LogContext;
+}
+
+/// Runs `future` with `context` added to its log records. Nested scopes keep the outer
+/// fields they do not override. Tasks spawned inside do not inherit the context.
+pub async fn with_context<F: Future>(context: LogContext, future: F) -> F::Output {
+    let context = CONTEXT
+        .try_with(|outer| outer.merged(context.clone()))
+        .unwrap_or(context);
+    CONTEXT.scope(context, future).await
+}
+
+/// Logs a signal line that is also broadcast to websocket clients.
+pub fn signal(signal_type: &str, line: &str) {
+    log::info!(target: "signal", signal_type = signal_type; "{line}");
+}
+
+/// Key that opts a call site into rate limiting, e.g.
+/// `log::warn!(target: "stream", rate_limited = true; "invalid JSON frame: {err}")`.
+pub const RATE_LIMITED: &str = "rate_limited";
+
+fn is_rate_limited(record: &Record) -> bool {
+    record
+        .key_values()
+        .get(Key::from_str(RATE_LIMITED))
+        .is_some()
+}
+
+/// Lets the first record from an opted-in call site through per window and counts the rest.
+#[derive(Debug)]
+struct RateLimiter {
+    window: Duration,
+    sites: Mutex<HashMap<(String, u32), (Instant, u64)>>,
+}
+
+impl RateLimiter {
+    /// `Some(suppressed since the last emitted record)` to log, `None` to drop.
+    fn check(&self, site: (String, u32), now: Instant) -> Option<u64> {
+        let mut sites = self.sites.lock().expect("log rate limiter poisoned");
+        match sites.get_mut(&site) {
+            Some((since, suppressed)) if now.duration_since(*since) < self.window => {
+                *suppressed += 1;
+                None
+            }
+            Some((since, suppressed)) => {
+                *since = now;
+                Some(std::mem::take(suppressed))
+            }
+            None => {
+                sites.insert(site, (now, 0));
+                Some(0)
+            }
+        }
+    }
+}
+
+struct Logger {
+    filter: RwLock<LogFilter>,
+    format: LogFormat,
+    limiter: Option<RateLimiter>,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Installs the process logger. Later calls keep the first configuration.
+pub fn init(config: &LogConfig) {
+    let filter = LogFilter::parse(&config.filter).unwrap_or_else(|err| {
+        eprintln!("[logging] {err}; LOG_FILTER ignored, logging at info");
+        LogFilter::default()
+    });
+    let max_level = filter.max_level();
+    let logger = LOGGER.get_or_init(|| Logger {
+        filter: RwLock::new(filter),
+        format: config.format,
+        limiter: (config.rate_limit_secs > 0).then(|| RateLimiter {
+            window: Duration::from_secs(config.rate_limit_secs),
+            sites: Mutex::default(),
+        }),
+    });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// Replaces the level filter of the running logger.
+pub fn set_filter(spec: &str) -> Result<LogFilter, String> {
+    let filter = LogFilter::parse(spec)?;
+    let logger = LOGGER.get().ok_or("logger not initialised")?;
+    log::set_max_level(filter.max_level());
+    *logger.filter.write().expect("log filter poisoned") = filter.clone();
+    Ok(filter)
+}
+
+pub fn current_filter() -> Option<LogFilter> {
+    LOGGER
+        .get()
+        .map(|logger| logger.filter.read().expect("log filter poisoned").clone())
+}
+
+/// `GET /log/filter` shows the level filter, `PUT /log/filter` with a filter as the body
+/// replaces it. Both need `Authorization: Bearer <LOG_ADMIN_TOKEN>`; without a token the
+/// route is not served at all.
+pub fn log_filter_route(
+    admin_token: Option<String>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let expected = admin_token.map(|token| format!("Bearer {token}"));
+    let authorized = warp::path!("log" / "filter")
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(move |header: Option<String>| {
+            let expected = expected.clone();
+            async move {
+                match expected {
+                    Some(expected) => Ok(header.as_deref() == Some(expected.as_str())),
+                    None => Err(warp::reject::not_found()),
+                }
+            }
+        });
+    let get = authorized.clone().and(warp::get()).map(|authorized: bool| {
+        if !authorized {
+            return unauthorized();
+        }
+        let filter = current_filter().map(|filter| filter.to_string());
+        warp::reply::with_status(
+            warp::reply::json(&json!({ "filter": filter })),
+            StatusCode::OK,
+        )
+    });
+    let put = authorized
+        .and(warp::put())
+        .and(warp::body::content_length_limit(4 * 1024))
+        .and(warp::body::bytes())
+        .map(|authorized: bool, body: warp::hyper::body::Bytes| {
+            if !authorized {
+                return unauthorized();
+            }
+            replace_filter(body)
+        });
+    get.or(put)
+}
+
+fn unauthorized() -> warp::reply::WithStatus<warp::reply::Json> {
+    warp::reply::with_status(
+        warp::reply::json(&json!({ "error": "missing or wrong admin token" })),
+        StatusCode::UNAUTHORIZED,
+    )
+}
+
+fn replace_filter(body: impl AsRef<[u8]>) -> warp::reply::WithStatus<warp::reply::Json> {
+    let spec = String::from_utf8_lossy(body.as_ref());
+    match set_filter(&spec) {
+        Ok(filter) => {
+            log::info!(target: "logging", "filter set to {filter}");
+            warp::reply::with_status(
+                warp::reply::json(&json!({ "filter": filter.to_string() })),
+                StatusCode::OK,
+            )
+        }
+        Err(err) => warp::reply::with_status(
+            warp::reply::json(&json!({ "error": err })),
+            StatusCode::BAD_REQUEST,
+        ),
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        let filter = self.filter.read().expect("log filter poisoned");
+        metadata.level() <= filter.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let suppressed = match &self.limiter {
+            Some(limiter) if is_rate_limited(record) => {
+                let site = (
+                    record.file().unwrap_or(record.target()).to_string(),
+                    record.line().unwrap_or(0),
+                );
+                match limiter.check(site, Instant::now()) {
+                    Some(suppressed) => suppressed,
+                    None => return,
+                }
+            }
+            _ => 0,
+        };
+
+        let mut fields: Vec<(String, JsonValue)> = Vec::new();
+        let _ = CONTEXT.try_with(|context| {
+            fields.extend(
+                context
+                    .fields()
+                    .map(|(key, value)| (key.to_string(), JsonValue::from(value))),
+            );
+        });
+        let _ = record.key_values().visit(&mut FieldCollector(&mut fields));
+        if suppressed > 0 {
+            fields.push(("suppressed".to_string(), JsonValue::from(suppressed)));
+        }
+
+        let line = match self.format {
+            LogFormat::Text => format_text(record, &fields),
+            LogFormat::Json => format_json(record, fields),
+        };
+        if record.level() <= Level::Warn {
+            let _ = writeln!(std::io::stderr().lock(), "{line}");
+        } else {
+            let _ = writeln!(std::io::stdout().lock(), "{line}");
+        }
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stdout().flush();
+        let _ = std::io::stderr().flush();
+    }
+}
+
+struct FieldCollector<'a>(&'a mut Vec<(String, JsonValue)>);
+
+impl<'kvs> VisitSource<'kvs> for FieldCollector<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), KvError> {
+        if key.as_str() == RATE_LIMITED {
+            return Ok(());
+        }
+        let value = if let Some(v) = value.to_bool() {
+            JsonValue::from(v)
+        } else if let Some(v) = value.to_i64() {
+            JsonValue::from(v)
+        } else if let Some(v) = value.to_u64() {
+            JsonValue::from(v)
+        } else if let Some(v) = value.to_f64().filter(|v| v.is_finite()) {
+            JsonValue::from(v)
+        } else {
+            JsonValue::from(value.to_string())
+        };
+        self.0.retain(|(existing, _)| existing != key.as_str());
+        self.0.push((key.as_str().to_string(), value));
+        Ok(())
+    }
+}
+
+fn timestamp() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn format_text(record: &Record, fields: &[(String, JsonValue)]) -> String {
+    let mut line = format!(
+        "{} {:<5} [{}] {}",
+        timestamp(),
+        record.level(),
+        record.target(),
+        record.args()
+    );
+    for (key, value) in fields {
+        match value {
+            JsonValue::String(text) => line.push_str(&format!(" {key}={text}")),
+            other => line.push_str(&format!(" {key}={other}")),
+        }
+    }
+    line
+}
+
+fn format_json(record: &Record, fields: Vec<(String, JsonValue)>) -> String {
+    let mut object = Map::new();
+    object.insert("ts".to_string(), JsonValue::from(timestamp()));
+    object.insert(
+        "level".to_string(),
+        JsonValue::from(record.level().as_str()),
+    );
+    object.insert("target".to_string(), JsonValue::from(record.target()));
+    object.insert(
+        "msg".to_string(),
+        JsonValue::from(record.args().to_string()),
+    );
+    for (key, value) in fields {
+        object.entry(key).or_insert(value);
+    }
+    JsonValue::Object(object).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_picks_most_specific_directive() {
+        let filter = LogFilter::parse("warn, notify=info ,notify/telegram=debug,stream=off")
+            .expect("valid filter");
+        assert_eq!(filter.level_for("news"), LevelFilter::Warn);
+        assert_eq!(filter.level_for("notify/queue"), LevelFilter::Info);
+        assert_eq!(filter.level_for("notify/telegram"), LevelFilter::Debug);
+        assert_eq!(filter.level_for("notifyx"), LevelFilter::Warn);
+        assert_eq!(filter.level_for("stream"), LevelFilter::Off);
+        assert_eq!(filter.max_level(), LevelFilter::Debug);
+        assert_eq!(
+            filter.to_string(),
+            "warn,stream=off,notify=info,notify/telegram=debug"
+        );
+        assert!(LogFilter::parse("info,news=loud").is_err());
+    }
+
+    #[test]
+    fn only_opted_in_call_sites_are_rate_limited() {
+        let args = format_args!("invalid JSON frame");
+        let opted_in = [(RATE_LIMITED, true), ("payload_type", false)];
+        let record = Record::builder()
+            .level(Level::Warn)
+            .args(args)
+            .key_values(&opted_in)
+            .build();
+        assert!(is_rate_limited(&record));
+        let mut fields = Vec::new();
+        let _ = record.key_values().visit(&mut FieldCollector(&mut fields));
+        assert_eq!(
+            fields,
+            vec![("payload_type".to_string(), JsonValue::from(false))]
+        );
+
+        let args = format_args!("telegram send failed");
+        let record = Record::builder().level(Level::Error).args(args).build();
+        assert!(!is_rate_limited(&record));
+    }
+
+    #[test]
+    fn rate_limiter_reports_suppressed_count_after_window() {
+        let limiter = RateLimiter {
+            window: Duration::from_secs(10),
+            sites: Mutex::default(),
+        };
+        let site = || ("src/dispatch.rs".to_string(), 42);
+        let start = Instant::now();
+        assert_eq!(limiter.check(site(), start), Some(0));
+        assert_eq!(limiter.check(site(), start + Duration::from_secs(1)), None);
+        assert_eq!(limiter.check(site(), start + Duration::from_secs(2)), None);
+        assert_eq!(
+            limiter.check(("src/news.rs".to_string(), 7), start),
+            Some(0)
+        );
+        assert_eq!(
+            limiter.check(site(), start + Duration::from_secs(11)),
+            Some(2)
+        );
+    }
+
+    #[tokio::test]
+    async fn log_filter_route_needs_the_admin_token() {
+        let request = |method: &str, token: Option<&str>| {
+            let mut request = warp::test::request()
+                .method(method)
+                .path("/log/filter")
+                .body("off");
+            if let Some(token) = token {
+                request = request.header("authorization", format!("Bearer {token}"));
+            }
+            request
+        };
+
+        let disabled = log_filter_route(None);
+        assert_eq!(
+            request("GET", Some("secret"))
+                .reply(&disabled)
+                .await
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+
+        let route = log_filter_route(Some("secret".to_string()));
+        for (method, token) in [("GET", None), ("PUT", None), ("PUT", Some("guess"))] {
+            assert_eq!(
+                request(method, token).reply(&route).await.status(),
+                StatusCode::UNAUTHORIZED,
+                "{method} {token:?}"
+            );
+        }
+        assert_eq!(
+            request("GET", Some("secret")).reply(&route).await.status(),
+            StatusCode::OK
+        );
+    }
+
+    #[test]
+    fn json_lines_carry_context_and_key_values() {
+        let fields = vec![
+            ("symbol".to_string(), JsonValue::from("btcusdt")),
+            ("suppressed".to_string(), JsonValue::from(3u64)),
+        ];
+        let line = format_json(
+            &Record::builder()
+                .level(Level::Warn)
+                .target("stream")
+                .args(format_args!("invalid JSON frame"))
+                .build(),
+            fields,
+        );
+        let parsed: JsonValue = serde_json::from_str(&line).expect("json line");
+        assert_eq!(parsed["level"], "WARN");
+        assert_eq!(parsed["target"], "stream");
+        assert_eq!(parsed["msg"], "invalid JSON frame");
+        assert_eq!(parsed["symbol"], "btcusdt");
+        assert_eq!(parsed["suppressed"], 3);
+    }
+}
@@ -3,35 +3,85 @@ use feeder_service::binance::*;
 use feeder_service::binance_depth::*;
 use feeder_service::binance_funding::*;
 use feeder_service::binance_kline::*;
-use feeder_service::config::{Config, NewsConfig};
+use feeder_service::breadth::{BreadthTracker, MarketBreadth, format_breadth_log};
+use feeder_service::config::{Config, FlowConfig, LogConfig, NewsConfig, SymbolConfig};
+use feeder_service::conviction::{ConvictionScorer, emit_conviction};
 use feeder_service::correlation::engine::CorrelationEngine;
-use feeder_service::correlation::model::{MarketEvent, MarketEventKind, parse_news_event};
+use feeder_service::correlation::model::{MarketEvent, MarketEventKind, NewsEvent};
+use feeder_service::cross_asset::{
+    CrossAssetTracker, MoveEvent, format_decomposition_log, format_matrix_log,
+};
+use feeder_service::decimal::Decimal;
+use feeder_service::dispatch::{StreamMessage, dispatch_message};
+use feeder_service::logging::{self, LogContext, log_filter_route};
 use feeder_service::news::correlation::CorrelationService;
 use feeder_service::news::providers::fetch_all_news;
 use feeder_service::news::store::NewsStore;
-use feeder_service::news::tagging::tag_symbols;
+use feeder_service::news::tagging::SymbolTagger;
 use feeder_service::notify::{
-    NotificationFanout, build_signal_notification, telegram::TelegramNotifier,
+    NotificationFanout, build_signal_notification, queue::format_queue_metrics,
+    telegram::TelegramNotifier,
+};
+use feeder_service::outcomes::{
+    HORIZONS, OutcomeStore, OutcomeTracker, TrackedSignal, emit_outcomes, outcomes_route,
+    spawn_outcome_report,
+};
+use feeder_service::paper::{PaperTrader, emit_paper_events, emit_paper_report};
+use feeder_service::rules::{
+    RuleEngine, SharedRules, dispatch_rule_matches, init_rules, spawn_rule_reload,
+};
+use feeder_service::shard::{self, MarketMessage, ShardReceiver, ShardRouter, format_shard_stats};
+use feeder_service::shutdown::{
+    Shutdown, ShutdownCause, ShutdownReport, format_shutdown_log, wait_for_signal,
+};
+use feeder_service::snapshot::{
+    StateStore, SymbolState, collect_symbol_states, load_state, restore_big_move_detectors,
+    spawn_state_writer,
+};
+use feeder_service::threshold::AdaptiveThresholds;
+use feeder_service::universe::{
+    MINI_TICKER_STREAM, SharedSymbolConfigs, StreamSet, UniverseChange, UniverseRanker,
+    format_universe_log, subscription_frame,
+};
+use feeder_service::volatility::{VolatilityTracker, format_vol_regime_log};
+use feeder_service::whale::{
+    BookSide, IcebergDetector, TakerSide, WhaleClusterDetector, format_iceberg_log,
+    format_whale_log,
 };
 use feeder_service::ws_helpers::*;
-use futures_util::StreamExt;
+use futures_util::{Sink, SinkExt, StreamExt};
 use local_ip_address::local_ip;
 use serde_json::json;
 use std::collections::HashMap;
+use std::io::Write as _;
 use std::sync::Arc;
-use tokio::sync::broadcast;
-use tokio::time::{Duration, interval};
+use std::time::Instant;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{Duration, interval, timeout_at};
 use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
 use warp::Filter;
 
-use feeder_service::refactor::big_move_detector::{BigMoveDetector, BigMoveSignal, DepthSnapshot};
+use feeder_service::refactor::big_move_detector::{
+    BigMoveDetector, BigMoveDetectorState, DepthSnapshot, format_big_move_alert,
+};
+use feeder_service::spike_window::{SpikeWindowDetector, format_spike_log, format_window_label};
+use feeder_service::symbols::{SymbolRegistry, init_registry};
 use feeder_service::time_helpers::format_daily_time_resistance_log;
+use feeder_service::trade_flow::{
+    FlowSnapshot, TradeFlowTracker, format_flow_imbalance_log, format_flow_log,
+};
 
 #[tokio::main]
 async fn main() {
     dotenv::dotenv().ok();
+    let log_config = LogConfig::load();
+    logging::init(&log_config);
 
-    let config = Config::load();
+    let mut config = Config::load();
+    let registry = init_registry(&mut config).await;
     if config.symbols.is_empty() {
         return;
     }
@@ -44,16 +94,8 @@ async fn main() {
         .map(|v| v == "true" || v == "1")
         .unwrap_or(false); // inactive by default in production
 
-    // Maps and state
-    let mut config_map: HashMap<String, _> = HashMap::new();
-    let mut last_prices: HashMap<String, f64> = HashMap::new();
-    let mut big_move_detectors: HashMap<String, BigMoveDetector> = HashMap::new();
-    let mut correlation_engine = CorrelationEngine::new(
-        config.corr_min_move_pct,
-        config.corr_max_lag_seconds,
-        config.corr_min_confidence,
-    );
-    let mut last_funding_alert_ms: HashMap<String, u64> = HashMap::new();
+    // Symbol configs shared by all shard workers (replaced copy-on-write by the universe)
+    let mut config_map: HashMap<String, SymbolConfig> = HashMap::new();
 
     // Symbol list (lowercase used later)
     let symbols: Vec<String> = config
@@ -62,20 +104,34 @@ async fn main() {
         .map(|cfg| cfg.symbol.clone())
         .collect();
 
+    log::info!(
+        target: "config",
+        "spike windows: {}",
+        config
+            .spike_windows_secs
+            .iter()
+            .map(|secs| format_window_label(secs * 1_000))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
     for cfg in &config.symbols {
-        println!(
-            "Symbol: {} => Big Trade Qty: {}, Spike %: {}",
+        log::info!(
+            target: "config",
+            "Symbol: {} => Big Trade Qty: {} ({}), Spike %: {}, Whale notional: {}, Big-move profiles: {}",
             cfg.symbol.to_uppercase(),
             cfg.big_trade_qty,
-            cfg.spike_pct
+            cfg.big_trade_rule.as_label(),
+            cfg.spike_pct,
+            cfg.whale_notional,
+            cfg.big_move_profiles
+                .iter()
+                .map(|p| p.as_label())
+                .collect::<Vec<_>>()
+                .join(", ")
         );
         config_map.insert(cfg.symbol.clone(), cfg.clone());
-        // instantiate detector for each symbol (preserve previous behaviour if used later)
-        big_move_detectors.insert(
-            cfg.symbol.to_lowercase(),
-            BigMoveDetector::new(5, 75.0, 0.0, 3),
-        );
     }
+    let config_map = SharedSymbolConfigs::new(config_map);
 
     let (tx, _rx) = broadcast::channel(config.broadcast_capacity);
     let telegram_notifier = config
@@ -83,26 +139,101 @@ async fn main() {
         .is_ready()
         .then(|| TelegramNotifier::new(config.telegram.clone()));
     if config.telegram.enabled && telegram_notifier.is_none() {
-        eprintln!(
-            "[notify/telegram] TELEGRAM enabled but missing TELEGRAM_BOT_TOKEN or TELEGRAM_CHAT_ID; Telegram fanout disabled"
+        log::warn!(
+            target: "notify/telegram",
+            "TELEGRAM enabled but missing TELEGRAM_BOT_TOKEN or TELEGRAM_CHAT_ID; Telegram fanout disabled"
+        );
+    }
+    let notifier = Arc::new(
+        NotificationFanout::new(telegram_notifier)
+            .with_digest(config.digest.clone(), &config.telegram)
+            .with_delivery_queue(&config.delivery),
+    );
+    if notifier.delivery_metrics().is_some() {
+        log::info!(
+            target: "notify/queue",
+            "enabled: capacity={} workers={} overflow={:?}",
+            config.delivery.queue_capacity, config.delivery.workers, config.delivery.overflow
+        );
+        if config.delivery.metrics_interval_secs > 0 {
+            let notifier = notifier.clone();
+            let period = Duration::from_secs(config.delivery.metrics_interval_secs);
+            tokio::spawn(async move {
+                let mut ticker = interval(period);
+                ticker.tick().await;
+                loop {
+                    ticker.tick().await;
+                    if let Some(metrics) = notifier.delivery_metrics() {
+                        log::info!(target: "notify/queue", "{}", format_queue_metrics(&metrics));
+                    }
+                }
+            });
+        }
+    }
+    if config.digest.enabled {
+        log::info!(
+            target: "notify/digest",
+            "enabled: window={}s immediate_limit={} escalation_after={}s escalation_chat={}",
+            config.digest.window_secs,
+            config.digest.immediate_limit,
+            config.digest.escalation_after_secs,
+            config.digest.escalation_chat_id.is_some()
         );
+        let notifier = notifier.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(1));
+            loop {
+                ticker.tick().await;
+                notifier.flush_due(&tx, Utc::now().timestamp_millis()).await;
+            }
+        });
     }
-    let notifier = Arc::new(NotificationFanout::new(telegram_notifier));
-    let correlation_service = NewsStore::new(config.news.db_path.clone());
-    let correlation_service = match correlation_service.init() {
-        Ok(()) => Some(CorrelationService::from_env(correlation_service)),
+    // Shared with the ingest loop so news writes invalidate the correlation cache.
+    let news_store = NewsStore::new(config.news.db_path.clone());
+    let correlation_service = match news_store.init() {
+        Ok(()) => Some(CorrelationService::from_env(news_store.clone())),
         Err(err) => {
-            eprintln!("[news] correlation disabled, failed to init db: {err}");
+            log::warn!(target: "news", "correlation disabled, failed to init db: {err}");
             None
         }
     };
 
-    // Spawn Warp server for websocket clients
+    let outcome_store = if config.outcomes.enabled {
+        match OutcomeStore::open(&config.outcomes.db_path) {
+            Ok(store) => {
+                log::info!(
+                    target: "outcomes",
+                    "tracking big_move/quant4h/funding returns at {} into {}",
+                    HORIZONS
+                        .iter()
+                        .map(|(label, _)| *label)
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    config.outcomes.db_path
+                );
+                Some(store)
+            }
+            Err(err) => {
+                log::warn!(target: "outcomes", "tracking disabled, failed to open db: {err}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Stops the warp server, websocket clients and the news loop once the pipeline drained.
+    let shutdown = Shutdown::new();
+
+    // Spawn Warp server for websocket clients and the outcome stats API
     let ws_route = warp::path("aggTrade").and(warp::ws()).map({
         let tx = tx.clone();
+        let shutdown = shutdown.clone();
         move |ws: warp::ws::Ws| {
             let tx_inner = tx.clone();
-            ws.on_upgrade(move |socket| handle_client(socket, tx_inner))
+            let shutdown = shutdown.clone();
+            ws.on_upgrade(move |socket| handle_client_with_shutdown(socket, tx_inner, shutdown))
         }
     });
 
@@ -110,18 +241,21 @@ async fn main() {
         .map(|ip| ip.to_string())
         .unwrap_or_else(|_| "127.0.0.1".to_string());
 
-    println!(
+    log::info!(
+        target: "server",
         "WebSocket server running on ws://{}:{}/aggTrade",
         ip_display, config.port
     );
 
     if config.disable_depth_stream {
-        println!(
+        log::info!(
+            target: "config",
             "Depth stream DISABLED (DISABLE_DEPTH_STREAM raw value: {:?})",
             std::env::var("DISABLE_DEPTH_STREAM").unwrap_or_default()
         );
     } else {
-        println!(
+        log::info!(
+            target: "config",
             "Depth filters => min_qty: {}, min_notional: {}, min_pressure: {}",
             config.big_depth_min_qty,
             config.big_depth_min_notional,
@@ -129,43 +263,95 @@ async fn main() {
         );
     }
 
-    tokio::spawn(warp::serve(ws_route).run(([0, 0, 0, 0], config.port)));
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", config.port))
+        .await
+        .expect("Failed to bind websocket server port");
+    let server = tokio::spawn({
+        let shutdown = shutdown.clone();
+        warp::serve(
+            ws_route
+                .or(outcomes_route(outcome_store.clone()))
+                .or(log_filter_route(log_config.admin_token.clone())),
+        )
+        .incoming(listener)
+        .graceful(async move { shutdown.wait().await })
+        .run()
+    });
 
-    if config.news.enabled {
+    let news_loop = if config.news.enabled {
         let news_cfg = config.news.clone();
-        tokio::spawn(async move {
-            if let Err(err) = run_news_ingest_loop(news_cfg).await {
-                eprintln!("[news] ingest loop terminated: {err}");
+        let tagger = registry
+            .as_ref()
+            .map(|registry| SymbolTagger::from_registry(registry, &symbols))
+            .unwrap_or_default();
+        let shutdown = shutdown.clone();
+        Some(tokio::spawn(async move {
+            if let Err(err) = run_news_ingest_loop(news_cfg, news_store, tagger, shutdown).await {
+                log::error!(target: "news", "ingest loop terminated: {err}");
             }
-        });
+        }))
     } else {
-        println!("[news] ingestion is disabled (set ENABLE_NEWS_INGEST=true to enable)");
-    }
+        log::info!(target: "news", "ingestion is disabled (set ENABLE_NEWS_INGEST=true to enable)");
+        None
+    };
 
     // Build Binance streams: aggTrade for each symbol + diff depth streams (unless disabled)
-    let mut streams: Vec<String> = symbols.iter().map(|s| format!("{}@aggTrade", s)).collect();
-    if enable_depth {
-        streams.extend(build_diff_depth_streams(&symbols, 100));
-    } else {
-        println!("[INFO] Depth streams are disabled by feature flag.");
+    let stream_set = StreamSet {
+        depth: enable_depth,
+        kline: enable_kline_quant,
+        funding: config.enable_funding_rate,
+    };
+    let mut streams = stream_set.streams_for_all(&symbols);
+    if !enable_depth {
+        log::info!(target: "config", "Depth streams are disabled by feature flag.");
     }
 
-    if enable_kline_quant {
-        streams.extend(build_kline_streams(&symbols, "4h"));
-    } else {
-        println!(
-            "[INFO] Kline quant analysis is inactive (set ENABLE_KLINE_QUANT=true to enable)."
+    if !enable_kline_quant {
+        log::info!(
+            target: "config",
+            "Kline quant analysis is inactive (set ENABLE_KLINE_QUANT=true to enable)."
         );
     }
 
     if config.enable_funding_rate {
-        streams.extend(symbols.iter().map(|s| format!("{}@markPrice@1s", s)));
-        println!(
-            "[INFO] Funding rate detection enabled: threshold={:.3}% cooldown={}s",
+        log::info!(
+            target: "config",
+            "Funding rate detection enabled: threshold={:.3}% cooldown={}s",
             config.funding_rate_alert_pct, config.funding_rate_cooldown_secs
         );
     } else {
-        println!("[INFO] Funding rate detection is disabled.");
+        log::info!(target: "config", "Funding rate detection is disabled.");
+    }
+
+    let mut universe = config.universe.enabled.then(|| {
+        let ranker = UniverseRanker::new(&config.universe, &symbols);
+        match registry.as_ref() {
+            Some(registry) => ranker.with_registry(registry),
+            None => ranker,
+        }
+    });
+    let mut breadth = config.breadth.enabled.then(|| {
+        log::info!(
+            target: "breadth",
+            "market breadth over {} pairs every {}s, dominance pair {}",
+            config.breadth.quote_asset,
+            config.breadth.interval_secs,
+            config.breadth.dominance_symbol.to_uppercase()
+        );
+        BreadthTracker::new(&config.breadth)
+    });
+    if universe.is_some() || breadth.is_some() {
+        streams.push(MINI_TICKER_STREAM.to_string());
+    }
+    if universe.is_some() {
+        log::info!(
+            target: "universe",
+            "dynamic: top {} {} pairs by 24h quote volume, exit past rank {}, rebalance every {}s",
+            config.universe.top_n,
+            config.universe.quote_asset,
+            config.universe.exit_rank,
+            config.universe.rebalance_interval_secs
+        );
     }
 
     let daily_offset_hours = std::env::var("TIME_RESISTANCE_DAILY_UTC_OFFSET_HOURS")
@@ -187,9 +373,10 @@ async fn main() {
         reversal_window_minutes,
         astro_weight,
     ) {
-        println!("{}", line);
+        log::info!(target: "time_resistance", "{line}");
     } else {
-        eprintln!(
+        log::warn!(
+            target: "time_resistance",
             "[TIME_RESISTANCE][1D] unable to compute boundary | session_offset={:+} | window={}m | astro_weight={:.2}",
             daily_offset_hours, reversal_window_minutes, astro_weight
         );
@@ -197,22 +384,176 @@ async fn main() {
 
     if !config.news_streams.is_empty() {
         streams.extend(config.news_streams.iter().cloned());
-        println!("[INFO] Added extra news streams: {:?}", config.news_streams);
+        log::info!(target: "config", "Added extra news streams: {:?}", config.news_streams);
     }
 
     let url = format!(
         "wss://data-stream.binance.vision/stream?streams={}",
         streams.join("/")
     );
-    println!("Connecting to Binance: {}", url);
+    log::info!(target: "stream", "Connecting to Binance: {}", url);
+
+    let cross_asset = config.cross_asset.enabled.then(|| {
+        log::info!(
+            target: "cross_asset",
+            "correlations vs {} over {} x {}s bars, lead-lag up to {} bars",
+            config.cross_asset.reference_symbol.to_uppercase(),
+            config.cross_asset.window_bars,
+            config.cross_asset.bar_secs,
+            config.cross_asset.max_lag_bars
+        );
+        CrossAssetTracker::new(&config.cross_asset)
+    });
+
+    if config.volatility.enabled {
+        log::info!(
+            target: "volatility",
+            "regimes from {} x {}s bars vs {}-bar baseline, threshold scaling {}",
+            config.volatility.window_bars,
+            config.volatility.bar_secs,
+            config.volatility.baseline_bars,
+            if config.volatility.scale_thresholds {
+                "on"
+            } else {
+                "off"
+            }
+        );
+    }
+
+    // Detector state saved by the previous run, restored into the shards and merge stage.
+    let now_ms = Utc::now().timestamp_millis() as u64;
+    let mut restored = load_state(&config, now_ms).unwrap_or_default();
+    let state_store = config.state.enabled.then(|| {
+        let store = StateStore::new(&config.state.path);
+        spawn_state_writer(store.clone(), config.state.interval_secs);
+        store
+    });
+    let mut correlation_engine = CorrelationEngine::new(
+        config.corr_min_move_pct,
+        config.corr_max_lag_seconds,
+        config.corr_min_confidence,
+    );
+    if let Some(state) = restored.correlation.take() {
+        correlation_engine.restore(state, now_ms);
+    }
+
+    // Cross-symbol merge stage owns the correlation engine; shard workers own per-symbol state.
+    let (merge_tx, merge_rx) = mpsc::channel(config.sharding.queue_capacity);
+    let merge_stage = tokio::spawn(run_merge_stage(
+        merge_rx,
+        correlation_engine,
+        cross_asset,
+        outcome_store.map(|store| (OutcomeTracker::new(&config.outcomes), store)),
+        config
+            .paper
+            .enabled
+            .then(|| PaperTrader::new(&config.paper)),
+        config.conviction.enabled.then(|| {
+            ConvictionScorer::new(
+                &config.conviction,
+                config.funding_rate_alert_pct,
+                config.flow.imbalance_min_notional,
+            )
+        }),
+        state_store.clone(),
+        tx.clone(),
+    ));
+
+    let alert_rules = config.rules.enabled().then(|| {
+        let rules = init_rules(&config.rules);
+        spawn_rule_reload(config.rules.clone(), rules.clone());
+        rules
+    });
+
+    // The registry may have dropped SYMBOLS since SHARD_COUNT was capped at load.
+    config.sharding.shards = config.sharding.shards.clamp(1, config.max_symbol_count());
+    let config = Arc::new(config);
+    let (router, shard_receivers) = ShardRouter::new(
+        config.sharding.shards,
+        config.sharding.queue_capacity,
+        config.sharding.overflow,
+    );
+    log::info!(
+        target: "shard",
+        "{} shard workers, queue capacity {}, overflow {:?}",
+        router.shard_count(),
+        config.sharding.queue_capacity,
+        config.sharding.overflow
+    );
+    let mut restored_by_shard: Vec<HashMap<String, SymbolState>> =
+        vec![HashMap::new(); router.shard_count()];
+    for (symbol, state) in restored.symbols {
+        restored_by_shard[shard::shard_index(&symbol, router.shard_count())].insert(symbol, state);
+    }
+    let mut shard_workers = Vec::with_capacity(router.shard_count());
+    for ((index, rx), restored) in shard_receivers
+        .into_iter()
+        .enumerate()
+        .zip(restored_by_shard)
+    {
+        let ctx = ShardContext {
+            index,
+            config: config.clone(),
+            config_map: config_map.clone(),
+            tx: tx.clone(),
+            correlation_service: correlation_service.clone(),
+            notifier: notifier.clone(),
+            merge: merge_tx.clone(),
+            rules: alert_rules.clone(),
+            state: state_store.clone(),
+        };
+        shard_workers.push(tokio::spawn(run_shard_worker(rx, ctx, restored)));
+    }
+
+    // Optional raw-frame capture for offline replay with the backtest binary.
+    let mut market_capture = std::env::var("MARKET_CAPTURE_PATH")
+        .ok()
+        .filter(|path| !path.trim().is_empty())
+        .and_then(|path| {
+            match std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+            {
+                Ok(file) => {
+                    log::info!(target: "capture", "recording raw market frames to {}", path);
+                    Some(std::io::LineWriter::new(file))
+                }
+                Err(err) => {
+                    log::warn!(target: "capture", "cannot open {}: {}", path, err);
+                    None
+                }
+            }
+        });
 
     let (ws_stream, _) = connect_async(&url)
         .await
         .expect("Failed to connect to Binance");
-    let mut read = ws_stream;
-
-    // Main loop: read messages from Binance websocket
-    while let Some(msg) = read.next().await {
+    let (mut write, mut read) = ws_stream.split();
+    let mut next_request_id: u64 = 1;
+    let mut last_shard_stats = Instant::now();
+    let mut last_dropped = 0;
+    // News and breadth events the reader could not hand to a full merge stage.
+    let mut merge_dropped = 0;
+
+    // Main loop: read messages from Binance websocket and route them to shard workers.
+    // Parsing happens here; all per-symbol processing runs on the shard that owns the symbol.
+    let signal = wait_for_signal();
+    tokio::pin!(signal);
+    let cause = loop {
+        let msg = tokio::select! {
+            msg = read.next() => match msg {
+                Some(msg) => msg,
+                None => {
+                    log::warn!(target: "shutdown", "upstream stream ended");
+                    break ShutdownCause::UpstreamClosed;
+                }
+            },
+            name = &mut signal => {
+                log::info!(target: "shutdown", "{name} received, draining");
+                break ShutdownCause::Signal(name);
+            }
+        };
         if let Ok(msg) = msg {
             if !msg.is_text() {
                 continue;
@@ -223,98 +564,646 @@ async fn main() {
                 Err(_) => continue,
             };
 
-            // 1) aggTrade messages
-            if let Some(agg) = parse_agg_trade(payload) {
-                process_agg_trade(
-                    &agg,
-                    &config_map,
-                    &mut last_prices,
-                    &mut correlation_engine,
-                    &tx,
-                    correlation_service.as_ref(),
-                    notifier.as_ref(),
-                )
-                .await;
-                continue;
+            if let Some(capture) = market_capture.as_mut()
+                && writeln!(capture, "{}", payload).is_err()
+            {
+                log::warn!(target: "capture", "write failed; stopping market capture");
+                market_capture = None;
+            }
+
+            // Classify once by stream name / event type and deserialize straight into the
+            // typed payload.
+            match dispatch_message(payload) {
+                // 2) depth updates (skipped when DISABLE_DEPTH_STREAM is set)
+                StreamMessage::Market(MarketMessage::Depth(_)) if config.disable_depth_stream => {
+                    continue;
+                }
+                // 3) kline updates (4h quant vector signal on closed candles)
+                StreamMessage::Market(MarketMessage::Kline(_)) if !enable_kline_quant => continue,
+                // 5) funding rate updates
+                StreamMessage::Market(MarketMessage::Funding(_)) if !config.enable_funding_rate => {
+                    continue;
+                }
+                // 1), 2), 3), 5) per-symbol market data goes to the owning shard
+                StreamMessage::Market(message) => {
+                    router.route(message);
+                    if last_shard_stats.elapsed() >= SHARD_STATS_INTERVAL {
+                        log_shard_stats(&router, merge_dropped, &mut last_dropped);
+                        last_shard_stats = Instant::now();
+                    }
+                    continue;
+                }
+                // 4) external news events used for correlation
+                StreamMessage::News(news_event) => {
+                    try_merge(&merge_tx, MergeEvent::News(news_event), &mut merge_dropped);
+                    continue;
+                }
+                // 6) all-market tickers driving market breadth and the dynamic symbol universe
+                StreamMessage::MiniTickers(tickers) => {
+                    let now_ms = tickers.iter().map(|t| t.event_time).max().unwrap_or(0);
+                    if let Some(tracker) = breadth.as_mut() {
+                        tracker.observe(&tickers);
+                        if let Some(snapshot) = tracker.snapshot_if_due(now_ms) {
+                            let msg = format_breadth_log(&snapshot);
+                            log::info!(target: "breadth", "{msg}");
+                            let _ = tx.send(msg);
+                            let _ = tx.send(snapshot.to_event_json());
+                            try_merge(&merge_tx, MergeEvent::Breadth(snapshot), &mut merge_dropped);
+                        }
+                    }
+                    if let Some(ranker) = universe.as_mut() {
+                        ranker.observe(&tickers);
+                        if let Some(change) = ranker.rebalance_if_due(now_ms)
+                            && !change.is_empty()
+                        {
+                            apply_universe_change(
+                                &change,
+                                &config_map,
+                                registry.as_ref(),
+                                &stream_set,
+                                &mut write,
+                                &mut next_request_id,
+                            )
+                            .await;
+                            let msg = format_universe_log(&change, ranker.members().count());
+                            log::info!(target: "universe", "{msg}");
+                            let _ = tx.send(msg);
+                        }
+                    }
+                    continue;
+                }
+                StreamMessage::Invalid(err) => {
+                    log::warn!(target: "stream", rate_limited = true; "invalid JSON frame: {err}");
+                    continue;
+                }
+                StreamMessage::Unhandled => {}
+            }
+
+            // Unknown / unhandled stream messages, visible with `LOG_FILTER=stream=debug`
+            if log::log_enabled!(target: "stream", log::Level::Debug) {
+                let snippet: String = payload.chars().take(180).collect();
+                let suffix = if payload.chars().count() > 180 {
+                    "..."
+                } else {
+                    ""
+                };
+                log::debug!(target: "stream", "unhandled text message: '{}{}'", snippet, suffix);
+            }
+        }
+    };
+
+    // Every stage below shares one drain deadline so a stuck stage cannot hold up the exit.
+    let deadline =
+        (Instant::now() + Duration::from_secs(config.shutdown.drain_timeout_secs)).into();
+    let mut report = ShutdownReport::new(cause);
+
+    if cause != ShutdownCause::UpstreamClosed {
+        let close = Message::Close(Some(CloseFrame {
+            code: CloseCode::Normal,
+            reason: "feeder shutting down".into(),
+        }));
+        if timeout_at(deadline, write.send(close)).await.is_err() {
+            report.timed_out.push("upstream");
+        }
+    }
+    if let Some(capture) = market_capture.as_mut()
+        && let Err(err) = capture.flush()
+    {
+        log::warn!(target: "capture", "final flush failed: {err}");
+    }
+
+    log_shard_stats(&router, merge_dropped, &mut last_dropped);
+    // Closing the shard queues lets every worker finish its backlog and publish its final
+    // state; the merge stage follows once all of its senders are gone.
+    drop(router);
+    drop(merge_tx);
+    let pipeline = async {
+        for worker in shard_workers {
+            let _ = worker.await;
+        }
+        let _ = merge_stage.await;
+    };
+    if timeout_at(deadline, pipeline).await.is_err() {
+        report.timed_out.push("pipeline");
+    }
+
+    match timeout_at(deadline, notifier.drain(&tx, deadline)).await {
+        Ok(undelivered) => report.undelivered = undelivered,
+        Err(_) => report.timed_out.push("notifier"),
+    }
+
+    // Clients get a close frame; the news loop stops at its next tick, never mid-write.
+    shutdown.trigger();
+    if timeout_at(deadline, server).await.is_err() {
+        report.timed_out.push("server");
+    }
+    if let Some(news_loop) = news_loop
+        && timeout_at(deadline, news_loop).await.is_err()
+    {
+        report.timed_out.push("news");
+    }
+
+    if let Some(store) = state_store {
+        match store.save_async(Utc::now().timestamp_millis() as u64).await {
+            Ok(count) => {
+                log::info!(target: "state", "saved {} symbols to {}", count, config.state.path)
+            }
+            Err(err) => log::error!(target: "state", "snapshot failed: {err:#}"),
+        }
+    }
+
+    log::info!(target: "shutdown", "{}", format_shutdown_log(&report));
+    std::process::exit(report.exit_code());
+}
+
+/// Registers configs for added symbols before subscribing to their streams, and
+/// unsubscribes removed symbols before dropping their configs.
+async fn apply_universe_change<W>(
+    change: &UniverseChange,
+    config_map: &SharedSymbolConfigs,
+    registry: Option<&SymbolRegistry>,
+    stream_set: &StreamSet,
+    write: &mut W,
+    next_request_id: &mut u64,
+) where
+    W: Sink<Message> + Unpin,
+{
+    for symbol in &change.added {
+        let mut cfg = Config::load_symbol(symbol);
+        if let Some(info) = registry.and_then(|r| r.get(symbol)) {
+            cfg.precision.tick_size = cfg.precision.tick_size.or(info.tick_size);
+            cfg.precision.step_size = cfg.precision.step_size.or(info.step_size);
+        }
+        config_map.insert(cfg);
+    }
+
+    for (method, symbols) in [
+        ("SUBSCRIBE", &change.added),
+        ("UNSUBSCRIBE", &change.removed),
+    ] {
+        if symbols.is_empty() {
+            continue;
+        }
+        let frame = subscription_frame(
+            method,
+            &stream_set.streams_for_all(symbols),
+            *next_request_id,
+        );
+        *next_request_id += 1;
+        if write.send(Message::Text(frame)).await.is_err() {
+            log::warn!(target: "universe", "failed to send {method} request");
+        }
+    }
+
+    for symbol in &change.removed {
+        config_map.remove(symbol);
+    }
+}
+
+/// Input to the cross-symbol merge stage.
+enum MergeEvent {
+    Market(MarketEvent),
+    News(NewsEvent),
+    Breadth(MarketBreadth),
+    /// Spike or big move to decompose against the cross-asset reference.
+    Move(MoveEvent),
+    /// Directional signal whose forward returns are tracked.
+    Signal(TrackedSignal),
+    /// Periodic taker-flow snapshot for the conviction score.
+    Flow(FlowSnapshot),
+    /// Diff depth update for the paper trader's order book.
+    Depth(feeder_service::binance_depth::DepthUpdate),
+    /// Symbol that left the universe, sent by the shard that owned it.
+    SymbolRemoved(String),
+}
+
+/// Shared handles for one shard worker.
+struct ShardContext {
+    index: usize,
+    config: Arc<Config>,
+    config_map: SharedSymbolConfigs,
+    tx: broadcast::Sender<String>,
+    correlation_service: Option<CorrelationService>,
+    notifier: Arc<NotificationFanout>,
+    merge: mpsc::Sender<MergeEvent>,
+    /// User-defined alert rules, when configured.
+    rules: Option<SharedRules>,
+    /// Receives this shard's detector state for the state file, when enabled.
+    state: Option<StateStore>,
+}
+
+/// Per-symbol detector state owned by a single shard worker.
+struct ShardState {
+    /// Symbol configs the per-symbol maps were last synced against.
+    symbols: Arc<HashMap<String, SymbolConfig>>,
+    last_prices: HashMap<String, f64>,
+    /// One detector per configured big-move profile.
+    big_move_detectors: HashMap<String, Vec<BigMoveDetector>>,
+    last_funding_alert_ms: HashMap<String, u64>,
+    spike_detector: SpikeWindowDetector,
+    big_trade_thresholds: AdaptiveThresholds,
+    whale_detector: WhaleClusterDetector,
+    iceberg_detector: IcebergDetector,
+    trade_flow: Option<TradeFlowTracker>,
+    volatility: Option<VolatilityTracker>,
+    rules: Option<RuleEngine>,
+    /// Saved big-move windows, applied when the symbol's detectors are created.
+    restored_big_moves: HashMap<String, Vec<BigMoveDetectorState>>,
+    state_max_age_ms: u64,
+    last_state_export: Instant,
+}
+
+impl ShardState {
+    fn new(
+        config: &Config,
+        rules: Option<&SharedRules>,
+        restored: HashMap<String, SymbolState>,
+    ) -> Self {
+        let mut last_prices = HashMap::new();
+        let mut last_funding_alert_ms = HashMap::new();
+        let mut restored_big_moves = HashMap::new();
+        for (symbol, state) in restored {
+            if let Some(price) = state.last_price {
+                last_prices.insert(symbol.clone(), price);
+            }
+            if let Some(last) = state.last_funding_alert_ms {
+                last_funding_alert_ms.insert(symbol.clone(), last);
+            }
+            restored_big_moves.insert(symbol, state.big_move);
+        }
+        Self {
+            symbols: Arc::default(),
+            last_prices,
+            big_move_detectors: HashMap::new(),
+            last_funding_alert_ms,
+            spike_detector: SpikeWindowDetector::new(&config.spike_windows_secs),
+            big_trade_thresholds: AdaptiveThresholds::new(
+                config.threshold.window_secs,
+                config.threshold.recalc_interval_secs,
+                config.threshold.min_samples,
+            ),
+            whale_detector: WhaleClusterDetector::new(
+                config.whale.cluster_window_ms,
+                config.whale.adjacent_bps,
+            ),
+            iceberg_detector: IcebergDetector::new(
+                config.whale.iceberg_window_secs,
+                config.whale.iceberg_min_refills,
+                config.whale.iceberg_refill_ratio,
+            ),
+            trade_flow: config
+                .flow
+                .enabled
+                .then(|| TradeFlowTracker::new(config.flow.emit_interval_secs)),
+            volatility: config
+                .volatility
+                .enabled
+                .then(|| VolatilityTracker::new(&config.volatility)),
+            rules: rules.map(|rules| RuleEngine::new(rules.clone(), &config.rules)),
+            restored_big_moves,
+            state_max_age_ms: config.state.max_age_secs.saturating_mul(1_000),
+            last_state_export: Instant::now(),
+        }
+    }
+
+    fn export_state(&self) -> HashMap<String, SymbolState> {
+        collect_symbol_states(
+            &self.last_prices,
+            &self.big_move_detectors,
+            &self.last_funding_alert_ms,
+        )
+    }
+
+    /// Creates detectors for symbols that joined this shard and drops all state for symbols
+    /// that left the universe. Returns the removed symbols this shard owns, so the merge
+    /// stage hears about each removal once.
+    fn sync_symbols(
+        &mut self,
+        configs: &Arc<HashMap<String, SymbolConfig>>,
+        shard_count: usize,
+        shard_index: usize,
+    ) -> Vec<String> {
+        if Arc::ptr_eq(&self.symbols, configs) {
+            return Vec::new();
+        }
+
+        let removed: Vec<String> = self
+            .symbols
+            .keys()
+            .filter(|symbol| !configs.contains_key(*symbol))
+            .cloned()
+            .collect();
+        for symbol in &removed {
+            self.last_prices.remove(symbol);
+            self.big_move_detectors.remove(symbol);
+            self.last_funding_alert_ms.remove(symbol);
+            self.spike_detector.remove_symbol(symbol);
+            self.big_trade_thresholds.remove_symbol(symbol);
+            self.whale_detector.remove_symbol(symbol);
+            self.iceberg_detector.remove_symbol(symbol);
+            if let Some(flow) = self.trade_flow.as_mut() {
+                flow.remove_symbol(symbol);
+            }
+            if let Some(volatility) = self.volatility.as_mut() {
+                volatility.remove_symbol(symbol);
+            }
+            if let Some(rules) = self.rules.as_mut() {
+                rules.remove_symbol(symbol);
+            }
+        }
+
+        for (symbol, cfg) in configs.iter() {
+            if shard::shard_index(symbol, shard_count) == shard_index {
+                self.big_move_detectors
+                    .entry(symbol.clone())
+                    .or_insert_with(|| {
+                        let mut detectors: Vec<BigMoveDetector> = cfg
+                            .big_move_profiles
+                            .iter()
+                            .map(BigMoveDetector::from_profile)
+                            .collect();
+                        if let Some(states) = self.restored_big_moves.remove(symbol) {
+                            restore_big_move_detectors(
+                                &mut detectors,
+                                states,
+                                Utc::now().timestamp_millis() as u64,
+                                self.state_max_age_ms,
+                            );
+                        }
+                        detectors
+                    });
             }
+        }
+        self.symbols = configs.clone();
+        removed
+            .into_iter()
+            .filter(|symbol| shard::shard_index(symbol, shard_count) == shard_index)
+            .collect()
+    }
+}
 
-            // 2) depth updates (skipped when DISABLE_DEPTH_STREAM is set)
-            if !config.disable_depth_stream {
-                if let Some(depth) = parse_depth_update(payload) {
+/// How often the reader logs shard backlogs and overflow counters.
+const SHARD_STATS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Logs shard backlogs and merge-stage drops, as a warning when messages were dropped
+/// since the last report.
+fn log_shard_stats(router: &ShardRouter, merge_dropped: u64, last_dropped: &mut u64) {
+    let stats = router.stats();
+    let dropped: u64 = stats.iter().map(|s| s.dropped).sum::<u64>() + merge_dropped;
+    let line = format!(
+        "{} merge_dropped={}",
+        format_shard_stats(&stats),
+        merge_dropped
+    );
+    if dropped > *last_dropped {
+        log::warn!(target: "shard", "queues full, {} messages dropped: {}", dropped - *last_dropped, line);
+    } else {
+        log::info!(target: "shard", "queues {}", line);
+    }
+    *last_dropped = dropped;
+}
+
+/// Hands a reader-side event to the merge stage without waiting, so a busy merge stage
+/// never stalls ingest. Counts the event when the merge queue is full.
+fn try_merge(merge: &mpsc::Sender<MergeEvent>, event: MergeEvent, dropped: &mut u64) {
+    if let Err(mpsc::error::TrySendError::Full(_)) = merge.try_send(event) {
+        *dropped += 1;
+    }
+}
+
+async fn run_shard_worker(
+    mut rx: ShardReceiver,
+    ctx: ShardContext,
+    restored: HashMap<String, SymbolState>,
+) {
+    let mut state = ShardState::new(&ctx.config, ctx.rules.as_ref(), restored);
+    let export_interval = Duration::from_secs(ctx.config.state.interval_secs);
+    let correlation_service = ctx.correlation_service.as_ref();
+    let notifier = ctx.notifier.as_ref();
+    let shard_count = ctx.config.sharding.shards;
+
+    while let Some(message) = rx.recv().await {
+        let config_map = ctx.config_map.snapshot();
+        for symbol in state.sync_symbols(&config_map, shard_count, ctx.index) {
+            let _ = ctx.merge.send(MergeEvent::SymbolRemoved(symbol)).await;
+        }
+        let log_context = LogContext::market(message.symbol(), message.stream());
+        logging::with_context(log_context, async {
+            match message {
+                MarketMessage::AggTrade(agg) => {
+                    process_agg_trade(
+                        &agg,
+                        &config_map,
+                        &mut state.last_prices,
+                        &mut state.spike_detector,
+                        &mut state.big_trade_thresholds,
+                        &mut state.whale_detector,
+                        state.trade_flow.as_mut(),
+                        state.volatility.as_mut(),
+                        state.rules.as_mut(),
+                        &ctx.config.flow,
+                        &ctx.merge,
+                        &ctx.tx,
+                        correlation_service,
+                        notifier,
+                    )
+                    .await;
+                }
+                MarketMessage::Depth(depth) => {
                     process_depth_update(
                         &depth,
                         &config_map,
-                        &config,
-                        &mut big_move_detectors,
-                        &mut correlation_engine,
-                        &tx,
-                        correlation_service.as_ref(),
-                        notifier.as_ref(),
+                        &ctx.config,
+                        &mut state.big_move_detectors,
+                        &mut state.iceberg_detector,
+                        state.volatility.as_ref(),
+                        state.rules.as_mut(),
+                        &ctx.merge,
+                        &ctx.tx,
+                        correlation_service,
+                        notifier,
                     )
                     .await;
-                    continue;
                 }
-            }
-
-            // 3) kline updates (4h quant vector signal on closed candles)
-            if enable_kline_quant {
-                if let Some(kline_event) = parse_kline_event(payload) {
+                MarketMessage::Kline(kline_event) => {
                     process_kline_event(
                         &kline_event,
                         &config_map,
-                        &mut correlation_engine,
-                        &tx,
-                        correlation_service.as_ref(),
-                        notifier.as_ref(),
+                        state.volatility.as_mut(),
+                        state.rules.as_mut(),
+                        &ctx.merge,
+                        &ctx.tx,
+                        correlation_service,
+                        notifier,
                     )
                     .await;
-                    continue;
                 }
-            }
-
-            // 4) external news events used for correlation
-            if let Some(news_event) = parse_news_event(payload) {
-                correlation_engine.ingest_news(news_event);
-                continue;
-            }
-
-            // 5) funding rate updates
-            if config.enable_funding_rate {
-                if let Some(funding) = parse_funding_rate_update(payload) {
+                MarketMessage::Funding(funding) => {
                     process_funding_rate_update(
                         &funding,
                         &config_map,
-                        &config,
-                        &mut last_funding_alert_ms,
-                        &mut correlation_engine,
-                        &tx,
-                        correlation_service.as_ref(),
-                        notifier.as_ref(),
+                        &ctx.config,
+                        &mut state.last_funding_alert_ms,
+                        state.rules.as_mut(),
+                        &ctx.merge,
+                        &ctx.tx,
+                        correlation_service,
+                        notifier,
                     )
                     .await;
-                    continue;
                 }
             }
+        })
+        .await;
+        if let Some(store) = ctx.state.as_ref()
+            && state.last_state_export.elapsed() >= export_interval
+        {
+            store.update_symbols(ctx.index, state.export_state());
+            state.last_state_export = Instant::now();
+        }
+    }
+    if let Some(store) = ctx.state.as_ref() {
+        store.update_symbols(ctx.index, state.export_state());
+    }
+    log::warn!(target: "shard", "worker {} stopped", ctx.index);
+}
 
-            // Unknown / unhandled stream messages (optional logging controlled by env)
-            if std::env::var_os("LOG_UNKNOWN_STREAM_MESSAGES").is_some() {
-                let snippet: String = payload.chars().take(180).collect();
-                let suffix = if payload.chars().count() > 180 {
-                    "..."
-                } else {
-                    ""
+/// How often the merge stage hands the correlation queues to the state store.
+const STATE_EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Feeds market events from every shard, plus news, into the shared correlation engine,
+/// the cross-asset return series and the signal outcome tracker.
+#[allow(clippy::too_many_arguments)]
+async fn run_merge_stage(
+    mut rx: mpsc::Receiver<MergeEvent>,
+    mut correlation_engine: CorrelationEngine,
+    mut cross_asset: Option<CrossAssetTracker>,
+    mut outcomes: Option<(OutcomeTracker, OutcomeStore)>,
+    mut paper: Option<PaperTrader>,
+    mut conviction: Option<ConvictionScorer>,
+    state: Option<StateStore>,
+    tx: broadcast::Sender<String>,
+) {
+    let mut last_state_export = Instant::now();
+    while let Some(event) = rx.recv().await {
+        if let Some(store) = state.as_ref()
+            && last_state_export.elapsed() >= STATE_EXPORT_INTERVAL
+        {
+            store.update_correlation(correlation_engine.state());
+            last_state_export = Instant::now();
+        }
+        match event {
+            MergeEvent::Market(market_event) => {
+                if matches!(market_event.kind, MarketEventKind::AggTrade)
+                    && let Some((tracker, store)) = outcomes.as_mut()
+                {
+                    let now_ms = market_event.timestamp_ms;
+                    let mut completed =
+                        tracker.on_trade(&market_event.symbol, now_ms, market_event.price);
+                    if tracker.report_due(now_ms) {
+                        completed.extend(tracker.expire(now_ms));
+                        spawn_outcome_report(store.clone(), tx.clone());
+                    }
+                    emit_outcomes(completed, store, &tx);
+                }
+                if matches!(market_event.kind, MarketEventKind::AggTrade)
+                    && let Some(trader) = paper.as_mut()
+                {
+                    let now_ms = market_event.timestamp_ms;
+                    let events = trader.on_trade(&market_event.symbol, now_ms, market_event.price);
+                    emit_paper_events(events, &tx);
+                    if trader.report_due(now_ms) {
+                        emit_paper_report(trader, &tx);
+                    }
+                }
+                if let Some(tracker) = cross_asset.as_mut() {
+                    tracker.record(
+                        &market_event.symbol,
+                        market_event.timestamp_ms,
+                        market_event.price,
+                    );
+                    if let Some(matrix) = tracker.matrix_if_due(market_event.timestamp_ms) {
+                        let msg = format_matrix_log(&matrix);
+                        logging::signal("cross_asset", &msg);
+                        let _ = tx.send(msg);
+                        let _ = tx.send(matrix.to_event_json());
+                    }
+                }
+                let correlation = match conviction.as_mut() {
+                    Some(scorer) => {
+                        emit_conviction(scorer.observe_market_event(&market_event), &tx);
+                        let correlation = correlation_engine.on_market_event(market_event);
+                        if let Some(signal) = correlation.as_ref() {
+                            emit_conviction(scorer.observe_correlation(signal), &tx);
+                        }
+                        correlation
+                    }
+                    None => correlation_engine.on_market_event(market_event),
+                };
+                emit_correlation(correlation, &tx);
+            }
+            MergeEvent::Move(move_event) => {
+                let Some(decomposition) =
+                    cross_asset.as_ref().and_then(|t| t.decompose(&move_event))
+                else {
+                    continue;
                 };
-                eprintln!("[stream] unhandled text message: '{}{}'", snippet, suffix);
+                let msg = format_decomposition_log(&decomposition);
+                logging::signal("cross_asset", &msg);
+                let _ = tx.send(msg);
+                let _ = tx.send(decomposition.to_event_json());
             }
+            MergeEvent::Signal(signal) => {
+                if let Some(trader) = paper.as_mut() {
+                    emit_paper_events(trader.on_signal(&signal), &tx);
+                }
+                if let Some((tracker, _)) = outcomes.as_mut() {
+                    tracker.track(signal);
+                }
+            }
+            MergeEvent::Flow(snapshot) => {
+                if let Some(scorer) = conviction.as_mut() {
+                    emit_conviction(scorer.observe_flow(&snapshot), &tx);
+                }
+            }
+            MergeEvent::Depth(depth) => {
+                if let Some(trader) = paper.as_mut() {
+                    trader.on_depth(&depth);
+                }
+            }
+            MergeEvent::SymbolRemoved(symbol) => {
+                if let Some(tracker) = cross_asset.as_mut() {
+                    tracker.remove_symbol(&symbol);
+                }
+                if let Some(scorer) = conviction.as_mut() {
+                    scorer.remove_symbol(&symbol);
+                }
+                if let Some(trader) = paper.as_mut() {
+                    trader.remove_symbol(&symbol);
+                }
+            }
+            MergeEvent::News(news_event) => correlation_engine.ingest_news(news_event),
+            MergeEvent::Breadth(breadth) => correlation_engine.on_market_breadth(breadth),
         }
     }
+    if let Some(store) = state.as_ref() {
+        store.update_correlation(correlation_engine.state());
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn process_agg_trade(
     agg: &feeder_service::binance::AggTrade,
-    config_map: &HashMap<String, feeder_service::config::SymbolConfig>,
+    config_map: &HashMap<String, SymbolConfig>,
     last_prices: &mut HashMap<String, f64>,
-    correlation_engine: &mut CorrelationEngine,
+    spike_detector: &mut SpikeWindowDetector,
+    big_trade_thresholds: &mut AdaptiveThresholds,
+    whale_detector: &mut WhaleClusterDetector,
+    trade_flow: Option<&mut TradeFlowTracker>,
+    volatility: Option<&mut VolatilityTracker>,
+    rules: Option<&mut RuleEngine>,
+    flow_config: &FlowConfig,
+    merge: &mpsc::Sender<MergeEvent>,
     tx: &broadcast::Sender<String>,
     correlation_service: Option<&CorrelationService>,
     notifier: &NotificationFanout,
@@ -325,20 +1214,46 @@ async fn process_agg_trade(
         None => return,
     };
 
-    let current_price = agg.p.parse::<f64>().unwrap_or(0.0);
+    let current_price = agg.p.to_f64();
     let prev_price = last_prices.get(&symbol).copied();
-    let spike = calc_spike(prev_price, current_price);
-    let qty = agg.q.parse::<f64>().unwrap_or(0.0);
-    let direction = if let Some(prev) = prev_price {
-        if current_price > prev {
-            1
-        } else if current_price < prev {
-            -1
-        } else {
-            0
+    let qty = agg.q.to_f64();
+    let mut spike_pct = cfg.spike_pct;
+    if let Some(tracker) = volatility {
+        if let Some(change) = tracker.record_trade(&symbol, agg.t, current_price) {
+            let msg = format_vol_regime_log(&change);
+            logging::signal("vol_regime", &msg);
+            let _ = tx.send(msg);
+            let _ = tx.send(change.to_event_json());
         }
+        spike_pct *= tracker.threshold_scale(&symbol);
+    }
+    let evaluation = spike_detector.push(&symbol, agg.t, current_price, qty, spike_pct);
+    let big_trade_qty = big_trade_thresholds.observe(
+        &symbol,
+        agg.t,
+        current_price,
+        qty,
+        cfg.big_trade_rule,
+        cfg.big_trade_qty,
+    );
+    let window_move = evaluation.strongest_move_pct;
+    // Only windows that actually fired count as a spike, so a sustained move does not
+    // re-trigger per trade.
+    let spike = evaluation
+        .spikes
+        .iter()
+        .map(|s| s.move_pct.abs())
+        .fold(0.0, f64::max);
+    let direction = if window_move > 0.0 {
+        1
+    } else if window_move < 0.0 {
+        -1
     } else {
-        0
+        match prev_price {
+            Some(prev) if current_price > prev => 1,
+            Some(prev) if current_price < prev => -1,
+            _ => 0,
+        }
     };
 
     let market_event = MarketEvent {
@@ -347,15 +1262,16 @@ async fn process_agg_trade(
         kind: MarketEventKind::AggTrade,
         move_pct: spike,
         notional: current_price * qty,
+        price: current_price,
         direction,
     };
 
-    emit_correlation(correlation_engine.on_market_event(market_event), tx);
+    let _ = merge.send(MergeEvent::Market(market_event)).await;
 
     last_prices.insert(symbol.clone(), current_price);
 
     // Preserve asynchronous logging & broadcasting behaviour
-    log_and_broadcast(tx, agg, spike, cfg).await;
+    log_and_broadcast(tx, agg, spike, big_trade_qty, spike_pct).await;
 
     build_and_send_enriched_payload(
         tx,
@@ -365,21 +1281,162 @@ async fn process_agg_trade(
         &symbol,
         agg.t as i64,
         json!({
-            "price": current_price,
-            "quantity": agg.q.parse::<f64>().unwrap_or(0.0),
-            "spike_pct": spike,
+            "price": agg.p,
+            "quantity": agg.q,
+            "spike_pct": window_move,
             "buyer_maker": agg.m,
+            "big_trade_qty": big_trade_qty,
+        }),
+    )
+    .await;
+
+    for window_spike in &evaluation.spikes {
+        let spike_msg = format_spike_log(&symbol, window_spike);
+        logging::signal("spike", &spike_msg);
+        let _ = tx.send(spike_msg);
+        let _ = merge
+            .send(MergeEvent::Move(MoveEvent {
+                symbol: symbol.clone(),
+                source: "price_spike",
+                start_ms: window_spike.start_ms,
+                end_ms: window_spike.end_ms,
+                move_pct: Some(window_spike.move_pct),
+            }))
+            .await;
+
+        build_and_send_enriched_payload(
+            tx,
+            correlation_service,
+            notifier,
+            "price_spike",
+            &symbol,
+            agg.t as i64,
+            json!({
+                "spike_pct": window_spike.move_pct,
+                "window": window_spike.window_label(),
+                "start_price": window_spike.start_price,
+                "end_price": window_spike.end_price,
+                "high": window_spike.high,
+                "low": window_spike.low,
+                "notional": window_spike.notional,
+                "trade_count": window_spike.trade_count,
+            }),
+        )
+        .await;
+    }
+
+    let mut whale_notional = 0.0;
+    if let Some(cluster) = whale_detector.push(
+        &symbol,
+        agg.t,
+        current_price,
+        qty,
+        agg.m,
+        cfg.whale_notional,
+    ) {
+        whale_notional = match cluster.side {
+            TakerSide::Buy => cluster.notional,
+            TakerSide::Sell => -cluster.notional,
+        };
+        let whale_msg = format_whale_log(&cluster, cfg.whale_notional, &cfg.precision);
+        logging::signal("whale", &whale_msg);
+        let _ = tx.send(whale_msg);
+
+        build_and_send_enriched_payload(
+            tx,
+            correlation_service,
+            notifier,
+            "whale_cluster",
+            &symbol,
+            agg.t as i64,
+            json!({
+                "side": cluster.side.as_label(),
+                "notional": cluster.notional,
+                "quantity": cluster.qty,
+                "trade_count": cluster.trade_count,
+                "min_price": cluster.min_price,
+                "max_price": cluster.max_price,
+                "span_ms": cluster.span_ms(),
+            }),
+        )
+        .await;
+    }
+
+    let mut readings = vec![
+        ("price", current_price),
+        ("trade_qty", qty),
+        ("trade_notional", current_price * qty),
+        ("spike_pct", window_move),
+        ("whale_notional", whale_notional),
+    ];
+    if let Some(tracker) = trade_flow {
+        tracker.record(&symbol, agg.t, current_price, qty, agg.m);
+        if let Some(snapshot) = tracker.snapshot_if_due(&symbol, agg.t) {
+            emit_trade_flow(&snapshot, flow_config, tx, correlation_service, notifier).await;
+            let imbalance = snapshot
+                .strongest_imbalance(0.0, flow_config.imbalance_min_notional)
+                .map_or(0.0, |imbalance| imbalance.imbalance_pct);
+            readings.push(("flow_imbalance_pct", imbalance));
+            readings.push(("cvd", snapshot.cvd));
+            let _ = merge.send(MergeEvent::Flow(snapshot)).await;
+        }
+    }
+
+    if let Some(engine) = rules {
+        let matches = engine.observe(&symbol, agg.t, &readings);
+        dispatch_rule_matches(matches, tx, correlation_service, notifier).await;
+    }
+}
+
+async fn emit_trade_flow(
+    snapshot: &FlowSnapshot,
+    flow_config: &FlowConfig,
+    tx: &broadcast::Sender<String>,
+    correlation_service: Option<&CorrelationService>,
+    notifier: &NotificationFanout,
+) {
+    let flow_msg = format_flow_log(snapshot);
+    logging::signal("trade_flow", &flow_msg);
+    let _ = tx.send(flow_msg);
+
+    let Some(imbalance) = snapshot.strongest_imbalance(
+        flow_config.imbalance_alert_pct,
+        flow_config.imbalance_min_notional,
+    ) else {
+        return;
+    };
+
+    let alert = format_flow_imbalance_log(snapshot, &imbalance);
+    logging::signal("flow_imbalance", &alert);
+    let _ = tx.send(alert);
+
+    build_and_send_enriched_payload(
+        tx,
+        correlation_service,
+        notifier,
+        "trade_flow_imbalance",
+        &snapshot.symbol,
+        snapshot.timestamp_ms as i64,
+        json!({
+            "imbalance_pct": imbalance.imbalance_pct,
+            "window": imbalance.label,
+            "total_notional": imbalance.total_notional,
+            "cvd": snapshot.cvd,
         }),
     )
     .await;
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn process_depth_update(
     depth: &feeder_service::binance_depth::DepthUpdate,
-    config_map: &HashMap<String, feeder_service::config::SymbolConfig>,
+    config_map: &HashMap<String, SymbolConfig>,
     config: &Config,
-    big_move_detectors: &mut HashMap<String, BigMoveDetector>,
-    correlation_engine: &mut CorrelationEngine,
+    big_move_detectors: &mut HashMap<String, Vec<BigMoveDetector>>,
+    iceberg_detector: &mut IcebergDetector,
+    volatility: Option<&VolatilityTracker>,
+    rules: Option<&mut RuleEngine>,
+    merge: &mpsc::Sender<MergeEvent>,
     tx: &broadcast::Sender<String>,
     correlation_service: Option<&CorrelationService>,
     notifier: &NotificationFanout,
@@ -390,6 +1447,43 @@ async fn process_depth_update(
         None => return,
     };
 
+    // The paper trader rebuilds its order book from every diff, before any filtering.
+    if config.paper.enabled {
+        let _ = merge.send(MergeEvent::Depth(depth.clone())).await;
+    }
+
+    // Iceberg refills are tracked on every level update, before the big-level filters below.
+    let mut icebergs =
+        iceberg_detector.on_depth_levels(&symbol, BookSide::Bid, &depth.bids, depth.event_time);
+    icebergs.extend(iceberg_detector.on_depth_levels(
+        &symbol,
+        BookSide::Ask,
+        &depth.asks,
+        depth.event_time,
+    ));
+    for iceberg in &icebergs {
+        let iceberg_msg = format_iceberg_log(iceberg, &cfg.precision);
+        logging::signal("iceberg", &iceberg_msg);
+        let _ = tx.send(iceberg_msg);
+
+        build_and_send_enriched_payload(
+            tx,
+            correlation_service,
+            notifier,
+            "iceberg",
+            &symbol,
+            depth.event_time as i64,
+            json!({
+                "side": iceberg.side.as_label(),
+                "price": iceberg.price,
+                "refills": iceberg.refills,
+                "peak_qty": iceberg.peak_qty,
+                "current_qty": iceberg.current_qty,
+            }),
+        )
+        .await;
+    }
+
     let matched_bids = collect_big_levels(&depth.bids, cfg.big_trade_qty, 3);
     let matched_asks = collect_big_levels(&depth.asks, cfg.big_trade_qty, 3);
 
@@ -409,12 +1503,11 @@ async fn process_depth_update(
         qty_ok || notional_ok
     };
 
-    let extract_big_levels = |levels: &[[String; 2]]| {
+    let extract_big_levels = |levels: &[[Decimal; 2]]| {
         levels
             .iter()
             .filter_map(|level| {
-                let price = level[0].parse::<f64>().ok()?;
-                let qty = level[1].parse::<f64>().ok()?;
+                let (price, qty) = (level[0].to_f64(), level[1].to_f64());
                 if !price.is_finite() || !qty.is_finite() || price <= 0.0 || qty <= 0.0 {
                     return None;
                 }
@@ -463,11 +1556,23 @@ async fn process_depth_update(
 
     let top_bid = big_bids
         .first()
-        .map(|(price, qty)| format!("{:.2}x{:.3}", price, qty))
+        .map(|(price, qty)| {
+            format!(
+                "{}x{}",
+                cfg.precision.format_price(*price),
+                cfg.precision.format_qty(*qty)
+            )
+        })
         .unwrap_or_else(|| "-".to_string());
     let top_ask = big_asks
         .first()
-        .map(|(price, qty)| format!("{:.2}x{:.3}", price, qty))
+        .map(|(price, qty)| {
+            format!(
+                "{}x{}",
+                cfg.precision.format_price(*price),
+                cfg.precision.format_qty(*qty)
+            )
+        })
         .unwrap_or_else(|| "-".to_string());
 
     let pressure_bar = format_pressure_visual(bid_pressure_pct, 12);
@@ -479,6 +1584,7 @@ async fn process_depth_update(
         kind: MarketEventKind::DepthPressure,
         move_pct: pressure_move,
         notional: total_notional,
+        price: 0.0,
         direction: if dominant_side == "BUY" {
             1
         } else if dominant_side == "SELL" {
@@ -488,7 +1594,7 @@ async fn process_depth_update(
         },
     };
 
-    emit_correlation(correlation_engine.on_market_event(depth_market_event), tx);
+    let _ = merge.send(MergeEvent::Market(depth_market_event)).await;
 
     let depth_msg = format!(
         "[DEPTH] {} {} [{}] B:{:.1}% S:{:.1}% | notional {} vs {} | top {} / {}",
@@ -503,7 +1609,7 @@ async fn process_depth_update(
         top_ask
     );
 
-    println!("{}", depth_msg);
+    logging::signal("depth", &depth_msg);
     let _ = tx.send(depth_msg.clone());
 
     build_and_send_enriched_payload(
@@ -523,58 +1629,96 @@ async fn process_depth_update(
     )
     .await;
 
-    if let Some(detector) = big_move_detectors.get_mut(&symbol) {
+    // 0 between breakouts, so rules without `within` only see the start itself.
+    let mut big_move = 0.0;
+    if let Some(detectors) = big_move_detectors.get_mut(&symbol) {
+        let scale = volatility.map_or(1.0, |v| v.threshold_scale(&symbol));
         let snap = DepthSnapshot {
             bid_pressure_pct,
             total_notional,
+            timestamp_ms: depth.event_time,
         };
 
-        let evaluation = detector.push_with_self_explanation(snap);
-        println!("{}", evaluation.self_explanation_log);
-
-        match evaluation.signal {
-            BigMoveSignal::BullishBreakout {
-                avg_pressure,
-                total_notional,
-            } => {
-                let alert = format!(
-                    "[BIGMOVE] {} BULLISH BREAKOUT likely! avg_pressure={:.1}% notional={:.0}",
-                    depth.symbol.to_uppercase(),
-                    avg_pressure,
-                    total_notional
-                );
-                println!("{}", alert);
-                let _ = tx.send(alert);
-            }
-            BigMoveSignal::BearishBreakout {
-                avg_pressure,
-                total_notional,
-            } => {
-                let alert = format!(
-                    "[BIGMOVE] {} BEARISH BREAKOUT likely! avg_pressure={:.1}% notional={:.0}",
-                    depth.symbol.to_uppercase(),
-                    avg_pressure,
-                    total_notional
-                );
-                println!("{}", alert);
+        let mut started = None;
+        for detector in detectors.iter_mut() {
+            detector.set_pressure_scale(scale);
+            let evaluation = detector.push_with_self_explanation(snap.clone());
+            log::debug!(target: "big_move", "{}", evaluation.self_explanation_log);
+
+            if let Some(alert) =
+                format_big_move_alert(&depth.symbol, detector.profile(), &evaluation.signal)
+            {
+                if evaluation.signal.is_start() {
+                    started = evaluation.signal.direction();
+                }
+                logging::signal("big_move", &alert);
                 let _ = tx.send(alert);
             }
-            BigMoveSignal::None => {}
+        }
+
+        if let Some(direction) = started {
+            big_move = direction.sign() as f64;
+            let _ = merge
+                .send(MergeEvent::Signal(TrackedSignal {
+                    signal_type: "big_move",
+                    symbol: symbol.clone(),
+                    direction: direction.sign(),
+                    timestamp_ms: depth.event_time,
+                }))
+                .await;
+            let _ = merge
+                .send(MergeEvent::Move(MoveEvent {
+                    symbol: symbol.clone(),
+                    source: "big_move",
+                    start_ms: depth.event_time,
+                    end_ms: depth.event_time,
+                    move_pct: None,
+                }))
+                .await;
         }
     }
+
+    if let Some(engine) = rules {
+        let matches = engine.observe(
+            &symbol,
+            depth.event_time,
+            &[
+                ("bid_pressure_pct", bid_pressure_pct),
+                ("depth_notional", total_notional),
+                ("big_move", big_move),
+            ],
+        );
+        dispatch_rule_matches(matches, tx, correlation_service, notifier).await;
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn process_kline_event(
     event: &feeder_service::binance_kline::KlineEvent,
-    config_map: &HashMap<String, feeder_service::config::SymbolConfig>,
-    correlation_engine: &mut CorrelationEngine,
+    config_map: &HashMap<String, SymbolConfig>,
+    volatility: Option<&mut VolatilityTracker>,
+    rules: Option<&mut RuleEngine>,
+    merge: &mpsc::Sender<MergeEvent>,
     tx: &broadcast::Sender<String>,
     correlation_service: Option<&CorrelationService>,
     notifier: &NotificationFanout,
 ) {
     let symbol = event.symbol.to_lowercase();
-    if !config_map.contains_key(&symbol) {
+    let Some(cfg) = config_map.get(&symbol) else {
         return;
+    };
+
+    if let Some(tracker) = volatility
+        && event.kline.is_closed
+    {
+        tracker.record_kline(
+            &symbol,
+            event.kline.open_time,
+            event.kline.close_time,
+            event.kline.high.to_f64(),
+            event.kline.low.to_f64(),
+            event.kline.close.to_f64(),
+        );
     }
 
     if let Some(signal) = build_quant_signal_from_kline(event) {
@@ -584,6 +1728,7 @@ async fn process_kline_event(
             kind: MarketEventKind::KlineClose,
             move_pct: signal.return_pct.abs(),
             notional: signal.quote_volume,
+            price: event.kline.close.to_f64(),
             direction: if signal.return_pct > 0.0 {
                 1
             } else if signal.return_pct < 0.0 {
@@ -593,35 +1738,26 @@ async fn process_kline_event(
             },
         };
 
-        emit_correlation(correlation_engine.on_market_event(market_event), tx);
+        let _ = merge.send(MergeEvent::Market(market_event)).await;
 
-        let direction = if signal.return_pct > 0.0 {
-            "BULLISH"
-        } else if signal.return_pct < 0.0 {
-            "BEARISH"
-        } else {
-            "FLAT"
-        };
+        let msg = format_quant_log(&signal, &cfg.precision);
 
-        let msg = format!(
-            "[QUANT4H] {} {} | window={}..{} | O:{:.2} C:{:.2} H:{:.2} L:{:.2} ret={:+.2}% range={:.2}% taker_buy={:.1}% qvol={:.0} trades={}",
-            signal.symbol.to_uppercase(),
-            direction,
-            signal.interval_start_ms,
-            signal.interval_end_ms,
-            signal.open,
-            signal.close,
-            signal.high,
-            signal.low,
-            signal.return_pct,
-            signal.range_pct,
-            signal.taker_buy_ratio_pct,
-            signal.quote_volume,
-            signal.trade_count
-        );
-
-        println!("{}", msg);
+        logging::signal("quant4h", &msg);
         let _ = tx.send(msg);
+        let _ = merge
+            .send(MergeEvent::Signal(TrackedSignal {
+                signal_type: "quant4h",
+                symbol: symbol.clone(),
+                direction: if signal.return_pct > 0.0 {
+                    1
+                } else if signal.return_pct < 0.0 {
+                    -1
+                } else {
+                    0
+                },
+                timestamp_ms: signal.interval_end_ms,
+            }))
+            .await;
 
         build_and_send_enriched_payload(
             tx,
@@ -639,15 +1775,30 @@ async fn process_kline_event(
             }),
         )
         .await;
+
+        if let Some(engine) = rules {
+            let matches = engine.observe(
+                &symbol,
+                signal.interval_end_ms,
+                &[
+                    ("quant_return_pct", signal.return_pct),
+                    ("quant_range_pct", signal.range_pct),
+                    ("taker_buy_pct", signal.taker_buy_ratio_pct),
+                ],
+            );
+            dispatch_rule_matches(matches, tx, correlation_service, notifier).await;
+        }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn process_funding_rate_update(
     event: &feeder_service::binance_funding::FundingRateUpdate,
-    config_map: &HashMap<String, feeder_service::config::SymbolConfig>,
+    config_map: &HashMap<String, SymbolConfig>,
     config: &Config,
     last_funding_alert_ms: &mut HashMap<String, u64>,
-    correlation_engine: &mut CorrelationEngine,
+    rules: Option<&mut RuleEngine>,
+    merge: &mpsc::Sender<MergeEvent>,
     tx: &broadcast::Sender<String>,
     correlation_service: Option<&CorrelationService>,
     notifier: &NotificationFanout,
@@ -657,9 +1808,13 @@ async fn process_funding_rate_update(
         return;
     }
 
-    let Some(rate_pct) = funding_rate_pct(&event.funding_rate) else {
-        return;
-    };
+    let rate_pct = funding_rate_pct(&event.funding_rate);
+
+    // Rules see every funding update, not only the ones above the alert threshold.
+    if let Some(engine) = rules {
+        let matches = engine.observe(&symbol, event.event_time, &[("funding_rate_pct", rate_pct)]);
+        dispatch_rule_matches(matches, tx, correlation_service, notifier).await;
+    }
 
     if !is_high_funding_rate(rate_pct, config.funding_rate_alert_pct) {
         return;
@@ -681,6 +1836,7 @@ async fn process_funding_rate_update(
         kind: MarketEventKind::FundingRate,
         move_pct: rate_pct.abs(),
         notional: 0.0,
+        price: 0.0,
         direction: if rate_pct > 0.0 {
             1
         } else if rate_pct < 0.0 {
@@ -689,7 +1845,7 @@ async fn process_funding_rate_update(
             0
         },
     };
-    emit_correlation(correlation_engine.on_market_event(market_event), tx);
+    let _ = merge.send(MergeEvent::Market(market_event)).await;
 
     let side = if rate_pct >= 0.0 {
         "LONG_BIASED"
@@ -705,8 +1861,16 @@ async fn process_funding_rate_update(
         event.next_funding_time,
     );
 
-    println!("{}", msg);
+    logging::signal("funding", &msg);
     let _ = tx.send(msg);
+    let _ = merge
+        .send(MergeEvent::Signal(TrackedSignal {
+            signal_type: "funding",
+            symbol: symbol.clone(),
+            direction: if rate_pct >= 0.0 { 1 } else { -1 },
+            timestamp_ms: event.event_time,
+        }))
+        .await;
 
     build_and_send_enriched_payload(
         tx,
@@ -716,6 +1880,7 @@ async fn process_funding_rate_update(
         &symbol,
         event.event_time as i64,
         json!({
+            "funding_rate": event.funding_rate,
             "funding_rate_pct": rate_pct,
             "funding_rate_threshold_pct": config.funding_rate_alert_pct,
             "next_funding_time": event.next_funding_time,
@@ -738,7 +1903,7 @@ async fn build_and_send_enriched_payload(
         return;
     };
 
-    if let Ok(correlation) = service.correlate(symbol, event_timestamp) {
+    if let Ok(correlation) = service.correlate(symbol, event_timestamp).await {
         let payload = build_signal_notification(
             signal_type,
             symbol,
@@ -752,8 +1917,12 @@ async fn build_and_send_enriched_payload(
     }
 }
 
-async fn run_news_ingest_loop(news_config: NewsConfig) -> anyhow::Result<()> {
-    let store = NewsStore::new(news_config.db_path.clone());
+async fn run_news_ingest_loop(
+    news_config: NewsConfig,
+    store: NewsStore,
+    tagger: SymbolTagger,
+    shutdown: Shutdown,
+) -> anyhow::Result<()> {
     store.init()?;
 
     let http = reqwest::Client::builder()
@@ -764,17 +1933,26 @@ async fn run_news_ingest_loop(news_config: NewsConfig) -> anyhow::Result<()> {
     let mut ticker = interval(Duration::from_secs(news_config.poll_interval_secs.max(30)));
 
     if !news_config.has_provider_api_key() {
-        eprintln!(
-            "[news] no provider API key configured; fetched will stay 0 until FINNHUB_API_KEY or NEWSAPI_API_KEY is set"
+        log::warn!(
+            target: "news",
+            "no provider API key configured; fetched will stay 0 until FINNHUB_API_KEY or NEWSAPI_API_KEY is set"
         );
     }
 
     loop {
-        ticker.tick().await;
+        // Shutdown is only observed between polls so a batch is never cut off mid-write.
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown.wait() => {
+                log::info!(target: "news", "ingest loop stopped");
+                return Ok(());
+            }
+        }
 
         if !news_config.has_provider_api_key() {
-            println!(
-                "[news] fetched=0 inserted=0 pruned=0 db={} reason=no_provider_api_key providers=finnhub=disabled;newsapi=disabled",
+            log::info!(
+                target: "news",
+                "fetched=0 inserted=0 pruned=0 db={} reason=no_provider_api_key providers=finnhub=disabled;newsapi=disabled",
                 news_config.db_path,
             );
             continue;
@@ -782,22 +1960,24 @@ async fn run_news_ingest_loop(news_config: NewsConfig) -> anyhow::Result<()> {
 
         let (mut fetched, diagnostics) = fetch_all_news(&http, &news_config).await?;
         for item in &mut fetched {
-            tag_symbols(item);
+            tagger.tag(item);
         }
 
         fetched.sort_by_key(|item| item.published_at);
 
-        let inserted = store.upsert_many(&fetched)?;
+        let fetched_count = fetched.len();
+        let inserted = store.upsert_many_async(fetched).await?;
 
         let retention_cutoff =
             chrono::Utc::now().timestamp() - (news_config.retention_hours * 3600);
-        let pruned = store.prune_older_than(retention_cutoff)?;
+        let pruned = store.prune_older_than_async(retention_cutoff).await?;
 
-        let reason = diagnostics.fetch_reason(fetched.len());
+        let reason = diagnostics.fetch_reason(fetched_count);
 
-        println!(
-            "[news] fetched={} inserted={} pruned={} db={} reason={} providers={}",
-            fetched.len(),
+        log::info!(
+            target: "news",
+            "fetched={} inserted={} pruned={} db={} reason={} providers={}",
+            fetched_count,
             inserted,
             pruned,
             news_config.db_path,
@@ -822,8 +2002,12 @@ fn emit_correlation(
         MarketEventKind::FundingRate => "funding",
     };
 
+    let market = match (signal.market_regime, signal.market_pct_up) {
+        (Some(regime), Some(pct_up)) => format!(" market={}({:.0}% up)", regime.as_str(), pct_up),
+        _ => String::new(),
+    };
     let corr_msg = format!(
-        "[NEWS_CORR] {} kind={} conf={:.2} lag={}ms move={:+.3}% notional={:.0} windows=5m:{} 15m:{} 1h:{} headline=\"{}\"",
+        "[NEWS_CORR] {} kind={} conf={:.2} lag={}ms move={:+.3}% notional={:.0} windows=5m:{} 15m:{} 1h:{}{} headline=\"{}\"",
         signal.symbol.to_uppercase(),
         kind,
         signal.confidence,
@@ -833,9 +2017,10 @@ fn emit_correlation(
         signal.window_5m_count,
         signal.window_15m_count,
         signal.window_1h_count,
+        market,
         signal.news_headline,
     );
 
-    println!("{}", corr_msg);
+    logging::signal("news_corr", &corr_msg);
     let _ = tx.send(corr_msg);
 }
@@ -1,6 +1,14 @@
 use crate::news::store::{NewsRecord, NewsStore};
 use anyhow::Result;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Extra time past the lookahead fetched into the cache, so later events reuse the same rows.
+const CACHE_SLACK_MS: i64 = 60_000;
+/// Rows fetched per cache fill.
+const CACHE_FETCH_LIMIT: usize = 200;
 
 #[derive(Debug, Clone)]
 pub struct CorrelationService {
@@ -8,6 +16,37 @@ pub struct CorrelationService {
     default_lookback_ms: i64,
     default_lookahead_ms: i64,
     max_matches: usize,
+    cache_ttl: Duration,
+    cache: Arc<Mutex<HashMap<String, CachedNews>>>,
+}
+
+/// Recent rows for one symbol covering `(covered_from, covered_to]` in published time.
+#[derive(Debug)]
+struct CachedNews {
+    generation: u64,
+    fetched_at: Instant,
+    covered_from: i64,
+    covered_to: i64,
+    records: Vec<NewsRecord>,
+}
+
+impl CachedNews {
+    fn covers(&self, from_ts: i64, to_ts: i64, generation: u64, ttl: Duration) -> bool {
+        self.generation == generation
+            && self.fetched_at.elapsed() < ttl
+            && from_ts > self.covered_from
+            && to_ts <= self.covered_to
+    }
+
+    fn select(&self, from_ts: i64, to_ts: i64, limit: usize) -> Vec<NewsRecord> {
+        // Records are kept newest first, matching the store's ordering.
+        self.records
+            .iter()
+            .filter(|record| record.published_at >= from_ts && record.published_at <= to_ts)
+            .take(limit.max(1))
+            .cloned()
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -37,9 +76,17 @@ impl CorrelationService {
             default_lookback_ms,
             default_lookahead_ms,
             max_matches,
+            cache_ttl: Duration::from_secs(5),
+            cache: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// How long cached rows per symbol are reused; zero disables the cache.
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
     pub fn from_env(store: NewsStore) -> Self {
         let lookback_secs = std::env::var("NEWS_CORRELATION_LOOKBACK_SECS")
             .ok()
@@ -54,20 +101,29 @@ impl CorrelationService {
             .and_then(|v| v.parse::<usize>().ok())
             .unwrap_or(5);
 
+        let cache_ttl_ms = std::env::var("NEWS_CORRELATION_CACHE_TTL_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(5_000);
+
         Self::new(
             store,
             lookback_secs * 1000,
             lookahead_secs * 1000,
             max_matches,
         )
+        .with_cache_ttl(Duration::from_millis(cache_ttl_ms))
     }
 
-    pub fn correlate(&self, symbol: &str, event_ts_ms: i64) -> Result<CorrelationResult> {
+    /// Matches news around `event_ts_ms`. Served from the per-symbol cache when possible;
+    /// otherwise the store is queried on the blocking thread pool.
+    pub async fn correlate(&self, symbol: &str, event_ts_ms: i64) -> Result<CorrelationResult> {
         let from_ts = event_ts_ms - self.default_lookback_ms;
         let to_ts = event_ts_ms + self.default_lookahead_ms;
-        let records = self
-            .store
-            .get_recent_by_symbol(symbol, from_ts, to_ts, self.max_matches)?;
+        let records = match self.cached_records(symbol, from_ts, to_ts) {
+            Some(records) => records,
+            None => self.fill_cache(symbol, from_ts, to_ts).await?,
+        };
 
         Ok(CorrelationResult {
             lookback_ms: self.default_lookback_ms,
@@ -85,6 +141,125 @@ impl CorrelationService {
     }
 }
 
+impl CorrelationService {
+    fn cached_records(&self, symbol: &str, from_ts: i64, to_ts: i64) -> Option<Vec<NewsRecord>> {
+        if self.cache_ttl.is_zero() {
+            return None;
+        }
+        let generation = self.store.generation();
+        let cache = self.cache.lock().expect("news cache poisoned");
+        cache
+            .get(&symbol.to_ascii_uppercase())
+            .filter(|entry| entry.covers(from_ts, to_ts, generation, self.cache_ttl))
+            .map(|entry| entry.select(from_ts, to_ts, self.max_matches))
+    }
+
+    async fn fill_cache(&self, symbol: &str, from_ts: i64, to_ts: i64) -> Result<Vec<NewsRecord>> {
+        if self.cache_ttl.is_zero() {
+            return self
+                .store
+                .get_recent_by_symbol_async(symbol, from_ts, to_ts, self.max_matches)
+                .await;
+        }
+
+        let generation = self.store.generation();
+        let fetch_to = to_ts.saturating_add(CACHE_SLACK_MS);
+        let fetched = self
+            .store
+            .get_recent_by_symbol_async(symbol, from_ts, fetch_to, CACHE_FETCH_LIMIT)
+            .await?;
+
+        // A truncated fetch only covers rows newer than the oldest one returned.
+        let covered_from = if fetched.len() >= CACHE_FETCH_LIMIT {
+            fetched
+                .last()
+                .map(|record| record.published_at)
+                .unwrap_or(from_ts)
+        } else {
+            from_ts - 1
+        };
+        let entry = CachedNews {
+            generation,
+            fetched_at: Instant::now(),
+            covered_from,
+            covered_to: fetch_to,
+            records: fetched,
+        };
+        let records = entry.select(from_ts, to_ts, self.max_matches);
+        self.cache
+            .lock()
+            .expect("news cache poisoned")
+            .insert(symbol.to_ascii_uppercase(), entry);
+        Ok(records)
+    }
+}
+
 fn correlation_score(records: &[NewsRecord]) -> f64 {
     (records.len() as f64).min(5.0) / 5.0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::news::types::NewsItem;
+
+    fn item(id: &str, published_at: i64) -> NewsItem {
+        NewsItem {
+            id: id.to_string(),
+            source: "test".to_string(),
+            published_at,
+            title: format!("Bitcoin headline {id}"),
+            summary: String::new(),
+            url: format!("https://example.com/{id}"),
+            symbols: vec!["BTCUSDT".to_string()],
+            sentiment_score: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_repeat_lookups_from_cache_and_invalidates_on_write() {
+        let db_path = std::env::temp_dir()
+            .join(format!(
+                "feeder-service-corr-cache-{}.sqlite",
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .expect("valid time")
+                    .as_nanos()
+            ))
+            .to_string_lossy()
+            .to_string();
+        let store = NewsStore::new(db_path.clone());
+        store.init().expect("init");
+        store.upsert_many(&[item("a", 1_000_000)]).expect("seed");
+
+        let service = CorrelationService::new(store.clone(), 60_000, 60_000, 5)
+            .with_cache_ttl(Duration::from_secs(60));
+        let first = service
+            .correlate("btcusdt", 1_000_500)
+            .await
+            .expect("first");
+        assert_eq!(first.matches.len(), 1);
+
+        // Rows removed behind the store's back are still served from cache.
+        rusqlite::Connection::open(&db_path)
+            .expect("open")
+            .execute("DELETE FROM news_item_symbols", [])
+            .expect("delete");
+        let cached = service
+            .correlate("btcusdt", 1_001_000)
+            .await
+            .expect("cached");
+        assert_eq!(cached.matches.len(), 1);
+
+        // A write through the store bumps the generation and forces a reload.
+        store.upsert_many(&[item("b", 1_000_900)]).expect("insert");
+        let reloaded = service
+            .correlate("btcusdt", 1_001_000)
+            .await
+            .expect("reload");
+        assert_eq!(reloaded.matches.len(), 1);
+        assert_eq!(reloaded.matches[0].headline, "Bitcoin headline b");
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+}
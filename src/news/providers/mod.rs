@@ -88,7 +88,7 @@ pub async fn fetch_all_news(
             Ok(mut provider_items) => items.append(&mut provider_items),
             Err(err) => {
                 diagnostics.finnhub = ProviderStatus::Failed;
-                eprintln!("[news] finnhub fetch failed: {err}");
+                log::warn!(target: "news", "finnhub fetch failed: {err}");
             }
         }
     }
@@ -99,7 +99,7 @@ pub async fn fetch_all_news(
             Ok(mut provider_items) => items.append(&mut provider_items),
             Err(err) => {
                 diagnostics.newsapi = ProviderStatus::Failed;
-                eprintln!("[news] newsapi fetch failed: {err}");
+                log::warn!(target: "news", "newsapi fetch failed: {err}");
             }
         }
     }
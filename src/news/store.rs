@@ -3,6 +3,13 @@ use anyhow::Result;
 use rusqlite::{Connection, params};
 use std::collections::{HashSet, hash_map::DefaultHasher};
 use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Idle connections kept open for reuse; extra connections opened under load are closed on return.
+const MAX_IDLE_CONNECTIONS: usize = 4;
+const STATEMENT_CACHE_CAPACITY: usize = 32;
+const BUSY_TIMEOUT_MS: u64 = 5_000;
 
 #[derive(Debug, Clone)]
 pub struct NewsRecord {
@@ -16,20 +23,68 @@ pub struct NewsRecord {
     pub sentiment_score: Option<f64>,
 }
 
+/// SQLite-backed news store.
+///
+/// Clones share a small pool of long-lived WAL connections with prepared statement caches,
+/// and a write generation counter that readers use to invalidate cached results. The
+/// `*_async` methods run the same queries on Tokio's blocking thread pool.
 #[derive(Debug, Clone)]
 pub struct NewsStore {
     db_path: String,
+    idle: Arc<Mutex<Vec<Connection>>>,
+    generation: Arc<AtomicU64>,
 }
 
 impl NewsStore {
     pub fn new(db_path: impl Into<String>) -> Self {
         Self {
             db_path: db_path.into(),
+            idle: Arc::new(Mutex::new(Vec::new())),
+            generation: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Incremented after every write, so caches can tell when their contents are stale.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    fn open_connection(&self) -> Result<Connection> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.busy_timeout(std::time::Duration::from_millis(BUSY_TIMEOUT_MS))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+        Ok(conn)
+    }
+
+    fn with_connection<T>(&self, f: impl FnOnce(&mut Connection) -> Result<T>) -> Result<T> {
+        let pooled = self.idle.lock().expect("news store pool poisoned").pop();
+        let mut conn = match pooled {
+            Some(conn) => conn,
+            None => self.open_connection()?,
+        };
+
+        let result = f(&mut conn);
+
+        let mut idle = self.idle.lock().expect("news store pool poisoned");
+        if idle.len() < MAX_IDLE_CONNECTIONS {
+            idle.push(conn);
         }
+        result
+    }
+
+    fn bump_generation(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
     }
 
     pub fn init(&self) -> Result<()> {
-        let mut conn = Connection::open(&self.db_path)?;
+        self.with_connection(Self::init_schema)?;
+        self.bump_generation();
+        Ok(())
+    }
+
+    fn init_schema(conn: &mut Connection) -> Result<()> {
         let tx = conn.transaction()?;
 
         tx.execute_batch(
@@ -94,7 +149,17 @@ impl NewsStore {
     }
 
     pub fn upsert_many(&self, items: &[NewsItem]) -> Result<usize> {
-        let mut conn = Connection::open(&self.db_path)?;
+        let inserted = self.with_connection(|conn| Self::upsert_items(conn, items))?;
+        self.bump_generation();
+        Ok(inserted)
+    }
+
+    pub async fn upsert_many_async(&self, items: Vec<NewsItem>) -> Result<usize> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.upsert_many(&items)).await?
+    }
+
+    fn upsert_items(conn: &mut Connection, items: &[NewsItem]) -> Result<usize> {
         let tx = conn.transaction()?;
         let mut inserted = 0usize;
 
@@ -103,14 +168,15 @@ impl NewsStore {
             let symbols = normalize_symbols(&item.symbols);
             let symbols_json = serde_json::to_string(&symbols)?;
 
-            let changed = tx.execute(
+            let changed = tx.prepare_cached(
                 "
                 INSERT OR IGNORE INTO news_items
                     (provider, article_id, published_at, title, summary, url, url_hash, symbols, sentiment_score)
                 VALUES
                     (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
                 ",
-                params![
+            )?
+            .execute(params![
                     item.source,
                     item.id,
                     item.published_at,
@@ -119,27 +185,26 @@ impl NewsStore {
                     item.url,
                     url_hash,
                     symbols_json,
-                    item.sentiment_score,
-                ],
-            )?;
+                item.sentiment_score,
+            ])?;
             inserted += changed;
 
-            let news_item_id: i64 = tx.query_row(
-                "SELECT id FROM news_items WHERE provider = ?1 AND article_id = ?2",
-                params![item.source, item.id],
-                |row| row.get(0),
-            )?;
+            let news_item_id: i64 = tx
+                .prepare_cached(
+                    "SELECT id FROM news_items WHERE provider = ?1 AND article_id = ?2",
+                )?
+                .query_row(params![item.source, item.id], |row| row.get(0))?;
 
+            let mut symbol_stmt = tx.prepare_cached(
+                "
+                INSERT INTO news_item_symbols (news_item_id, symbol, published_at)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT(news_item_id, symbol)
+                DO UPDATE SET published_at = excluded.published_at
+                ",
+            )?;
             for symbol in symbols {
-                tx.execute(
-                    "
-                    INSERT INTO news_item_symbols (news_item_id, symbol, published_at)
-                    VALUES (?1, ?2, ?3)
-                    ON CONFLICT(news_item_id, symbol)
-                    DO UPDATE SET published_at = excluded.published_at
-                    ",
-                    params![news_item_id, symbol, item.published_at],
-                )?;
+                symbol_stmt.execute(params![news_item_id, symbol, item.published_at])?;
             }
         }
 
@@ -148,7 +213,17 @@ impl NewsStore {
     }
 
     pub fn prune_older_than(&self, min_published_at: i64) -> Result<usize> {
-        let mut conn = Connection::open(&self.db_path)?;
+        let deleted = self.with_connection(|conn| Self::prune(conn, min_published_at))?;
+        self.bump_generation();
+        Ok(deleted)
+    }
+
+    pub async fn prune_older_than_async(&self, min_published_at: i64) -> Result<usize> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.prune_older_than(min_published_at)).await?
+    }
+
+    fn prune(conn: &mut Connection, min_published_at: i64) -> Result<usize> {
         let tx = conn.transaction()?;
 
         tx.execute(
@@ -177,9 +252,33 @@ impl NewsStore {
         to_ts: i64,
         limit: usize,
     ) -> Result<Vec<NewsRecord>> {
-        let conn = Connection::open(&self.db_path)?;
+        self.with_connection(|conn| Self::query_recent(conn, symbol, from_ts, to_ts, limit))
+    }
+
+    pub async fn get_recent_by_symbol_async(
+        &self,
+        symbol: &str,
+        from_ts: i64,
+        to_ts: i64,
+        limit: usize,
+    ) -> Result<Vec<NewsRecord>> {
+        let store = self.clone();
+        let symbol = symbol.to_string();
+        tokio::task::spawn_blocking(move || {
+            store.get_recent_by_symbol(&symbol, from_ts, to_ts, limit)
+        })
+        .await?
+    }
+
+    fn query_recent(
+        conn: &mut Connection,
+        symbol: &str,
+        from_ts: i64,
+        to_ts: i64,
+        limit: usize,
+    ) -> Result<Vec<NewsRecord>> {
         let query_limit = limit.max(1) as i64;
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "
             SELECT ni.provider, ni.article_id, ni.published_at, ni.title, ni.summary, ni.url, ni.symbols, ni.sentiment_score
             FROM news_item_symbols nis
@@ -1,4 +1,7 @@
+use std::collections::HashMap;
+
 use crate::news::types::NewsItem;
+use crate::symbols::SymbolRegistry;
 
 const SYMBOL_ALIASES: [(&str, &str); 12] = [
     ("btc", "btcusdt"),
@@ -15,15 +18,109 @@ const SYMBOL_ALIASES: [(&str, &str); 12] = [
     ("dogecoin", "dogeusdt"),
 ];
 
+/// Shortest bare uppercase ticker matched; shorter ones need a `$` prefix (`$OP`).
+const MIN_BARE_TICKER_LEN: usize = 3;
+
+/// Tags news with symbols from the built-in aliases plus, when a registry is loaded, every
+/// listed base asset mentioned as a ticker (`PEPE`, `$ARB`).
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTagger {
+    /// Uppercase base asset -> symbol.
+    tickers: HashMap<String, String>,
+}
+
+impl SymbolTagger {
+    pub fn from_registry(registry: &SymbolRegistry, preferred: &[String]) -> Self {
+        let tickers = registry
+            .base_asset_map(preferred)
+            .into_iter()
+            .map(|(base, symbol)| (base.to_uppercase(), symbol))
+            .collect();
+        Self { tickers }
+    }
+
+    pub fn ticker_count(&self) -> usize {
+        self.tickers.len()
+    }
+
+    pub fn tag(&self, item: &mut NewsItem) {
+        let text = format!("{} {}", item.title, item.summary);
+        let haystack = text.to_lowercase();
+        let mut symbols: Vec<String> = SYMBOL_ALIASES
+            .iter()
+            .filter_map(|(alias, canonical)| haystack.contains(alias).then_some(*canonical))
+            .map(str::to_string)
+            .collect();
+
+        if !self.tickers.is_empty() {
+            symbols.extend(
+                ticker_tokens(&text).filter_map(|ticker| self.tickers.get(ticker).cloned()),
+            );
+        }
+
+        symbols.sort();
+        symbols.dedup();
+        item.symbols = symbols;
+    }
+}
+
+/// Uppercase alphanumeric words, case-sensitive so ordinary words are not read as tickers.
+fn ticker_tokens(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '$'))
+        .filter_map(|token| {
+            let (dollar, ticker) = match token.strip_prefix('$') {
+                Some(rest) => (true, rest),
+                None => (false, token),
+            };
+            let is_ticker = !ticker.is_empty()
+                && ticker.bytes().any(|b| b.is_ascii_uppercase())
+                && ticker
+                    .bytes()
+                    .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit());
+            (is_ticker && (dollar || ticker.len() >= MIN_BARE_TICKER_LEN)).then_some(ticker)
+        })
+}
+
 pub fn tag_symbols(item: &mut NewsItem) {
-    let haystack = format!("{} {}", item.title, item.summary).to_lowercase();
-    let mut symbols: Vec<String> = SYMBOL_ALIASES
-        .iter()
-        .filter_map(|(alias, canonical)| haystack.contains(alias).then_some(*canonical))
-        .map(str::to_string)
-        .collect();
-
-    symbols.sort();
-    symbols.dedup();
-    item.symbols = symbols;
+    SymbolTagger::default().tag(item);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(title: &str) -> NewsItem {
+        NewsItem {
+            id: "1".to_string(),
+            source: "test".to_string(),
+            published_at: 0,
+            title: title.to_string(),
+            summary: String::new(),
+            url: String::new(),
+            symbols: Vec::new(),
+            sentiment_score: None,
+        }
+    }
+
+    #[test]
+    fn registry_tickers_extend_builtin_aliases() {
+        let registry = SymbolRegistry::from_exchange_info(include_str!(
+            "../../tests/fixtures/exchange_info.json"
+        ))
+        .expect("fixture parses");
+        let tagger = SymbolTagger::from_registry(&registry, &[]);
+
+        let mut story = item("PEPE rallies as Bitcoin holds; $SOL flat, pepe fans cheer");
+        tagger.tag(&mut story);
+        assert_eq!(story.symbols, vec!["btcusdt", "pepeusdt", "solusdt"]);
+
+        // Delisted bases and lowercase words are not tagged.
+        let mut story = item("LUNA returns? One more pepe meme");
+        tagger.tag(&mut story);
+        assert!(story.symbols.is_empty());
+
+        let mut story = item("PEPE rallies");
+        tag_symbols(&mut story);
+        assert!(story.symbols.is_empty());
+    }
 }
@@ -50,7 +50,10 @@ impl TelegramNotifier {
 
     pub async fn run(mut self, mut rx: broadcast::Receiver<String>) {
         if !self.is_ready() {
-            eprintln!("[telegram] notifier disabled or misconfigured; skipping task startup");
+            log::warn!(
+                target: "telegram",
+                "notifier disabled or misconfigured; skipping task startup",
+            );
             return;
         }
 
@@ -66,21 +69,23 @@ impl TelegramNotifier {
 
                         let text = format_telegram_message(&event);
                         if let Err(err) = self.send_with_retry(&text).await {
-                            eprintln!(
-                                "[telegram] failed to deliver {} alert for {}: {}",
+                            log::warn!(
+                                target: "telegram",
+                                "failed to deliver {} alert for {}: {}",
                                 event.alert_type, event.symbol, err
                             );
                         }
                     }
                 }
                 Err(broadcast::error::RecvError::Lagged(skipped)) => {
-                    eprintln!(
-                        "[telegram] lagged on broadcast channel; skipped {} messages",
+                    log::warn!(
+                        target: "telegram",
+                        "lagged on broadcast channel; skipped {} messages",
                         skipped
                     );
                 }
                 Err(broadcast::error::RecvError::Closed) => {
-                    eprintln!("[telegram] broadcast channel closed; notifier exiting");
+                    log::warn!(target: "telegram", "broadcast channel closed; notifier exiting");
                     break;
                 }
             }
@@ -97,10 +102,10 @@ impl TelegramNotifier {
         self.dedupe_state
             .retain(|_, ts| now.duration_since(*ts) <= debounce_window);
 
-        if let Some(last) = self.dedupe_state.get(key) {
-            if now.duration_since(*last) < debounce_window {
-                return true;
-            }
+        if let Some(last) = self.dedupe_state.get(key)
+            && now.duration_since(*last) < debounce_window
+        {
+            return true;
         }
 
         self.dedupe_state.insert(key.to_string(), now);
@@ -132,15 +137,17 @@ impl TelegramNotifier {
                     }
 
                     last_error = format!("telegram api status {}", status);
-                    eprintln!(
-                        "[telegram] sendMessage non-success status={} attempt={}/{}",
+                    log::warn!(
+                        target: "telegram",
+                        "sendMessage non-success status={} attempt={}/{}",
                         status, attempt, max_attempts
                     );
                 }
                 Err(err) => {
                     last_error = err.to_string();
-                    eprintln!(
-                        "[telegram] transport error attempt={}/{}: {}",
+                    log::warn!(
+                        target: "telegram",
+                        "transport error attempt={}/{}: {}",
                         attempt, max_attempts, err
                     );
                 }
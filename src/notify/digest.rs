@@ -0,0 +1,396 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::json;
+
+use super::SignalNotification;
+use crate::config::DigestConfig;
+
+/// What the fanout should do with an offered signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestDecision {
+    /// Quiet period: deliver the alert as-is.
+    Deliver,
+    /// Storm: the alert was folded into the symbol's pending digest.
+    Batched,
+}
+
+#[derive(Debug, Clone)]
+pub struct DigestMessage {
+    pub symbol: String,
+    pub window_start_ms: i64,
+    pub window_end_ms: i64,
+    pub count: usize,
+    pub counts_by_type: BTreeMap<String, usize>,
+    pub max_move_pct: Option<f64>,
+    pub strongest_correlation: f64,
+    pub top_headlines: Vec<String>,
+    pub ws_payload: String,
+    pub telegram_message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Escalation {
+    pub symbol: String,
+    pub storm_since_ms: i64,
+    pub duration_ms: i64,
+    pub ws_payload: String,
+    pub telegram_message: String,
+}
+
+#[derive(Debug, Default)]
+pub struct DigestFlush {
+    pub digests: Vec<DigestMessage>,
+    pub escalations: Vec<Escalation>,
+}
+
+#[derive(Debug)]
+struct SymbolWindow {
+    started_at_ms: i64,
+    seen: usize,
+    pending: Vec<SignalNotification>,
+}
+
+#[derive(Debug)]
+struct StormState {
+    since_ms: i64,
+    last_batched_ms: i64,
+    escalated: bool,
+}
+
+/// Groups notifications per symbol over a fixed window.
+///
+/// The first `immediate_limit` signals in a window are delivered straight away; anything
+/// beyond that is held back and summarised into one digest when the window closes. A symbol
+/// that keeps overflowing its window for `escalation_after_secs` raises a single escalation
+/// until it goes quiet for a full window again.
+#[derive(Debug)]
+pub struct DigestBatcher {
+    config: DigestConfig,
+    windows: HashMap<String, SymbolWindow>,
+    storms: HashMap<String, StormState>,
+}
+
+impl DigestBatcher {
+    pub fn new(config: DigestConfig) -> Self {
+        Self {
+            config,
+            windows: HashMap::new(),
+            storms: HashMap::new(),
+        }
+    }
+
+    fn window_ms(&self) -> i64 {
+        (self.config.window_secs.max(1) as i64) * 1_000
+    }
+
+    pub fn offer(&mut self, notification: &SignalNotification, now_ms: i64) -> DigestDecision {
+        let window_ms = self.window_ms();
+        let symbol = notification.symbol.to_lowercase();
+        let window = self
+            .windows
+            .entry(symbol.clone())
+            .or_insert_with(|| SymbolWindow {
+                started_at_ms: now_ms,
+                seen: 0,
+                pending: Vec::new(),
+            });
+
+        // A stale window without pending work can simply restart; one with pending work is
+        // left for `flush_due`, which the caller runs before offering.
+        if now_ms - window.started_at_ms >= window_ms && window.pending.is_empty() {
+            window.started_at_ms = now_ms;
+            window.seen = 0;
+        }
+
+        window.seen += 1;
+        if window.seen <= self.config.immediate_limit {
+            return DigestDecision::Deliver;
+        }
+
+        window.pending.push(notification.clone());
+        let storm = self.storms.entry(symbol).or_insert(StormState {
+            since_ms: now_ms,
+            last_batched_ms: now_ms,
+            escalated: false,
+        });
+        storm.last_batched_ms = now_ms;
+        DigestDecision::Batched
+    }
+
+    /// Closes every expired window and reports digests and escalations that became due.
+    pub fn flush_due(&mut self, now_ms: i64) -> DigestFlush {
+        let window_ms = self.window_ms();
+        let mut flush = DigestFlush::default();
+
+        let expired: Vec<String> = self
+            .windows
+            .iter()
+            .filter(|(_, window)| now_ms - window.started_at_ms >= window_ms)
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+
+        for symbol in expired {
+            let Some(window) = self.windows.remove(&symbol) else {
+                continue;
+            };
+            if !window.pending.is_empty() {
+                flush.digests.push(self.build_digest(
+                    &symbol,
+                    window.started_at_ms,
+                    window.started_at_ms + window_ms,
+                    &window.pending,
+                ));
+            }
+        }
+
+        self.storms
+            .retain(|_, storm| now_ms - storm.last_batched_ms < window_ms);
+
+        let escalation_ms = (self.config.escalation_after_secs as i64) * 1_000;
+        if escalation_ms > 0 {
+            for (symbol, storm) in self.storms.iter_mut() {
+                let duration_ms = now_ms - storm.since_ms;
+                if storm.escalated || duration_ms < escalation_ms {
+                    continue;
+                }
+                storm.escalated = true;
+                flush
+                    .escalations
+                    .push(build_escalation(symbol, storm.since_ms, duration_ms));
+            }
+        }
+
+        flush
+    }
+
+    /// Closes every open window early, e.g. on shutdown, so batched alerts are not lost.
+    pub fn flush_pending(&mut self, now_ms: i64) -> DigestFlush {
+        let mut flush = DigestFlush::default();
+        for (symbol, window) in std::mem::take(&mut self.windows) {
+            if !window.pending.is_empty() {
+                flush.digests.push(self.build_digest(
+                    &symbol,
+                    window.started_at_ms,
+                    now_ms,
+                    &window.pending,
+                ));
+            }
+        }
+        flush
+    }
+
+    fn build_digest(
+        &self,
+        symbol: &str,
+        window_start_ms: i64,
+        window_end_ms: i64,
+        pending: &[SignalNotification],
+    ) -> DigestMessage {
+        let mut counts_by_type: BTreeMap<String, usize> = BTreeMap::new();
+        for notification in pending {
+            *counts_by_type
+                .entry(notification.signal_type.clone())
+                .or_default() += 1;
+        }
+
+        let max_move_pct = pending
+            .iter()
+            .filter_map(|n| n.move_pct)
+            .max_by(|a, b| a.abs().total_cmp(&b.abs()));
+        let strongest_correlation = pending
+            .iter()
+            .map(|n| n.correlation_score)
+            .fold(0.0_f64, f64::max);
+
+        let mut ranked: Vec<&SignalNotification> = pending.iter().collect();
+        ranked.sort_by(|a, b| b.correlation_score.total_cmp(&a.correlation_score));
+        let mut top_headlines: Vec<String> = Vec::new();
+        for headline in ranked.iter().flat_map(|n| n.headlines.iter()) {
+            if top_headlines.len() >= self.config.max_headlines {
+                break;
+            }
+            if !top_headlines.contains(headline) {
+                top_headlines.push(headline.clone());
+            }
+        }
+
+        let symbol_upper = symbol.to_uppercase();
+        let ws_payload = json!({
+            "signal_type": "digest",
+            "symbol": symbol_upper,
+            "window_start_ms": window_start_ms,
+            "window_end_ms": window_end_ms,
+            "count": pending.len(),
+            "counts_by_type": counts_by_type,
+            "max_move_pct": max_move_pct,
+            "strongest_correlation": strongest_correlation,
+            "top_headlines": top_headlines,
+        })
+        .to_string();
+
+        let mut lines = vec![
+            format!("🗞 {} DIGEST", symbol_upper),
+            format!(
+                "{} batched signals in {}s: {}",
+                pending.len(),
+                (window_end_ms - window_start_ms) / 1_000,
+                counts_by_type
+                    .iter()
+                    .map(|(kind, count)| format!("{kind}×{count}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            match max_move_pct {
+                Some(pct) => format!("Max move: {:+.2}%", pct),
+                None => "Max move: n/a".to_string(),
+            },
+            format!("Strongest correlation: {:.2}", strongest_correlation),
+            "Top headlines:".to_string(),
+        ];
+        if top_headlines.is_empty() {
+            lines.push("• No matched headlines".to_string());
+        }
+        lines.extend(top_headlines.iter().map(|h| format!("• {}", h)));
+
+        DigestMessage {
+            symbol: symbol.to_string(),
+            window_start_ms,
+            window_end_ms,
+            count: pending.len(),
+            counts_by_type,
+            max_move_pct,
+            strongest_correlation,
+            top_headlines,
+            ws_payload,
+            telegram_message: lines.join("\n"),
+        }
+    }
+}
+
+fn build_escalation(symbol: &str, storm_since_ms: i64, duration_ms: i64) -> Escalation {
+    let symbol_upper = symbol.to_uppercase();
+    let ws_payload = json!({
+        "signal_type": "escalation",
+        "symbol": symbol_upper,
+        "storm_since_ms": storm_since_ms,
+        "duration_ms": duration_ms,
+    })
+    .to_string();
+
+    Escalation {
+        symbol: symbol.to_string(),
+        storm_since_ms,
+        duration_ms,
+        ws_payload,
+        telegram_message: format!(
+            "🚨 {} ESCALATION\nAlert storm ongoing for {}m (since {})",
+            symbol_upper,
+            duration_ms / 60_000,
+            storm_since_ms
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::news::correlation::MatchedNews;
+    use crate::notify::build_signal_notification;
+
+    fn config() -> DigestConfig {
+        DigestConfig {
+            enabled: true,
+            window_secs: 60,
+            immediate_limit: 2,
+            escalation_after_secs: 120,
+            escalation_chat_id: None,
+            max_headlines: 2,
+        }
+    }
+
+    fn signal(spike: f64, score: f64, headline: &str) -> SignalNotification {
+        build_signal_notification(
+            "agg_trade",
+            "btcusdt",
+            0,
+            serde_json::json!({ "spike_pct": spike }),
+            &[MatchedNews {
+                headline: headline.to_string(),
+                url: "https://example.com".to_string(),
+                published_at: 0,
+            }],
+            score,
+        )
+    }
+
+    #[test]
+    fn delivers_immediately_until_limit_then_batches() {
+        let mut batcher = DigestBatcher::new(config());
+
+        assert_eq!(
+            batcher.offer(&signal(0.1, 0.2, "a"), 0),
+            DigestDecision::Deliver
+        );
+        assert_eq!(
+            batcher.offer(&signal(0.2, 0.2, "b"), 1_000),
+            DigestDecision::Deliver
+        );
+        assert_eq!(
+            batcher.offer(&signal(0.3, 0.2, "c"), 2_000),
+            DigestDecision::Batched
+        );
+        assert!(batcher.flush_due(30_000).digests.is_empty());
+    }
+
+    #[test]
+    fn digest_summarises_batched_signals_when_window_closes() {
+        let mut batcher = DigestBatcher::new(config());
+        for (i, (spike, score, headline)) in [
+            (0.1, 0.1, "quiet"),
+            (0.2, 0.1, "quiet"),
+            (-0.9, 0.4, "dump"),
+            (0.5, 0.8, "etf"),
+            (0.3, 0.6, "macro"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            batcher.offer(&signal(spike, score, headline), i as i64 * 1_000);
+        }
+
+        let flush = batcher.flush_due(60_000);
+        assert_eq!(flush.digests.len(), 1);
+        let digest = &flush.digests[0];
+        assert_eq!(digest.count, 3);
+        assert_eq!(digest.counts_by_type.get("agg_trade"), Some(&3));
+        assert_eq!(digest.max_move_pct, Some(-0.9));
+        assert_eq!(digest.strongest_correlation, 0.8);
+        assert_eq!(digest.top_headlines, vec!["etf", "macro"]);
+        assert!(digest.telegram_message.contains("BTCUSDT DIGEST"));
+        assert!(digest.ws_payload.contains("\"signal_type\":\"digest\""));
+
+        // Window is reset: the next alert is delivered right away.
+        assert_eq!(
+            batcher.offer(&signal(0.1, 0.1, "x"), 61_000),
+            DigestDecision::Deliver
+        );
+    }
+
+    #[test]
+    fn escalates_once_when_storm_persists_and_resets_after_quiet_window() {
+        let mut batcher = DigestBatcher::new(config());
+        let mut escalations = 0;
+
+        for second in 0..200_i64 {
+            let now_ms = second * 1_000;
+            escalations += batcher.flush_due(now_ms).escalations.len();
+            batcher.offer(&signal(0.5, 0.5, "storm"), now_ms);
+        }
+        assert_eq!(escalations, 1);
+
+        // A full quiet window ends the storm.
+        let flush = batcher.flush_due(300_000);
+        assert!(flush.escalations.is_empty());
+        assert!(batcher.storms.is_empty());
+    }
+}
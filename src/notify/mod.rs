@@ -1,39 +1,222 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
 use serde_json::json;
 use tokio::sync::broadcast;
+use tokio::time::Instant;
 
+use crate::config::{DeliveryConfig, DigestConfig, TelegramConfig};
 use crate::news::correlation::MatchedNews;
 
+use self::digest::{DigestBatcher, DigestDecision, DigestFlush};
+use self::queue::{
+    DeliveryJob, DeliveryMetrics, DeliveryQueue, DeliveryTarget, PushOutcome,
+    spawn_delivery_workers,
+};
+
+pub mod digest;
+pub mod queue;
 pub mod telegram;
 
 #[derive(Debug, Clone)]
 pub struct SignalNotification {
+    pub signal_type: String,
+    pub symbol: String,
     pub ws_payload: String,
     pub telegram_message: String,
     pub correlation_score: f64,
+    /// Headline move used for digest summaries (return, spike or funding %).
+    pub move_pct: Option<f64>,
+    pub headlines: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct NotificationFanout {
     telegram: Option<telegram::TelegramNotifier>,
+    digest: Option<Arc<Mutex<DigestBatcher>>>,
+    escalation: Option<telegram::TelegramNotifier>,
+    delivery: Option<Arc<DeliveryQueue>>,
 }
 
 impl NotificationFanout {
     pub fn new(telegram: Option<telegram::TelegramNotifier>) -> Self {
-        Self { telegram }
+        Self {
+            telegram,
+            digest: None,
+            escalation: None,
+            delivery: None,
+        }
     }
 
-    pub async fn dispatch(&self, tx: &broadcast::Sender<String>, notification: SignalNotification) {
-        let _ = tx.send(notification.ws_payload);
-
-        if let Some(telegram) = &self.telegram {
-            telegram
-                .send_with_retry(
-                    &notification.telegram_message,
-                    notification.correlation_score,
-                )
+    /// Moves Telegram delivery onto a bounded queue drained by worker tasks, so `dispatch`
+    /// never waits on rate limiting or retries. Call after `with_digest` so the escalation
+    /// notifier is known to the workers.
+    ///
+    /// Delivery stays inline when the queue capacity is 0 or no Tokio runtime is running.
+    pub fn with_delivery_queue(mut self, config: &DeliveryConfig) -> Self {
+        if config.queue_capacity == 0 || tokio::runtime::Handle::try_current().is_err() {
+            return self;
+        }
+        if self.telegram.is_none() && self.escalation.is_none() {
+            return self;
+        }
+
+        let queue = Arc::new(DeliveryQueue::new(config.queue_capacity, config.overflow));
+        spawn_delivery_workers(
+            queue.clone(),
+            config.workers,
+            self.telegram.clone(),
+            self.escalation.clone(),
+        );
+        self.delivery = Some(queue);
+        self
+    }
+
+    pub fn delivery_metrics(&self) -> Option<DeliveryMetrics> {
+        self.delivery.as_ref().map(|queue| queue.metrics())
+    }
+
+    async fn deliver(&self, job: DeliveryJob) {
+        if let Some(queue) = &self.delivery {
+            let outcome = queue.push(job);
+            if outcome == PushOutcome::DroppedOldest {
+                log::warn!(
+                    target: "notify/queue",
+                    "queue full, dropped oldest notification (dropped={})",
+                    queue.metrics().dropped
+                );
+            }
+            return;
+        }
+
+        let notifier = match job.target {
+            DeliveryTarget::Primary => self.telegram.as_ref(),
+            DeliveryTarget::Escalation => self.escalation.as_ref(),
+        };
+        if let Some(notifier) = notifier {
+            notifier
+                .send_with_retry(&job.message, job.correlation_score)
                 .await;
         }
     }
+
+    /// Enables digest batching in front of Telegram and, when an escalation chat is
+    /// configured, a secondary notifier for storms that outlast `escalation_after_secs`.
+    pub fn with_digest(mut self, config: DigestConfig, telegram: &TelegramConfig) -> Self {
+        if !config.enabled {
+            return self;
+        }
+
+        self.escalation = config.escalation_chat_id.clone().and_then(|chat_id| {
+            let escalation_config = TelegramConfig {
+                chat_id: Some(chat_id),
+                min_correlation_score: 0.0,
+                ..telegram.clone()
+            };
+            escalation_config
+                .is_ready()
+                .then(|| telegram::TelegramNotifier::new(escalation_config))
+        });
+        self.digest = Some(Arc::new(Mutex::new(DigestBatcher::new(config))));
+        self
+    }
+
+    pub async fn dispatch(&self, tx: &broadcast::Sender<String>, notification: SignalNotification) {
+        let _ = tx.send(notification.ws_payload.clone());
+
+        let now_ms = Utc::now().timestamp_millis();
+        self.flush_due(tx, now_ms).await;
+
+        if let Some(digest) = &self.digest {
+            let decision = digest
+                .lock()
+                .expect("digest batcher poisoned")
+                .offer(&notification, now_ms);
+            if decision == DigestDecision::Batched {
+                return;
+            }
+        }
+
+        self.deliver(DeliveryJob {
+            target: DeliveryTarget::Primary,
+            coalesce_key: Some(format!(
+                "{}:{}",
+                notification.symbol, notification.signal_type
+            )),
+            message: notification.telegram_message,
+            correlation_score: notification.correlation_score,
+        })
+        .await;
+    }
+
+    /// Emits digests for closed batching windows and any escalations that became due.
+    ///
+    /// Called on every dispatch and from a periodic ticker so quiet symbols still flush.
+    pub async fn flush_due(&self, tx: &broadcast::Sender<String>, now_ms: i64) {
+        let Some(digest) = &self.digest else {
+            return;
+        };
+
+        let flush = digest
+            .lock()
+            .expect("digest batcher poisoned")
+            .flush_due(now_ms);
+        self.emit_flush(tx, flush).await;
+    }
+
+    /// Shutdown: emits every open digest window and waits until the delivery queue is
+    /// empty or `deadline` passes. Returns the number of alerts left undelivered.
+    pub async fn drain(&self, tx: &broadcast::Sender<String>, deadline: Instant) -> usize {
+        if let Some(digest) = &self.digest {
+            let flush = digest
+                .lock()
+                .expect("digest batcher poisoned")
+                .flush_pending(Utc::now().timestamp_millis());
+            self.emit_flush(tx, flush).await;
+        }
+        match &self.delivery {
+            Some(queue) => queue.wait_idle(deadline).await,
+            None => 0,
+        }
+    }
+
+    async fn emit_flush(&self, tx: &broadcast::Sender<String>, flush: DigestFlush) {
+        for message in flush.digests {
+            log::info!(
+                target: "notify/digest",
+                "{} batched={} max_move={:?} strongest_corr={:.2}",
+                message.symbol.to_uppercase(),
+                message.count,
+                message.max_move_pct,
+                message.strongest_correlation
+            );
+            let _ = tx.send(message.ws_payload);
+            self.deliver(DeliveryJob {
+                target: DeliveryTarget::Primary,
+                message: message.telegram_message,
+                correlation_score: message.strongest_correlation,
+                coalesce_key: None,
+            })
+            .await;
+        }
+
+        for escalation in flush.escalations {
+            log::info!(
+                target: "notify/digest",
+                "{} escalated after {}s of continuous batching",
+                escalation.symbol.to_uppercase(),
+                escalation.duration_ms / 1_000
+            );
+            let _ = tx.send(escalation.ws_payload);
+            self.deliver(DeliveryJob {
+                target: DeliveryTarget::Escalation,
+                message: escalation.telegram_message,
+                correlation_score: 1.0,
+                coalesce_key: None,
+            })
+            .await;
+        }
+    }
 }
 
 pub fn build_signal_notification(
@@ -63,9 +246,13 @@ pub fn build_signal_notification(
     );
 
     SignalNotification {
+        signal_type: signal_type.to_string(),
+        symbol: symbol.to_lowercase(),
         ws_payload,
         telegram_message,
         correlation_score,
+        move_pct: primary_move_pct(&move_metrics),
+        headlines: matches.iter().map(|news| news.headline.clone()).collect(),
     }
 }
 
@@ -81,9 +268,12 @@ fn format_telegram_message(
     let mut lines = vec![
         format!("🔔 {} {}", symbol, signal_type.to_uppercase()),
         format!("{} | {}", direction, magnitude),
-        format!("Correlation score: {:.2}", correlation_score),
-        "News:".to_string(),
     ];
+    if let Some(rule) = move_metrics.get("rule_expression").and_then(|v| v.as_str()) {
+        lines.push(format!("Rule: {}", rule));
+    }
+    lines.push(format!("Correlation score: {:.2}", correlation_score));
+    lines.push("News:".to_string());
 
     for news in matches.iter().take(3) {
         lines.push(format!("• {}", news.headline));
@@ -97,6 +287,12 @@ fn format_telegram_message(
     lines.join("\n")
 }
 
+fn primary_move_pct(move_metrics: &serde_json::Value) -> Option<f64> {
+    ["return_pct", "spike_pct", "funding_rate_pct"]
+        .iter()
+        .find_map(|key| move_metrics.get(key).and_then(|v| v.as_f64()))
+}
+
 fn direction_and_magnitude(move_metrics: &serde_json::Value) -> (String, String) {
     if let Some(ret) = move_metrics.get("return_pct").and_then(|v| v.as_f64()) {
         let direction = if ret > 0.0 {
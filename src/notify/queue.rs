@@ -0,0 +1,325 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use tokio::sync::Notify;
+use tokio::time::{Duration, Instant, sleep};
+
+use super::telegram::TelegramNotifier;
+
+/// What to do with a new job when the delivery queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Evict the oldest queued job.
+    DropOldest,
+    /// Replace a queued job with the same coalesce key (symbol + signal type), falling back
+    /// to dropping the oldest job when nothing matches.
+    #[default]
+    Coalesce,
+}
+
+impl OverflowPolicy {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "drop_oldest" | "drop-oldest" => Some(Self::DropOldest),
+            "coalesce" => Some(Self::Coalesce),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryTarget {
+    Primary,
+    Escalation,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeliveryJob {
+    pub target: DeliveryTarget,
+    pub message: String,
+    pub correlation_score: f64,
+    pub coalesce_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushOutcome {
+    Queued,
+    DroppedOldest,
+    Coalesced,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeliveryMetrics {
+    pub depth: usize,
+    pub max_depth: usize,
+    pub enqueued: u64,
+    pub delivered: u64,
+    pub dropped: u64,
+    pub coalesced: u64,
+}
+
+/// Bounded outbound notification queue. `push` never waits, so the market-data loop is
+/// never blocked by Telegram rate limiting or retries.
+#[derive(Debug)]
+pub struct DeliveryQueue {
+    capacity: usize,
+    policy: OverflowPolicy,
+    jobs: Mutex<VecDeque<DeliveryJob>>,
+    notify: Notify,
+    max_depth: AtomicUsize,
+    /// Jobs popped by a worker and not yet delivered.
+    in_flight: AtomicUsize,
+    enqueued: AtomicU64,
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+    coalesced: AtomicU64,
+}
+
+impl DeliveryQueue {
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            policy,
+            jobs: Mutex::new(VecDeque::new()),
+            notify: Notify::new(),
+            max_depth: AtomicUsize::new(0),
+            in_flight: AtomicUsize::new(0),
+            enqueued: AtomicU64::new(0),
+            delivered: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            coalesced: AtomicU64::new(0),
+        }
+    }
+
+    pub fn push(&self, job: DeliveryJob) -> PushOutcome {
+        let (outcome, depth) = {
+            let mut jobs = self.jobs.lock().expect("delivery queue poisoned");
+            let outcome = if jobs.len() < self.capacity {
+                jobs.push_back(job);
+                PushOutcome::Queued
+            } else {
+                let coalesce_at = match (&self.policy, &job.coalesce_key) {
+                    (OverflowPolicy::Coalesce, Some(key)) => jobs.iter().position(|queued| {
+                        queued.target == job.target && queued.coalesce_key.as_ref() == Some(key)
+                    }),
+                    _ => None,
+                };
+                match coalesce_at {
+                    Some(index) => {
+                        jobs[index] = job;
+                        PushOutcome::Coalesced
+                    }
+                    None => {
+                        jobs.pop_front();
+                        jobs.push_back(job);
+                        PushOutcome::DroppedOldest
+                    }
+                }
+            };
+            (outcome, jobs.len())
+        };
+
+        match outcome {
+            PushOutcome::Queued => {
+                self.enqueued.fetch_add(1, Ordering::Relaxed);
+            }
+            PushOutcome::DroppedOldest => {
+                self.enqueued.fetch_add(1, Ordering::Relaxed);
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            PushOutcome::Coalesced => {
+                self.coalesced.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.max_depth.fetch_max(depth, Ordering::Relaxed);
+        self.notify.notify_one();
+        outcome
+    }
+
+    pub fn try_pop(&self) -> Option<DeliveryJob> {
+        self.jobs
+            .lock()
+            .expect("delivery queue poisoned")
+            .pop_front()
+    }
+
+    /// Waits for the next job. It counts as pending until the worker marks it delivered.
+    pub async fn pop(&self) -> DeliveryJob {
+        loop {
+            let job = {
+                let mut jobs = self.jobs.lock().expect("delivery queue poisoned");
+                let job = jobs.pop_front();
+                if job.is_some() {
+                    self.in_flight.fetch_add(1, Ordering::Relaxed);
+                }
+                job
+            };
+            if let Some(job) = job {
+                return job;
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    /// Queued jobs plus jobs a worker is still delivering.
+    pub fn pending(&self) -> usize {
+        let jobs = self.jobs.lock().expect("delivery queue poisoned");
+        jobs.len() + self.in_flight.load(Ordering::Relaxed)
+    }
+
+    /// Waits until every job has been delivered or `deadline` passes, and returns how many
+    /// were still pending.
+    pub async fn wait_idle(&self, deadline: Instant) -> usize {
+        loop {
+            let pending = self.pending();
+            if pending == 0 || Instant::now() >= deadline {
+                return pending;
+            }
+            sleep(Duration::from_millis(25).min(deadline - Instant::now())).await;
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.jobs.lock().expect("delivery queue poisoned").len()
+    }
+
+    pub fn metrics(&self) -> DeliveryMetrics {
+        DeliveryMetrics {
+            depth: self.depth(),
+            max_depth: self.max_depth.load(Ordering::Relaxed),
+            enqueued: self.enqueued.load(Ordering::Relaxed),
+            delivered: self.delivered.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+        }
+    }
+
+    fn mark_delivered(&self) {
+        self.delivered.fetch_add(1, Ordering::Relaxed);
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Spawns `workers` tasks that drain the queue into the Telegram notifiers.
+///
+/// Must be called from within a Tokio runtime.
+pub fn spawn_delivery_workers(
+    queue: std::sync::Arc<DeliveryQueue>,
+    workers: usize,
+    telegram: Option<TelegramNotifier>,
+    escalation: Option<TelegramNotifier>,
+) {
+    for _ in 0..workers.max(1) {
+        let queue = queue.clone();
+        let telegram = telegram.clone();
+        let escalation = escalation.clone();
+        tokio::spawn(async move {
+            loop {
+                let job = queue.pop().await;
+                let notifier = match job.target {
+                    DeliveryTarget::Primary => telegram.as_ref(),
+                    DeliveryTarget::Escalation => escalation.as_ref(),
+                };
+                if let Some(notifier) = notifier {
+                    notifier
+                        .send_with_retry(&job.message, job.correlation_score)
+                        .await;
+                }
+                queue.mark_delivered();
+            }
+        });
+    }
+}
+
+pub fn format_queue_metrics(metrics: &DeliveryMetrics) -> String {
+    format!(
+        "depth={} max_depth={} enqueued={} delivered={} dropped={} coalesced={}",
+        metrics.depth,
+        metrics.max_depth,
+        metrics.enqueued,
+        metrics.delivered,
+        metrics.dropped,
+        metrics.coalesced
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(key: &str, message: &str) -> DeliveryJob {
+        DeliveryJob {
+            target: DeliveryTarget::Primary,
+            message: message.to_string(),
+            correlation_score: 1.0,
+            coalesce_key: Some(key.to_string()),
+        }
+    }
+
+    #[test]
+    fn drop_oldest_keeps_newest_jobs_when_full() {
+        let queue = DeliveryQueue::new(2, OverflowPolicy::DropOldest);
+        assert_eq!(queue.push(job("a", "1")), PushOutcome::Queued);
+        assert_eq!(queue.push(job("a", "2")), PushOutcome::Queued);
+        assert_eq!(queue.push(job("a", "3")), PushOutcome::DroppedOldest);
+
+        assert_eq!(queue.try_pop().map(|j| j.message).as_deref(), Some("2"));
+        assert_eq!(queue.try_pop().map(|j| j.message).as_deref(), Some("3"));
+        let metrics = queue.metrics();
+        assert_eq!(metrics.dropped, 1);
+        assert_eq!(metrics.max_depth, 2);
+        assert_eq!(metrics.depth, 0);
+    }
+
+    #[test]
+    fn coalesce_replaces_matching_job_in_place() {
+        let queue = DeliveryQueue::new(2, OverflowPolicy::Coalesce);
+        queue.push(job("btcusdt:agg_trade", "old"));
+        queue.push(job("ethusdt:agg_trade", "eth"));
+        assert_eq!(
+            queue.push(job("btcusdt:agg_trade", "new")),
+            PushOutcome::Coalesced
+        );
+        // No match: falls back to dropping the oldest.
+        assert_eq!(
+            queue.push(job("solusdt:agg_trade", "sol")),
+            PushOutcome::DroppedOldest
+        );
+
+        let messages: Vec<String> = std::iter::from_fn(|| queue.try_pop())
+            .map(|j| j.message)
+            .collect();
+        assert_eq!(messages, vec!["eth".to_string(), "sol".to_string()]);
+        assert_eq!(queue.metrics().coalesced, 1);
+    }
+
+    #[tokio::test]
+    async fn pop_waits_for_pushed_job() {
+        let queue = std::sync::Arc::new(DeliveryQueue::new(4, OverflowPolicy::Coalesce));
+        let waiter = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.pop().await })
+        };
+        tokio::task::yield_now().await;
+        queue.push(job("a", "hello"));
+        let popped = waiter.await.expect("worker task");
+        assert_eq!(popped.message, "hello");
+    }
+
+    #[tokio::test]
+    async fn wait_idle_counts_jobs_still_in_flight() {
+        let queue = DeliveryQueue::new(4, OverflowPolicy::Coalesce);
+        queue.push(job("a", "1"));
+        queue.push(job("b", "2"));
+        let _delivering = queue.pop().await;
+
+        let deadline = Instant::now() + Duration::from_millis(50);
+        assert_eq!(queue.wait_idle(deadline).await, 2);
+
+        queue.mark_delivered();
+        queue.pop().await;
+        queue.mark_delivered();
+        assert_eq!(queue.wait_idle(Instant::now()).await, 0);
+    }
+}
@@ -54,12 +54,18 @@ impl TelegramNotifier {
         }
 
         let Some(bot_token) = self.bot_token.as_deref() else {
-            eprintln!("[notify/telegram] missing TELEGRAM_BOT_TOKEN, skipping notification");
+            log::warn!(
+                target: "notify/telegram",
+                "missing TELEGRAM_BOT_TOKEN, skipping notification",
+            );
             return;
         };
 
         let Some(chat_id) = self.chat_id.as_deref() else {
-            eprintln!("[notify/telegram] missing TELEGRAM_CHAT_ID, skipping notification");
+            log::warn!(
+                target: "notify/telegram",
+                "missing TELEGRAM_CHAT_ID, skipping notification",
+            );
             return;
         };
 
@@ -95,7 +101,10 @@ impl TelegramNotifier {
         }
 
         if let Some(err) = last_error {
-            eprintln!("[notify/telegram] delivery failed, continuing stream processing: {err}");
+            log::warn!(
+                target: "notify/telegram",
+                "delivery failed, continuing stream processing: {err}",
+            );
         }
     }
 
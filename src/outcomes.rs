@@ -0,0 +1,644 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use rusqlite::{Connection, params};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use warp::Filter;
+use warp::http::StatusCode;
+
+use crate::config::OutcomeConfig;
+use crate::logging;
+
+/// Horizons at which a signal's return is sampled, as `(label, offset_ms)`.
+pub const HORIZONS: [(&str, u64); 5] = [
+    ("1m", 60_000),
+    ("5m", 300_000),
+    ("15m", 900_000),
+    ("1h", 3_600_000),
+    ("4h", 14_400_000),
+];
+
+/// Pending outcomes with no trades this long past the last horizon are closed as they are.
+const EXPIRY_GRACE_MS: u64 = 300_000;
+
+const BUSY_TIMEOUT_MS: u64 = 5_000;
+
+fn last_horizon_ms() -> u64 {
+    HORIZONS[HORIZONS.len() - 1].1
+}
+
+/// A directional signal whose subsequent price path should be tracked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedSignal {
+    /// `big_move`, `quant4h` or `funding`.
+    pub signal_type: &'static str,
+    pub symbol: String,
+    /// `1` when the signal points up, `-1` when it points down.
+    pub direction: i8,
+    pub timestamp_ms: u64,
+}
+
+/// Price path after a signal. Returns and excursions are signed in the signal's direction,
+/// so a positive return means the signal was right.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SignalOutcome {
+    pub signal_type: String,
+    pub symbol: String,
+    pub direction: i8,
+    pub timestamp_ms: u64,
+    pub entry_price: f64,
+    /// Return at each of `HORIZONS`; `None` when no trade arrived before expiry.
+    pub returns_pct: [Option<f64>; 5],
+    /// Best return seen up to the last horizon.
+    pub max_favorable_pct: f64,
+    /// Worst return seen up to the last horizon.
+    pub max_adverse_pct: f64,
+}
+
+impl SignalOutcome {
+    pub fn to_event_json(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            let returns: serde_json::Map<String, serde_json::Value> = HORIZONS
+                .iter()
+                .zip(self.returns_pct)
+                .map(|((label, _), ret)| (label.to_string(), ret.into()))
+                .collect();
+            obj.insert("returns_pct".to_string(), returns.into());
+            obj.insert("event".to_string(), "signal_outcome".into());
+        }
+        value.to_string()
+    }
+}
+
+/// Follows signals through the trade stream until their last horizon has passed.
+#[derive(Debug, Default)]
+pub struct OutcomeTracker {
+    last_prices: HashMap<String, f64>,
+    /// Pending outcomes per symbol; `entry_price` is 0 until the first trade is seen.
+    pending: HashMap<String, Vec<SignalOutcome>>,
+    report_interval_ms: u64,
+    next_report_ms: Option<u64>,
+}
+
+impl OutcomeTracker {
+    pub fn new(config: &OutcomeConfig) -> Self {
+        Self {
+            report_interval_ms: config.report_interval_secs * 1_000,
+            ..Self::default()
+        }
+    }
+
+    /// Starts tracking a signal from the symbol's last trade price, or from the next trade
+    /// when none has been seen yet. Flat signals are ignored.
+    pub fn track(&mut self, signal: TrackedSignal) {
+        if signal.direction == 0 {
+            return;
+        }
+        let entry_price = self.last_prices.get(&signal.symbol).copied().unwrap_or(0.0);
+        self.pending
+            .entry(signal.symbol.clone())
+            .or_default()
+            .push(SignalOutcome {
+                signal_type: signal.signal_type.to_string(),
+                symbol: signal.symbol,
+                direction: signal.direction.signum(),
+                timestamp_ms: signal.timestamp_ms,
+                entry_price,
+                returns_pct: [None; 5],
+                max_favorable_pct: 0.0,
+                max_adverse_pct: 0.0,
+            });
+    }
+
+    /// Updates the symbol's pending outcomes and returns those whose last horizon completed.
+    pub fn on_trade(&mut self, symbol: &str, ts_ms: u64, price: f64) -> Vec<SignalOutcome> {
+        if !price.is_finite() || price <= 0.0 {
+            return Vec::new();
+        }
+        self.last_prices.insert(symbol.to_string(), price);
+        let Some(pending) = self.pending.get_mut(symbol) else {
+            return Vec::new();
+        };
+
+        for outcome in pending.iter_mut() {
+            if ts_ms < outcome.timestamp_ms {
+                continue;
+            }
+            if outcome.entry_price <= 0.0 {
+                outcome.entry_price = price;
+            }
+            let ret = (price / outcome.entry_price - 1.0) * 100.0 * outcome.direction as f64;
+            let elapsed = ts_ms - outcome.timestamp_ms;
+            if elapsed <= last_horizon_ms() {
+                outcome.max_favorable_pct = outcome.max_favorable_pct.max(ret);
+                outcome.max_adverse_pct = outcome.max_adverse_pct.min(ret);
+            }
+            for (slot, (_, offset_ms)) in outcome.returns_pct.iter_mut().zip(HORIZONS) {
+                if slot.is_none() && elapsed >= offset_ms {
+                    *slot = Some(ret);
+                }
+            }
+        }
+
+        let (done, open): (Vec<_>, Vec<_>) = std::mem::take(pending)
+            .into_iter()
+            .partition(|o| o.returns_pct[HORIZONS.len() - 1].is_some());
+        if open.is_empty() {
+            self.pending.remove(symbol);
+        } else {
+            *pending = open;
+        }
+        done
+    }
+
+    /// Closes outcomes whose symbol stopped trading (e.g. left the universe). Outcomes that
+    /// never saw a trade are dropped.
+    pub fn expire(&mut self, now_ms: u64) -> Vec<SignalOutcome> {
+        let cutoff = last_horizon_ms() + EXPIRY_GRACE_MS;
+        let mut expired = Vec::new();
+        self.pending.retain(|_, pending| {
+            let (stale, open): (Vec<_>, Vec<_>) = std::mem::take(pending)
+                .into_iter()
+                .partition(|o| now_ms.saturating_sub(o.timestamp_ms) > cutoff);
+            expired.extend(stale.into_iter().filter(|o| o.entry_price > 0.0));
+            *pending = open;
+            !pending.is_empty()
+        });
+        expired
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.values().map(Vec::len).sum()
+    }
+
+    /// True once per report interval; the first call only schedules the next report.
+    pub fn report_due(&mut self, now_ms: u64) -> bool {
+        if self.report_interval_ms == 0 {
+            return false;
+        }
+        match self.next_report_ms {
+            Some(next) if now_ms >= next => {
+                self.next_report_ms = Some(now_ms + self.report_interval_ms);
+                true
+            }
+            Some(_) => false,
+            None => {
+                self.next_report_ms = Some(now_ms + self.report_interval_ms);
+                false
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HorizonStats {
+    pub horizon: &'static str,
+    /// Outcomes with a return at this horizon.
+    pub samples: u64,
+    /// Share of samples with a positive return, in percent.
+    pub hit_rate_pct: Option<f64>,
+    pub avg_return_pct: Option<f64>,
+}
+
+/// Aggregated outcomes for one signal type, per symbol or over all symbols (`*`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutcomeStats {
+    pub signal_type: String,
+    pub symbol: String,
+    pub signals: u64,
+    pub horizons: Vec<HorizonStats>,
+    pub avg_max_favorable_pct: Option<f64>,
+    pub avg_max_adverse_pct: Option<f64>,
+}
+
+/// SQLite table of completed outcomes. Clones share one connection; the `*_async` methods
+/// run on Tokio's blocking thread pool.
+#[derive(Debug, Clone)]
+pub struct OutcomeStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl OutcomeStore {
+    pub fn open(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        conn.busy_timeout(std::time::Duration::from_millis(BUSY_TIMEOUT_MS))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        let returns: String = HORIZONS
+            .iter()
+            .map(|(label, _)| format!("ret_{label} REAL,"))
+            .collect();
+        conn.execute_batch(&format!(
+            "
+            CREATE TABLE IF NOT EXISTS signal_outcomes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                signal_type TEXT NOT NULL,
+                symbol TEXT NOT NULL,
+                direction INTEGER NOT NULL,
+                signal_ts INTEGER NOT NULL,
+                entry_price REAL NOT NULL,
+                {returns}
+                mfe_pct REAL NOT NULL,
+                mae_pct REAL NOT NULL,
+                UNIQUE(signal_type, symbol, signal_ts, direction)
+            );
+            CREATE INDEX IF NOT EXISTS idx_signal_outcomes_type_symbol
+                ON signal_outcomes(signal_type, symbol);
+            "
+        ))?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Inserts outcomes, ignoring ones already stored; returns the number inserted.
+    pub fn insert_many(&self, outcomes: &[SignalOutcome]) -> Result<usize> {
+        let mut conn = self.conn.lock().expect("outcome store poisoned");
+        let tx = conn.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT OR IGNORE INTO signal_outcomes
+                    (signal_type, symbol, direction, signal_ts, entry_price,
+                     ret_1m, ret_5m, ret_15m, ret_1h, ret_4h, mfe_pct, mae_pct)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            )?;
+            for o in outcomes {
+                let [r1, r2, r3, r4, r5] = o.returns_pct;
+                inserted += stmt.execute(params![
+                    o.signal_type,
+                    o.symbol,
+                    o.direction,
+                    o.timestamp_ms as i64,
+                    o.entry_price,
+                    r1,
+                    r2,
+                    r3,
+                    r4,
+                    r5,
+                    o.max_favorable_pct,
+                    o.max_adverse_pct,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    pub async fn insert_many_async(&self, outcomes: Vec<SignalOutcome>) -> Result<usize> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.insert_many(&outcomes)).await?
+    }
+
+    /// Hit rates and average returns per signal type, optionally filtered. With
+    /// `by_symbol = false` all symbols are folded into one `*` row per type.
+    pub fn stats(
+        &self,
+        signal_type: Option<&str>,
+        symbol: Option<&str>,
+        by_symbol: bool,
+    ) -> Result<Vec<OutcomeStats>> {
+        let horizon_columns: String = HORIZONS
+            .iter()
+            .map(|(label, _)| {
+                format!("COUNT(ret_{label}), AVG(ret_{label} > 0) * 100.0, AVG(ret_{label}),")
+            })
+            .collect();
+        let sql = format!(
+            "SELECT signal_type, CASE WHEN ?3 THEN symbol ELSE '*' END AS grp, COUNT(*),
+                {horizon_columns}
+                AVG(mfe_pct), AVG(mae_pct)
+             FROM signal_outcomes
+             WHERE (?1 IS NULL OR signal_type = ?1) AND (?2 IS NULL OR symbol = ?2)
+             GROUP BY signal_type, grp
+             ORDER BY signal_type, grp"
+        );
+        let conn = self.conn.lock().expect("outcome store poisoned");
+        let mut stmt = conn.prepare_cached(&sql)?;
+        let rows = stmt.query_map(
+            params![signal_type, symbol.map(str::to_lowercase), by_symbol],
+            |row| {
+                let mut horizons = Vec::with_capacity(HORIZONS.len());
+                for (i, (label, _)) in HORIZONS.iter().enumerate() {
+                    let base = 3 + i * 3;
+                    horizons.push(HorizonStats {
+                        horizon: label,
+                        samples: row.get::<_, i64>(base)? as u64,
+                        hit_rate_pct: row.get(base + 1)?,
+                        avg_return_pct: row.get(base + 2)?,
+                    });
+                }
+                let tail = 3 + HORIZONS.len() * 3;
+                Ok(OutcomeStats {
+                    signal_type: row.get(0)?,
+                    symbol: row.get(1)?,
+                    signals: row.get::<_, i64>(2)? as u64,
+                    horizons,
+                    avg_max_favorable_pct: row.get(tail)?,
+                    avg_max_adverse_pct: row.get(tail + 1)?,
+                })
+            },
+        )?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    pub async fn stats_async(
+        &self,
+        signal_type: Option<String>,
+        symbol: Option<String>,
+        by_symbol: bool,
+    ) -> Result<Vec<OutcomeStats>> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || {
+            store.stats(signal_type.as_deref(), symbol.as_deref(), by_symbol)
+        })
+        .await?
+    }
+}
+
+/// `GET /signals/outcomes?type=big_move&symbol=btcusdt&by_symbol=false` with the stored
+/// hit rates. Answers 404 when outcome tracking is disabled.
+pub fn outcomes_route(
+    store: Option<OutcomeStore>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("signals" / "outcomes")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(move |query: HashMap<String, String>| {
+            let store = store.clone();
+            async move {
+                let Some(store) = store else {
+                    return Ok::<_, Infallible>(warp::reply::with_status(
+                        warp::reply::json(&serde_json::json!({
+                            "error": "signal outcome tracking is disabled"
+                        })),
+                        StatusCode::NOT_FOUND,
+                    ));
+                };
+                let by_symbol = query.get("by_symbol").is_none_or(|v| v != "false");
+                let stats = store
+                    .stats_async(
+                        query.get("type").cloned(),
+                        query.get("symbol").cloned(),
+                        by_symbol,
+                    )
+                    .await;
+                Ok(match stats {
+                    Ok(stats) => warp::reply::with_status(
+                        warp::reply::json(&serde_json::json!({
+                            "horizons": HORIZONS.iter().map(|(label, _)| *label).collect::<Vec<_>>(),
+                            "stats": stats,
+                        })),
+                        StatusCode::OK,
+                    ),
+                    Err(err) => warp::reply::with_status(
+                        warp::reply::json(&serde_json::json!({ "error": err.to_string() })),
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                })
+            }
+        })
+}
+
+fn format_pct(value: Option<f64>) -> String {
+    value
+        .map(|v| format!("{v:+.2}%"))
+        .unwrap_or_else(|| "n/a".to_string())
+}
+
+pub fn format_outcome_log(outcome: &SignalOutcome) -> String {
+    let returns: Vec<String> = HORIZONS
+        .iter()
+        .zip(outcome.returns_pct)
+        .map(|((label, _), ret)| format!("{label}={}", format_pct(ret)))
+        .collect();
+    format!(
+        "[OUTCOME] {} {} {} entry={} {} mfe={:+.2}% mae={:+.2}%",
+        outcome.symbol.to_uppercase(),
+        outcome.signal_type,
+        if outcome.direction > 0 {
+            "LONG"
+        } else {
+            "SHORT"
+        },
+        outcome.entry_price,
+        returns.join(" "),
+        outcome.max_favorable_pct,
+        outcome.max_adverse_pct,
+    )
+}
+
+/// One `[OUTCOME_REPORT]` line per stats row: `horizon=hit_rate/avg_return`.
+pub fn format_outcome_report(stats: &[OutcomeStats]) -> Vec<String> {
+    stats
+        .iter()
+        .map(|row| {
+            let horizons: Vec<String> = row
+                .horizons
+                .iter()
+                .map(|h| match h.hit_rate_pct {
+                    Some(hit) => {
+                        format!("{}={:.0}%/{}", h.horizon, hit, format_pct(h.avg_return_pct))
+                    }
+                    None => format!("{}=n/a", h.horizon),
+                })
+                .collect();
+            format!(
+                "[OUTCOME_REPORT] {} {} n={} {} mfe={} mae={}",
+                row.signal_type,
+                row.symbol.to_uppercase(),
+                row.signals,
+                horizons.join(" "),
+                format_pct(row.avg_max_favorable_pct),
+                format_pct(row.avg_max_adverse_pct),
+            )
+        })
+        .collect()
+}
+
+/// Broadcasts completed outcomes and writes them to the store in the background.
+pub fn emit_outcomes(
+    completed: Vec<SignalOutcome>,
+    store: &OutcomeStore,
+    tx: &broadcast::Sender<String>,
+) {
+    if completed.is_empty() {
+        return;
+    }
+    for outcome in &completed {
+        let msg = format_outcome_log(outcome);
+        logging::signal("outcome", &msg);
+        let _ = tx.send(msg);
+        let _ = tx.send(outcome.to_event_json());
+    }
+    let store = store.clone();
+    tokio::spawn(async move {
+        if let Err(err) = store.insert_many_async(completed).await {
+            log::error!(target: "outcomes", "failed to store outcomes: {err}");
+        }
+    });
+}
+
+/// Broadcasts per-signal-type hit rates from the stored outcomes.
+pub fn spawn_outcome_report(store: OutcomeStore, tx: broadcast::Sender<String>) {
+    tokio::spawn(async move {
+        match store.stats_async(None, None, false).await {
+            Ok(stats) => {
+                for line in format_outcome_report(&stats) {
+                    log::info!(target: "outcomes", "{line}");
+                    let _ = tx.send(line);
+                }
+            }
+            Err(err) => log::warn!(target: "outcomes", "failed to build report: {err}"),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(direction: i8, timestamp_ms: u64) -> TrackedSignal {
+        TrackedSignal {
+            signal_type: "big_move",
+            symbol: "btcusdt".to_string(),
+            direction,
+            timestamp_ms,
+        }
+    }
+
+    #[test]
+    fn samples_horizons_and_excursions_in_signal_direction() {
+        let mut tracker = OutcomeTracker::default();
+        tracker.on_trade("btcusdt", 0, 100.0);
+        tracker.track(signal(-1, 1_000));
+        tracker.track(signal(0, 1_000));
+        assert_eq!(tracker.pending_count(), 1);
+
+        assert!(tracker.on_trade("btcusdt", 30_000, 102.0).is_empty());
+        assert!(tracker.on_trade("btcusdt", 61_000, 99.0).is_empty());
+        assert!(tracker.on_trade("btcusdt", 3_601_000, 98.0).is_empty());
+        let done = tracker.on_trade("btcusdt", 14_401_000, 101.0);
+        assert_eq!(done.len(), 1);
+        let outcome = &done[0];
+        assert_eq!(outcome.entry_price, 100.0);
+        let rounded: Vec<Option<f64>> = outcome
+            .returns_pct
+            .iter()
+            .map(|r| r.map(|v| (v * 100.0).round() / 100.0))
+            .collect();
+        // Short signal: a falling price is a positive return.
+        assert_eq!(
+            rounded,
+            vec![Some(1.0), Some(2.0), Some(2.0), Some(2.0), Some(-1.0)]
+        );
+        assert!((outcome.max_favorable_pct - 2.0).abs() < 1e-9);
+        assert!((outcome.max_adverse_pct + 2.0).abs() < 1e-9);
+        assert_eq!(tracker.pending_count(), 0);
+        assert!(
+            format_outcome_log(outcome).starts_with("[OUTCOME] BTCUSDT big_move SHORT entry=100")
+        );
+
+        // A symbol that stops trading is closed with the horizons it reached.
+        tracker.track(signal(1, 20_000_000));
+        tracker.on_trade("btcusdt", 20_070_000, 100.0);
+        assert!(tracker.expire(20_000_000 + 14_400_000).is_empty());
+        let expired = tracker.expire(20_000_000 + 14_400_000 + EXPIRY_GRACE_MS + 1);
+        assert_eq!(expired.len(), 1);
+        assert!(expired[0].returns_pct[0].is_some() && expired[0].returns_pct[1].is_none());
+    }
+
+    #[test]
+    fn store_aggregates_hit_rates_per_type_and_symbol() {
+        let db_path = std::env::temp_dir()
+            .join(format!(
+                "feeder-service-outcomes-{}.sqlite",
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .expect("valid time")
+                    .as_nanos()
+            ))
+            .to_string_lossy()
+            .to_string();
+        let store = OutcomeStore::open(&db_path).expect("open");
+        let outcome = |symbol: &str, ts: u64, ret: f64| SignalOutcome {
+            signal_type: "big_move".to_string(),
+            symbol: symbol.to_string(),
+            direction: 1,
+            timestamp_ms: ts,
+            entry_price: 100.0,
+            returns_pct: [Some(ret), Some(ret), None, None, None],
+            max_favorable_pct: ret.max(0.0),
+            max_adverse_pct: ret.min(0.0),
+        };
+        let rows = vec![
+            outcome("btcusdt", 1, 1.0),
+            outcome("btcusdt", 2, -0.5),
+            outcome("ethusdt", 3, 0.3),
+        ];
+        assert_eq!(store.insert_many(&rows).expect("insert"), 3);
+        assert_eq!(store.insert_many(&rows[..1]).expect("duplicate"), 0);
+
+        let per_symbol = store.stats(None, None, true).expect("stats");
+        assert_eq!(per_symbol.len(), 2);
+        assert_eq!(per_symbol[0].symbol, "btcusdt");
+        assert_eq!(per_symbol[0].horizons[0].hit_rate_pct, Some(50.0));
+        assert_eq!(per_symbol[0].horizons[0].avg_return_pct, Some(0.25));
+        assert_eq!(per_symbol[0].horizons[2].samples, 0);
+
+        let totals = store.stats(Some("big_move"), None, false).expect("totals");
+        assert_eq!(totals.len(), 1);
+        assert_eq!(totals[0].signals, 3);
+        let report = format_outcome_report(&totals);
+        assert!(report[0].starts_with("[OUTCOME_REPORT] big_move * n=3 1m=67%/+0.27%"));
+        assert!(
+            store
+                .stats(Some("funding"), Some("BTCUSDT"), true)
+                .expect("filtered")
+                .is_empty()
+        );
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn stalled_symbol_expires_without_touching_active_ones() {
+        let mut tracker = OutcomeTracker::default();
+        tracker.on_trade("btcusdt", 0, 100.0);
+        tracker.track(signal(1, 1_000));
+        tracker.track(TrackedSignal {
+            symbol: "ethusdt".to_string(),
+            ..signal(-1, 10_000_000)
+        });
+        tracker.track(TrackedSignal {
+            symbol: "solusdt".to_string(),
+            ..signal(1, 1_000)
+        });
+        tracker.on_trade("ethusdt", 10_001_000, 50.0);
+        // btcusdt stalls after its 1m and 5m horizons; solusdt never trades at all.
+        tracker.on_trade("btcusdt", 301_000, 101.0);
+        assert_eq!(tracker.pending_count(), 3);
+
+        let cutoff = 1_000 + 14_400_000 + EXPIRY_GRACE_MS;
+        assert!(tracker.expire(cutoff).is_empty());
+        assert_eq!(tracker.pending_count(), 3);
+
+        let expired = tracker.expire(cutoff + 1);
+        assert_eq!(expired.len(), 1);
+        let btc = &expired[0];
+        assert_eq!(btc.symbol, "btcusdt");
+        let reached: Vec<bool> = btc.returns_pct.iter().map(Option::is_some).collect();
+        assert_eq!(reached, vec![true, true, false, false, false]);
+        assert!((btc.max_favorable_pct - 1.0).abs() < 1e-9);
+        // The later ethusdt signal is still within its horizons and stays pending.
+        assert_eq!(tracker.pending_count(), 1);
+
+        // A trade after expiry reopens nothing; only ethusdt is left to expire.
+        assert!(tracker.on_trade("btcusdt", cutoff + 2, 102.0).is_empty());
+        assert_eq!(tracker.expire(cutoff + 20_000_000).len(), 1);
+        assert_eq!(tracker.pending_count(), 0);
+    }
+}
@@ -0,0 +1,766 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::binance_depth::DepthUpdate;
+use crate::config::PaperConfig;
+use crate::decimal::Decimal;
+use crate::logging;
+use crate::outcomes::TrackedSignal;
+
+/// Signal types a strategy can trade; the same signals the outcome tracker follows.
+pub const PAPER_SIGNAL_TYPES: [&str; 3] = ["big_move", "funding", "quant4h"];
+
+/// A book touch further than this from the last trade is treated as stale.
+const MAX_BOOK_DEVIATION_PCT: f64 = 1.0;
+
+/// Levels kept per side; the far end of the book is dropped beyond this.
+const MAX_BOOK_LEVELS: usize = 1_000;
+
+/// Trade log entries kept per strategy for the report.
+const MAX_TRADE_LOG: usize = 200;
+
+/// Which signals a strategy trades and how positions are sized and closed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaperStrategy {
+    pub name: String,
+    /// One of `PAPER_SIGNAL_TYPES`.
+    pub signal_type: String,
+    /// Position size in quote currency.
+    pub notional: f64,
+    /// Adverse move (%) that closes the position; 0 disables the stop.
+    pub stop_loss_pct: f64,
+    /// Favorable move (%) that closes the position; 0 disables the target.
+    pub take_profit_pct: f64,
+    /// Seconds after which the position is closed; 0 holds until stop, target or reversal.
+    pub max_hold_secs: u64,
+}
+
+impl Default for PaperStrategy {
+    fn default() -> Self {
+        Self {
+            name: "big_move".to_string(),
+            signal_type: "big_move".to_string(),
+            notional: 1_000.0,
+            stop_loss_pct: 1.0,
+            take_profit_pct: 2.0,
+            max_hold_secs: 3_600,
+        }
+    }
+}
+
+impl PaperStrategy {
+    /// Parses `name:signal_type:notional:stop_pct:take_profit_pct:max_hold_secs`. Trailing
+    /// fields may be omitted and fall back to `base`, e.g. `quant:quant4h:500`.
+    pub fn parse(raw: &str, base: &PaperStrategy) -> Option<Self> {
+        let mut fields = raw.split(':').map(str::trim);
+        let name = fields.next().filter(|n| !n.is_empty())?.to_lowercase();
+        let signal_type = fields.next()?.to_lowercase();
+        if !PAPER_SIGNAL_TYPES.contains(&signal_type.as_str()) {
+            return None;
+        }
+        let mut strategy = Self {
+            name,
+            signal_type,
+            ..base.clone()
+        };
+        if let Some(v) = fields.next() {
+            strategy.notional = v.parse().ok().filter(|v: &f64| *v > 0.0)?;
+        }
+        if let Some(v) = fields.next() {
+            strategy.stop_loss_pct = v.parse().ok().filter(|v: &f64| *v >= 0.0)?;
+        }
+        if let Some(v) = fields.next() {
+            strategy.take_profit_pct = v.parse().ok().filter(|v: &f64| *v >= 0.0)?;
+        }
+        if let Some(v) = fields.next() {
+            strategy.max_hold_secs = v.parse().ok()?;
+        }
+        if fields.next().is_some() {
+            return None;
+        }
+        Some(strategy)
+    }
+}
+
+/// Local order book rebuilt from diff depth updates. Without a REST snapshot it only holds
+/// levels that changed since the stream started; crossed levels are resolved in favor of
+/// the newest update.
+#[derive(Debug, Default)]
+pub struct OrderBook {
+    bids: BTreeMap<Decimal, f64>,
+    asks: BTreeMap<Decimal, f64>,
+    /// `u` of the last applied update; 0 before the first one.
+    last_update_id: u64,
+}
+
+impl OrderBook {
+    /// Applies a diff. When update ids show a diff was missed (e.g. dropped by a full shard
+    /// queue), the book is cleared first and rebuilt from this diff on, since levels the
+    /// missed diff changed would otherwise stay stale. Returns whether that happened.
+    pub fn apply(&mut self, depth: &DepthUpdate) -> bool {
+        let gap = self.last_update_id > 0 && depth.first_update_id > self.last_update_id + 1;
+        if gap {
+            self.bids.clear();
+            self.asks.clear();
+        }
+        self.last_update_id = depth.final_update_id;
+        for [price, qty] in &depth.bids {
+            if qty.is_zero() {
+                self.bids.remove(price);
+            } else {
+                self.bids.insert(*price, qty.to_f64());
+                self.asks.retain(|ask, _| ask > price);
+            }
+        }
+        for [price, qty] in &depth.asks {
+            if qty.is_zero() {
+                self.asks.remove(price);
+            } else {
+                self.asks.insert(*price, qty.to_f64());
+                self.bids.retain(|bid, _| bid < price);
+            }
+        }
+        while self.bids.len() > MAX_BOOK_LEVELS {
+            self.bids.pop_first();
+        }
+        while self.asks.len() > MAX_BOOK_LEVELS {
+            self.asks.pop_last();
+        }
+        gap
+    }
+
+    pub fn best_bid(&self) -> Option<f64> {
+        self.bids.keys().next_back().map(Decimal::to_f64)
+    }
+
+    pub fn best_ask(&self) -> Option<f64> {
+        self.asks.keys().next().map(Decimal::to_f64)
+    }
+
+    /// Average price of a market order for `qty`, walking the asks for a buy and the bids
+    /// for a sell. Quantity beyond the known levels fills at the last level; a zero or
+    /// invalid quantity prices at the touch.
+    pub fn fill_price(&self, buy: bool, qty: f64) -> Option<f64> {
+        if qty.is_nan() || qty <= 0.0 {
+            return if buy {
+                self.best_ask()
+            } else {
+                self.best_bid()
+            };
+        }
+        let levels: Box<dyn Iterator<Item = (&Decimal, &f64)>> = if buy {
+            Box::new(self.asks.iter())
+        } else {
+            Box::new(self.bids.iter().rev())
+        };
+        let (mut remaining, mut cost, mut last_price) = (qty, 0.0, None);
+        for (price, level_qty) in levels {
+            let price = price.to_f64();
+            let take = remaining.min(*level_qty);
+            cost += take * price;
+            remaining -= take;
+            last_price = Some(price);
+            if remaining <= 0.0 {
+                break;
+            }
+        }
+        let last_price = last_price?;
+        Some((cost + remaining.max(0.0) * last_price) / qty)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitReason {
+    StopLoss,
+    TakeProfit,
+    TimeExit,
+    /// An opposite signal for the same strategy and symbol.
+    Reversal,
+}
+
+impl ExitReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitReason::StopLoss => "stop_loss",
+            ExitReason::TakeProfit => "take_profit",
+            ExitReason::TimeExit => "time_exit",
+            ExitReason::Reversal => "reversal",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PaperPosition {
+    pub strategy: String,
+    pub symbol: String,
+    /// `1` long, `-1` short.
+    pub direction: i8,
+    pub qty: f64,
+    pub entry_ms: u64,
+    pub entry_price: f64,
+    /// Entry fill price vs the last trade, signed as a cost.
+    pub entry_slippage_bps: f64,
+    pub entry_fee: f64,
+}
+
+/// A closed position. `pnl` is net of both fees.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PaperTrade {
+    pub strategy: String,
+    pub symbol: String,
+    pub direction: i8,
+    pub qty: f64,
+    pub entry_ms: u64,
+    pub exit_ms: u64,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub slippage_bps: f64,
+    pub fees: f64,
+    pub pnl: f64,
+    pub return_pct: f64,
+    pub exit_reason: ExitReason,
+}
+
+impl PaperTrade {
+    pub fn to_event_json(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("event".to_string(), "paper_trade".into());
+        }
+        value.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaperEvent {
+    Opened(PaperPosition),
+    Closed(PaperTrade),
+}
+
+/// Realized performance of one strategy.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StrategyStats {
+    pub strategy: String,
+    pub trades: u64,
+    pub wins: u64,
+    pub net_pnl: f64,
+    pub fees: f64,
+    /// Largest drop of realized equity from its running peak.
+    pub max_drawdown: f64,
+    pub open_positions: usize,
+    #[serde(skip)]
+    peak_pnl: f64,
+    /// Most recent closed trades, oldest first.
+    pub recent_trades: Vec<PaperTrade>,
+}
+
+impl StrategyStats {
+    pub fn win_rate_pct(&self) -> Option<f64> {
+        (self.trades > 0).then(|| self.wins as f64 / self.trades as f64 * 100.0)
+    }
+
+    fn record(&mut self, trade: &PaperTrade) {
+        self.trades += 1;
+        if trade.pnl > 0.0 {
+            self.wins += 1;
+        }
+        self.net_pnl += trade.pnl;
+        self.fees += trade.fees;
+        self.peak_pnl = self.peak_pnl.max(self.net_pnl);
+        self.max_drawdown = self.max_drawdown.max(self.peak_pnl - self.net_pnl);
+        self.recent_trades.push(trade.clone());
+        if self.recent_trades.len() > MAX_TRADE_LOG {
+            self.recent_trades.remove(0);
+        }
+    }
+}
+
+/// Opens simulated positions on tracked signals and closes them on stop-loss, take-profit,
+/// time or reversal, pricing fills from the local order book.
+#[derive(Debug, Default)]
+pub struct PaperTrader {
+    strategies: Vec<PaperStrategy>,
+    fee_bps: f64,
+    fallback_slippage_bps: f64,
+    books: HashMap<String, OrderBook>,
+    last_prices: HashMap<String, f64>,
+    positions: Vec<PaperPosition>,
+    stats: HashMap<String, StrategyStats>,
+    report_interval_ms: u64,
+    next_report_ms: Option<u64>,
+}
+
+impl PaperTrader {
+    pub fn new(config: &PaperConfig) -> Self {
+        Self {
+            strategies: config.strategies.clone(),
+            fee_bps: config.fee_bps,
+            fallback_slippage_bps: config.fallback_slippage_bps,
+            report_interval_ms: config.report_interval_secs * 1_000,
+            ..Self::default()
+        }
+    }
+
+    pub fn on_depth(&mut self, depth: &DepthUpdate) {
+        let gap = self
+            .books
+            .entry(depth.symbol.to_lowercase())
+            .or_default()
+            .apply(depth);
+        if gap {
+            log::warn!(
+                target: "paper",
+                rate_limited = true;
+                "{} depth diff missed before update {}, order book reset",
+                depth.symbol.to_uppercase(),
+                depth.first_update_id
+            );
+        }
+    }
+
+    /// Drops the order book of a symbol that left the universe. Open positions keep the
+    /// last trade price and fall back to fixed slippage when they close.
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        self.books.remove(&symbol.to_lowercase());
+    }
+
+    /// Fill price for a market order at `mark`, and its slippage in bps as a cost. Falls
+    /// back to a fixed slippage when the book is empty or stale.
+    fn fill(&self, symbol: &str, buy: bool, qty: f64, mark: f64) -> (f64, f64) {
+        let book = self.books.get(symbol);
+        let touch = book.and_then(|b| if buy { b.best_ask() } else { b.best_bid() });
+        let book_price = book
+            .zip(touch)
+            .filter(|(_, touch)| (touch / mark - 1.0).abs() * 100.0 <= MAX_BOOK_DEVIATION_PCT)
+            .and_then(|(book, _)| book.fill_price(buy, qty));
+        let side = if buy { 1.0 } else { -1.0 };
+        match book_price {
+            Some(price) => (price, (price / mark - 1.0) * 10_000.0 * side),
+            None => (
+                mark * (1.0 + side * self.fallback_slippage_bps / 10_000.0),
+                self.fallback_slippage_bps,
+            ),
+        }
+    }
+
+    fn close(&mut self, index: usize, ts_ms: u64, reason: ExitReason) -> Option<PaperTrade> {
+        let mark = *self.last_prices.get(&self.positions[index].symbol)?;
+        let position = self.positions.remove(index);
+        let (exit_price, exit_slippage_bps) =
+            self.fill(&position.symbol, position.direction < 0, position.qty, mark);
+        let exit_fee = exit_price * position.qty * self.fee_bps / 10_000.0;
+        let fees = position.entry_fee + exit_fee;
+        let gross = (exit_price - position.entry_price) * position.qty * position.direction as f64;
+        let trade = PaperTrade {
+            strategy: position.strategy,
+            symbol: position.symbol,
+            direction: position.direction,
+            qty: position.qty,
+            entry_ms: position.entry_ms,
+            exit_ms: ts_ms,
+            entry_price: position.entry_price,
+            exit_price,
+            slippage_bps: position.entry_slippage_bps + exit_slippage_bps,
+            fees,
+            pnl: gross - fees,
+            return_pct: (gross - fees) / (position.entry_price * position.qty) * 100.0,
+            exit_reason: reason,
+        };
+        self.stats
+            .entry(trade.strategy.clone())
+            .or_default()
+            .record(&trade);
+        Some(trade)
+    }
+
+    /// Opens a position for every strategy trading this signal type at the last trade
+    /// price. An opposite open position is closed first; one in the same direction is kept.
+    pub fn on_signal(&mut self, signal: &TrackedSignal) -> Vec<PaperEvent> {
+        let mut events = Vec::new();
+        let direction = signal.direction.signum();
+        let Some(mark) = self.last_prices.get(&signal.symbol).copied() else {
+            return events;
+        };
+        if direction == 0 {
+            return events;
+        }
+        let strategies: Vec<PaperStrategy> = self
+            .strategies
+            .iter()
+            .filter(|s| s.signal_type == signal.signal_type)
+            .cloned()
+            .collect();
+        for strategy in strategies {
+            let existing = self
+                .positions
+                .iter()
+                .position(|p| p.strategy == strategy.name && p.symbol == signal.symbol);
+            if let Some(index) = existing {
+                if self.positions[index].direction == direction {
+                    continue;
+                }
+                events.extend(
+                    self.close(index, signal.timestamp_ms, ExitReason::Reversal)
+                        .map(PaperEvent::Closed),
+                );
+            }
+
+            let qty = strategy.notional / mark;
+            let (entry_price, entry_slippage_bps) =
+                self.fill(&signal.symbol, direction > 0, qty, mark);
+            let position = PaperPosition {
+                strategy: strategy.name.clone(),
+                symbol: signal.symbol.clone(),
+                direction,
+                qty,
+                entry_ms: signal.timestamp_ms,
+                entry_price,
+                entry_slippage_bps,
+                entry_fee: entry_price * qty * self.fee_bps / 10_000.0,
+            };
+            self.positions.push(position.clone());
+            events.push(PaperEvent::Opened(position));
+        }
+        events
+    }
+
+    /// Marks the symbol's open positions to the trade price and closes those that hit
+    /// their stop, target or holding time.
+    pub fn on_trade(&mut self, symbol: &str, ts_ms: u64, price: f64) -> Vec<PaperEvent> {
+        if !price.is_finite() || price <= 0.0 {
+            return Vec::new();
+        }
+        self.last_prices.insert(symbol.to_string(), price);
+
+        let mut events = Vec::new();
+        let mut index = 0;
+        while index < self.positions.len() {
+            let position = &self.positions[index];
+            let strategy = self.strategies.iter().find(|s| s.name == position.strategy);
+            let reason = (position.symbol == symbol)
+                .then_some(strategy)
+                .flatten()
+                .and_then(|strategy| {
+                    let ret =
+                        (price / position.entry_price - 1.0) * 100.0 * position.direction as f64;
+                    if strategy.stop_loss_pct > 0.0 && ret <= -strategy.stop_loss_pct {
+                        Some(ExitReason::StopLoss)
+                    } else if strategy.take_profit_pct > 0.0 && ret >= strategy.take_profit_pct {
+                        Some(ExitReason::TakeProfit)
+                    } else if strategy.max_hold_secs > 0
+                        && ts_ms.saturating_sub(position.entry_ms) >= strategy.max_hold_secs * 1_000
+                    {
+                        Some(ExitReason::TimeExit)
+                    } else {
+                        None
+                    }
+                });
+            match reason.and_then(|reason| self.close(index, ts_ms, reason)) {
+                Some(trade) => events.push(PaperEvent::Closed(trade)),
+                None => index += 1,
+            }
+        }
+        events
+    }
+
+    /// Stats for every configured strategy, in configuration order.
+    pub fn report(&self) -> Vec<StrategyStats> {
+        self.strategies
+            .iter()
+            .map(|strategy| {
+                let mut stats = self.stats.get(&strategy.name).cloned().unwrap_or_default();
+                stats.strategy = strategy.name.clone();
+                stats.open_positions = self
+                    .positions
+                    .iter()
+                    .filter(|p| p.strategy == strategy.name)
+                    .count();
+                stats
+            })
+            .collect()
+    }
+
+    /// True once per report interval; the first call only schedules the next report.
+    pub fn report_due(&mut self, now_ms: u64) -> bool {
+        if self.report_interval_ms == 0 {
+            return false;
+        }
+        match self.next_report_ms {
+            Some(next) if now_ms >= next => {
+                self.next_report_ms = Some(now_ms + self.report_interval_ms);
+                true
+            }
+            Some(_) => false,
+            None => {
+                self.next_report_ms = Some(now_ms + self.report_interval_ms);
+                false
+            }
+        }
+    }
+}
+
+fn side_label(direction: i8) -> &'static str {
+    if direction > 0 { "LONG" } else { "SHORT" }
+}
+
+pub fn format_paper_log(event: &PaperEvent) -> String {
+    match event {
+        PaperEvent::Opened(position) => format!(
+            "[PAPER] {} OPEN {} {} qty={:.6} entry={} slippage={:.1}bps fee={:.4}",
+            position.strategy,
+            position.symbol.to_uppercase(),
+            side_label(position.direction),
+            position.qty,
+            position.entry_price,
+            position.entry_slippage_bps,
+            position.entry_fee,
+        ),
+        PaperEvent::Closed(trade) => format!(
+            "[PAPER] {} CLOSE {} {} {} entry={} exit={} pnl={:+.4} ({:+.2}%) fees={:.4} slippage={:.1}bps held={}s",
+            trade.strategy,
+            trade.symbol.to_uppercase(),
+            side_label(trade.direction),
+            trade.exit_reason.as_str(),
+            trade.entry_price,
+            trade.exit_price,
+            trade.pnl,
+            trade.return_pct,
+            trade.fees,
+            trade.slippage_bps,
+            trade.exit_ms.saturating_sub(trade.entry_ms) / 1_000,
+        ),
+    }
+}
+
+/// One `[PAPER_REPORT]` line per strategy.
+pub fn format_paper_report(stats: &[StrategyStats]) -> Vec<String> {
+    stats
+        .iter()
+        .map(|row| {
+            format!(
+                "[PAPER_REPORT] {} trades={} win={} pnl={:+.4} fees={:.4} max_dd={:.4} open={}",
+                row.strategy,
+                row.trades,
+                row.win_rate_pct()
+                    .map(|w| format!("{w:.0}%"))
+                    .unwrap_or_else(|| "n/a".to_string()),
+                row.net_pnl,
+                row.fees,
+                row.max_drawdown,
+                row.open_positions,
+            )
+        })
+        .collect()
+}
+
+/// Broadcasts opened and closed positions; closed trades also go out as `paper_trade` JSON.
+pub fn emit_paper_events(events: Vec<PaperEvent>, tx: &broadcast::Sender<String>) {
+    for event in &events {
+        let msg = format_paper_log(event);
+        logging::signal("paper", &msg);
+        let _ = tx.send(msg);
+        if let PaperEvent::Closed(trade) = event {
+            let _ = tx.send(trade.to_event_json());
+        }
+    }
+}
+
+/// Broadcasts the per-strategy report lines and a `paper_report` JSON event.
+pub fn emit_paper_report(trader: &PaperTrader, tx: &broadcast::Sender<String>) {
+    let report = trader.report();
+    for line in format_paper_report(&report) {
+        log::info!(target: "paper", "{line}");
+        let _ = tx.send(line);
+    }
+    let payload = serde_json::json!({ "event": "paper_report", "strategies": report });
+    let _ = tx.send(payload.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(v: &str) -> Decimal {
+        v.parse().expect("decimal")
+    }
+
+    fn depth(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> DepthUpdate {
+        DepthUpdate {
+            symbol: "BTCUSDT".to_string(),
+            bids: bids.iter().map(|(p, q)| [d(p), d(q)]).collect(),
+            asks: asks.iter().map(|(p, q)| [d(p), d(q)]).collect(),
+            event_time: 0,
+            first_update_id: 0,
+            final_update_id: 0,
+        }
+    }
+
+    fn signal(direction: i8, timestamp_ms: u64) -> TrackedSignal {
+        TrackedSignal {
+            signal_type: "big_move",
+            symbol: "btcusdt".to_string(),
+            direction,
+            timestamp_ms,
+        }
+    }
+
+    #[test]
+    fn book_applies_diffs_and_walks_levels() {
+        let mut book = OrderBook::default();
+        book.apply(&depth(
+            &[("99.9", "1"), ("99.8", "2")],
+            &[("100.1", "1"), ("100.2", "3")],
+        ));
+        book.apply(&depth(&[("99.8", "0")], &[]));
+        assert_eq!(
+            (book.best_bid(), book.best_ask()),
+            (Some(99.9), Some(100.1))
+        );
+        // 2 units: 1 at 100.1 and 1 at 100.2.
+        assert!((book.fill_price(true, 2.0).unwrap() - 100.15).abs() < 1e-9);
+        // Beyond the known depth the last level is used.
+        assert!((book.fill_price(false, 3.0).unwrap() - 99.9).abs() < 1e-9);
+        // A newer bid through the ask clears the crossed ask.
+        book.apply(&depth(&[("100.15", "1")], &[]));
+        assert_eq!(book.best_ask(), Some(100.2));
+    }
+
+    #[test]
+    fn missed_diff_resets_the_book() {
+        let with_ids = |first, last, bids: &[(&str, &str)], asks: &[(&str, &str)]| DepthUpdate {
+            first_update_id: first,
+            final_update_id: last,
+            ..depth(bids, asks)
+        };
+        let mut book = OrderBook::default();
+        assert!(!book.apply(&with_ids(10, 12, &[("99.9", "1")], &[("100.1", "1")])));
+        assert!(!book.apply(&with_ids(13, 15, &[("99.8", "2")], &[])));
+        assert_eq!(book.best_bid(), Some(99.9));
+
+        // 16..=17 never arrived; it might have removed 99.9, so nothing old is trusted.
+        assert!(book.apply(&with_ids(18, 20, &[], &[("100.3", "1")])));
+        assert_eq!((book.best_bid(), book.best_ask()), (None, Some(100.3)));
+        assert!(!book.apply(&with_ids(21, 21, &[("99.7", "1")], &[])));
+        assert_eq!(book.best_bid(), Some(99.7));
+    }
+
+    #[test]
+    fn removed_symbol_loses_its_book() {
+        let mut trader = PaperTrader::new(&PaperConfig {
+            enabled: true,
+            strategies: vec![PaperStrategy::default()],
+            fee_bps: 0.0,
+            fallback_slippage_bps: 5.0,
+            report_interval_secs: 0,
+        });
+        trader.on_trade("btcusdt", 0, 100.0);
+        trader.on_depth(&depth(&[("99.9", "100")], &[("100.1", "100")]));
+        assert_eq!(trader.fill("btcusdt", true, 1.0, 100.0).0, 100.1);
+
+        trader.remove_symbol("BTCUSDT");
+        assert!(trader.books.is_empty());
+        assert!((trader.fill("btcusdt", true, 1.0, 100.0).0 - 100.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn zero_quantity_prices_at_the_touch() {
+        let mut book = OrderBook::default();
+        assert_eq!(book.fill_price(true, 0.0), None);
+        book.apply(&depth(&[("99.9", "1")], &[("100.1", "1"), ("100.2", "3")]));
+        assert_eq!(book.fill_price(true, 0.0), Some(100.1));
+        assert_eq!(book.fill_price(false, 0.0), Some(99.9));
+        assert_eq!(book.fill_price(true, -1.0), Some(100.1));
+        assert_eq!(book.fill_price(false, f64::NAN), Some(99.9));
+    }
+
+    #[test]
+    fn trades_signals_with_fees_stops_and_reversals() {
+        let mut trader = PaperTrader::new(&PaperConfig {
+            enabled: true,
+            strategies: vec![PaperStrategy::default()],
+            fee_bps: 10.0,
+            fallback_slippage_bps: 0.0,
+            report_interval_secs: 0,
+        });
+        assert!(trader.on_signal(&signal(1, 0)).is_empty(), "no price yet");
+        trader.on_trade("btcusdt", 0, 100.0);
+
+        let opened = trader.on_signal(&signal(1, 1_000));
+        assert!(matches!(&opened[..], [PaperEvent::Opened(p)] if p.entry_price == 100.0));
+        assert!(trader.on_signal(&signal(1, 2_000)).is_empty());
+        assert!(trader.on_trade("btcusdt", 3_000, 101.0).is_empty());
+
+        // +2% hits the target: 10 units * 2 = 20 gross, fees 1.0 + 1.02.
+        let closed = trader.on_trade("btcusdt", 4_000, 102.0);
+        let [PaperEvent::Closed(trade)] = &closed[..] else {
+            panic!("expected a close, got {closed:?}");
+        };
+        assert_eq!(trade.exit_reason, ExitReason::TakeProfit);
+        assert!((trade.pnl - (20.0 - 2.02)).abs() < 1e-9);
+
+        // Book-priced short entry, then a reversal and a stop.
+        trader.on_depth(&depth(&[("101.9", "100")], &[("102.1", "100")]));
+        let opened = trader.on_signal(&signal(-1, 5_000));
+        assert!(matches!(&opened[..], [PaperEvent::Opened(p)] if p.entry_price == 101.9));
+        let events = trader.on_signal(&signal(1, 6_000));
+        assert!(matches!(
+            &events[..],
+            [PaperEvent::Closed(t), PaperEvent::Opened(_)] if t.exit_reason == ExitReason::Reversal
+        ));
+        let closed = trader.on_trade("btcusdt", 7_000, 100.0);
+        assert!(
+            matches!(&closed[..], [PaperEvent::Closed(t)] if t.exit_reason == ExitReason::StopLoss)
+        );
+
+        let report = trader.report();
+        assert_eq!(report[0].trades, 3);
+        assert_eq!(report[0].wins, 1);
+        assert!(report[0].max_drawdown > 0.0);
+        assert_eq!(report[0].recent_trades.len(), 3);
+        assert!(
+            format_paper_report(&report)[0].starts_with("[PAPER_REPORT] big_move trades=3 win=33%")
+        );
+    }
+
+    #[test]
+    fn holds_until_time_exit_and_falls_back_when_the_book_is_stale() {
+        let mut trader = PaperTrader::new(&PaperConfig {
+            enabled: true,
+            strategies: vec![PaperStrategy {
+                stop_loss_pct: 0.0,
+                take_profit_pct: 0.0,
+                max_hold_secs: 60,
+                ..PaperStrategy::default()
+            }],
+            fee_bps: 0.0,
+            fallback_slippage_bps: 5.0,
+            report_interval_secs: 0,
+        });
+        trader.on_trade("btcusdt", 0, 100.0);
+        // The touch is 10% away from the last trade, so the book is ignored.
+        trader.on_depth(&depth(&[("90", "100")], &[("110", "100")]));
+
+        let opened = trader.on_signal(&signal(1, 1_000));
+        let [PaperEvent::Opened(position)] = &opened[..] else {
+            panic!("expected an open, got {opened:?}");
+        };
+        assert!((position.entry_price - 100.05).abs() < 1e-9);
+        assert_eq!(position.entry_slippage_bps, 5.0);
+
+        // Far past any stop or target, but only the holding time closes it.
+        assert!(trader.on_trade("btcusdt", 60_999, 80.0).is_empty());
+        let closed = trader.on_trade("btcusdt", 61_000, 100.0);
+        let [PaperEvent::Closed(trade)] = &closed[..] else {
+            panic!("expected a close, got {closed:?}");
+        };
+        assert_eq!(trade.exit_reason, ExitReason::TimeExit);
+        assert_eq!(trade.exit_ms, 61_000);
+        // The sell exit slips below the mark too: 100.05 in, 99.95 out.
+        assert!((trade.exit_price - 99.95).abs() < 1e-9);
+        assert_eq!(trade.slippage_bps, 10.0);
+        assert!((trade.pnl + 0.1 * trade.qty).abs() < 1e-9);
+    }
+}
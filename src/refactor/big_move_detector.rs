@@ -1,11 +1,157 @@
 use std::collections::VecDeque;
 
+use serde::{Deserialize, Serialize};
+
+/// Named `BigMoveDetector` settings; a symbol can run several (e.g. `fast` and `slow`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BigMoveProfile {
+    pub name: String,
+    pub window_size: usize,
+    pub pressure_threshold: f64,
+    pub min_total_notional: f64,
+    pub min_consecutive: usize,
+    /// Points below the trigger threshold at which an active move counts as exhausted.
+    pub exit_gap_pct: f64,
+    /// Quiet time after exhaustion before the detector can re-arm.
+    pub cooldown_secs: u64,
+    /// Emit an update every N sustained snapshots (0 disables updates).
+    pub update_every: usize,
+}
+
+impl Default for BigMoveProfile {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            window_size: 5,
+            pressure_threshold: 75.0,
+            min_total_notional: 0.0,
+            min_consecutive: 3,
+            exit_gap_pct: 10.0,
+            cooldown_secs: 60,
+            update_every: 10,
+        }
+    }
+}
+
+impl BigMoveProfile {
+    /// Parses `name:window:pressure_pct:min_notional:consecutive`. Trailing fields may be
+    /// omitted and fall back to `base`, e.g. `slow:10:70`. Lifecycle settings always come
+    /// from `base`.
+    pub fn parse(raw: &str, base: &BigMoveProfile) -> Option<Self> {
+        let mut fields = raw.split(':').map(str::trim);
+        let name = fields.next().filter(|n| !n.is_empty())?.to_lowercase();
+        let mut profile = Self {
+            name,
+            ..base.clone()
+        };
+        if let Some(v) = fields.next() {
+            profile.window_size = v.parse().ok().filter(|v| *v > 0)?;
+        }
+        if let Some(v) = fields.next() {
+            profile.pressure_threshold = v.parse().ok().filter(|v| (50.0..=100.0).contains(v))?;
+        }
+        if let Some(v) = fields.next() {
+            profile.min_total_notional = v.parse().ok().filter(|v: &f64| *v >= 0.0)?;
+        }
+        if let Some(v) = fields.next() {
+            profile.min_consecutive = v.parse().ok().filter(|v| *v > 0)?;
+        }
+        if fields.next().is_some() {
+            return None;
+        }
+        profile.min_consecutive = profile.min_consecutive.min(profile.window_size);
+        Some(profile)
+    }
+
+    pub fn as_label(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}",
+            self.name,
+            self.window_size,
+            self.pressure_threshold,
+            self.min_total_notional,
+            self.min_consecutive
+        )
+    }
+}
+
+/// Where a detector is in the life of a move.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BigMovePhase {
+    /// Waiting for consistent extreme pressure.
+    #[default]
+    Armed,
+    /// Fired on the latest snapshot.
+    Triggered,
+    /// Pressure is holding above the exit threshold.
+    Sustained,
+    /// Pressure faded; waiting out the cooldown and a calm book before re-arming.
+    Exhausted,
+}
+
+impl BigMovePhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BigMovePhase::Armed => "armed",
+            BigMovePhase::Triggered => "triggered",
+            BigMovePhase::Sustained => "sustained",
+            BigMovePhase::Exhausted => "exhausted",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BigMoveDirection {
+    Bullish,
+    Bearish,
+}
+
+impl BigMoveDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BigMoveDirection::Bullish => "BULLISH",
+            BigMoveDirection::Bearish => "BEARISH",
+        }
+    }
+
+    /// `1` for bullish, `-1` for bearish.
+    pub fn sign(&self) -> i8 {
+        match self {
+            BigMoveDirection::Bullish => 1,
+            BigMoveDirection::Bearish => -1,
+        }
+    }
+
+    /// Pressure on this side of the book.
+    fn pressure(&self, bid_pressure_pct: f64) -> f64 {
+        match self {
+            BigMoveDirection::Bullish => bid_pressure_pct,
+            BigMoveDirection::Bearish => 100.0 - bid_pressure_pct,
+        }
+    }
+}
+
+/// The move a detector has fired on and is following until exhaustion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ActiveMove {
+    direction: BigMoveDirection,
+    started_ms: u64,
+    peak_pressure: f64,
+    sustained_snapshots: usize,
+}
+
 /// Rolling window of depth pressure snapshots per symbol.
 /// Fires a big-move signal when:
 ///   1. The rolling average pressure is extreme (>= threshold), AND
 ///   2. Total notional exceeds a minimum, AND
 ///   3. The direction has been consistent for `min_consecutive` snapshots.
+///
+/// After firing the detector follows the move (triggered -> sustained -> exhausted) and only
+/// re-arms once the cooldown has passed and the book is back inside the threshold, so one
+/// sustained move produces one start, periodic updates and one end.
 pub struct BigMoveDetector {
+    /// Profile name shown in `[BIGMOVE]` output.
+    profile: String,
     window: VecDeque<DepthSnapshot>,
     window_size: usize,
     /// 0-100. e.g. 75.0 means 75% bid or sell pressure triggers alert.
@@ -14,12 +160,33 @@ pub struct BigMoveDetector {
     min_total_notional: f64,
     /// How many consecutive same-side readings are required.
     min_consecutive: usize,
+    /// Stretches the threshold's distance from a balanced 50% book (volatility regime).
+    pressure_scale: f64,
+    exit_gap_pct: f64,
+    cooldown_ms: u64,
+    update_every: usize,
+    phase: BigMovePhase,
+    active: Option<ActiveMove>,
+    exhausted_at_ms: u64,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DepthSnapshot {
     pub bid_pressure_pct: f64,
     pub total_notional: f64,
+    /// Exchange event time; drives move durations and the cooldown.
+    pub timestamp_ms: u64,
+}
+
+/// Persisted window and phase of one detector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BigMoveDetectorState {
+    /// Profile the state belongs to; restored only into a detector of the same profile.
+    pub profile: String,
+    window: Vec<DepthSnapshot>,
+    phase: BigMovePhase,
+    active: Option<ActiveMove>,
+    exhausted_at_ms: u64,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -32,9 +199,43 @@ pub enum BigMoveSignal {
         avg_pressure: f64,
         total_notional: f64,
     },
+    /// Periodic update while a fired move keeps its pressure.
+    Sustained {
+        direction: BigMoveDirection,
+        avg_pressure: f64,
+        total_notional: f64,
+        duration_ms: u64,
+    },
+    /// The move's pressure dropped below the exit threshold.
+    Ended {
+        direction: BigMoveDirection,
+        peak_pressure: f64,
+        duration_ms: u64,
+    },
     None,
 }
 
+impl BigMoveSignal {
+    /// True for the start-of-move breakouts.
+    pub fn is_start(&self) -> bool {
+        matches!(
+            self,
+            BigMoveSignal::BullishBreakout { .. } | BigMoveSignal::BearishBreakout { .. }
+        )
+    }
+
+    pub fn direction(&self) -> Option<BigMoveDirection> {
+        match self {
+            BigMoveSignal::BullishBreakout { .. } => Some(BigMoveDirection::Bullish),
+            BigMoveSignal::BearishBreakout { .. } => Some(BigMoveDirection::Bearish),
+            BigMoveSignal::Sustained { direction, .. } | BigMoveSignal::Ended { direction, .. } => {
+                Some(*direction)
+            }
+            BigMoveSignal::None => None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct BigMoveEvaluation {
     pub signal: BigMoveSignal,
@@ -48,15 +249,61 @@ impl BigMoveDetector {
         min_total_notional: f64,
         min_consecutive: usize,
     ) -> Self {
+        let defaults = BigMoveProfile::default();
         Self {
+            profile: defaults.name,
             window: VecDeque::with_capacity(window_size),
             window_size,
             pressure_threshold,
             min_total_notional,
             min_consecutive,
+            pressure_scale: 1.0,
+            exit_gap_pct: defaults.exit_gap_pct,
+            cooldown_ms: defaults.cooldown_secs * 1000,
+            update_every: defaults.update_every,
+            phase: BigMovePhase::Armed,
+            active: None,
+            exhausted_at_ms: 0,
         }
     }
 
+    pub fn from_profile(profile: &BigMoveProfile) -> Self {
+        let mut detector = Self::new(
+            profile.window_size,
+            profile.pressure_threshold,
+            profile.min_total_notional,
+            profile.min_consecutive,
+        );
+        detector.profile = profile.name.clone();
+        detector.exit_gap_pct = profile.exit_gap_pct.max(0.0);
+        detector.cooldown_ms = profile.cooldown_secs * 1000;
+        detector.update_every = profile.update_every;
+        detector
+    }
+
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    pub fn phase(&self) -> BigMovePhase {
+        self.phase
+    }
+
+    /// Scales how far from 50% the pressure must be, e.g. 1.5 turns 75% into 87.5%.
+    pub fn set_pressure_scale(&mut self, scale: f64) {
+        self.pressure_scale = scale.max(0.0);
+    }
+
+    /// Pressure threshold after regime scaling, kept within (50, 99].
+    pub fn effective_pressure_threshold(&self) -> f64 {
+        (50.0 + (self.pressure_threshold - 50.0) * self.pressure_scale).clamp(50.0, 99.0)
+    }
+
+    /// Pressure an active move must hold to stay sustained.
+    pub fn exit_pressure_threshold(&self) -> f64 {
+        (self.effective_pressure_threshold() - self.exit_gap_pct).max(50.0)
+    }
+
     /// Push a new depth snapshot and evaluate.
     pub fn push(&mut self, snapshot: DepthSnapshot) -> BigMoveSignal {
         self.push_with_self_explanation(snapshot).signal
@@ -64,45 +311,102 @@ impl BigMoveDetector {
 
     /// Push a new depth snapshot and evaluate with an operator-facing explanation line.
     pub fn push_with_self_explanation(&mut self, snapshot: DepthSnapshot) -> BigMoveEvaluation {
+        let now_ms = snapshot.timestamp_ms;
         if self.window.len() == self.window_size {
             self.window.pop_front();
         }
         self.window.push_back(snapshot);
 
+        match self.phase {
+            BigMovePhase::Armed => self.evaluate_armed(now_ms),
+            BigMovePhase::Triggered | BigMovePhase::Sustained => self.evaluate_active(now_ms),
+            BigMovePhase::Exhausted => self.evaluate_exhausted(now_ms),
+        }
+    }
+
+    /// Window and phase for persisting across restarts.
+    pub fn state(&self) -> BigMoveDetectorState {
+        BigMoveDetectorState {
+            profile: self.profile.clone(),
+            window: self.window.iter().cloned().collect(),
+            phase: self.phase,
+            active: self.active.clone(),
+            exhausted_at_ms: self.exhausted_at_ms,
+        }
+    }
+
+    /// Restores a persisted state, dropping snapshots older than `max_age_ms`. A move that
+    /// was active when the state was saved and has no recent snapshots left is treated as
+    /// exhausted, so it is not reported as a new breakout after a restart.
+    pub fn restore(&mut self, state: BigMoveDetectorState, now_ms: u64, max_age_ms: u64) {
+        let last_seen_ms = state.window.last().map_or(0, |s| s.timestamp_ms);
+        let oldest_ms = now_ms.saturating_sub(max_age_ms);
+        let fresh: Vec<DepthSnapshot> = state
+            .window
+            .into_iter()
+            .filter(|s| s.timestamp_ms >= oldest_ms)
+            .collect();
+        let skip = fresh.len().saturating_sub(self.window_size);
+        self.window = fresh.into_iter().skip(skip).collect();
+        self.exhausted_at_ms = state.exhausted_at_ms;
+        if !self.window.is_empty() {
+            self.phase = state.phase;
+            self.active = state.active;
+        } else if state.active.is_some() || state.phase == BigMovePhase::Exhausted {
+            self.phase = BigMovePhase::Exhausted;
+            self.active = None;
+            self.exhausted_at_ms = self.exhausted_at_ms.max(last_seen_ms);
+        } else {
+            self.phase = BigMovePhase::Armed;
+            self.active = None;
+        }
+    }
+
+    fn explain(&self, signal: BigMoveSignal, message: String) -> BigMoveEvaluation {
+        BigMoveEvaluation {
+            signal,
+            self_explanation_log: format!("[BIGMOVE][SELF_EXPLAIN][{}] {}", self.profile, message),
+        }
+    }
+
+    /// Last `min_consecutive` snapshots, newest first.
+    fn recent(&self) -> Vec<&DepthSnapshot> {
+        self.window
+            .iter()
+            .rev()
+            .take(self.min_consecutive)
+            .collect()
+    }
+
+    fn evaluate_armed(&mut self, now_ms: u64) -> BigMoveEvaluation {
         if self.window.len() < self.min_consecutive {
-            return BigMoveEvaluation {
-                signal: BigMoveSignal::None,
-                self_explanation_log: format!(
-                    "[BIGMOVE][SELF_EXPLAIN] insufficient history ({}/{})",
+            return self.explain(
+                BigMoveSignal::None,
+                format!(
+                    "insufficient history ({}/{})",
                     self.window.len(),
                     self.min_consecutive
                 ),
-            };
+            );
         }
 
         // Check last `min_consecutive` snapshots for consistent extreme pressure
-        let recent: Vec<&DepthSnapshot> = self
-            .window
-            .iter()
-            .rev()
-            .take(self.min_consecutive)
-            .collect();
+        let threshold = self.effective_pressure_threshold();
+        let recent = self.recent();
 
-        let all_bullish = recent
-            .iter()
-            .all(|s| s.bid_pressure_pct >= self.pressure_threshold);
+        let all_bullish = recent.iter().all(|s| s.bid_pressure_pct >= threshold);
         let all_bearish = recent
             .iter()
-            .all(|s| (100.0 - s.bid_pressure_pct) >= self.pressure_threshold);
+            .all(|s| (100.0 - s.bid_pressure_pct) >= threshold);
 
         if !all_bullish && !all_bearish {
-            return BigMoveEvaluation {
-                signal: BigMoveSignal::None,
-                self_explanation_log: format!(
-                    "[BIGMOVE][SELF_EXPLAIN] no consistent extreme pressure in last {} snapshots (threshold={:.1}%)",
-                    self.min_consecutive, self.pressure_threshold
+            return self.explain(
+                BigMoveSignal::None,
+                format!(
+                    "no consistent extreme pressure in last {} snapshots (threshold={:.1}%)",
+                    self.min_consecutive, threshold
                 ),
-            };
+            );
         }
 
         let avg_pressure: f64 =
@@ -111,45 +415,225 @@ impl BigMoveDetector {
             recent.iter().map(|s| s.total_notional).sum::<f64>() / recent.len() as f64;
 
         if avg_notional < self.min_total_notional {
-            return BigMoveEvaluation {
-                signal: BigMoveSignal::None,
-                self_explanation_log: format!(
-                    "[BIGMOVE][SELF_EXPLAIN] pressure extreme but notional too small ({:.0} < {:.0})",
+            return self.explain(
+                BigMoveSignal::None,
+                format!(
+                    "pressure extreme but notional too small ({:.0} < {:.0})",
                     avg_notional, self.min_total_notional
                 ),
-            };
+            );
         }
 
-        if all_bullish {
-            BigMoveEvaluation {
-                signal: BigMoveSignal::BullishBreakout {
-                    avg_pressure,
-                    total_notional: avg_notional,
+        let direction = if all_bullish {
+            BigMoveDirection::Bullish
+        } else {
+            BigMoveDirection::Bearish
+        };
+        let side_pressure = direction.pressure(avg_pressure);
+        let peak_pressure = recent
+            .iter()
+            .map(|s| direction.pressure(s.bid_pressure_pct))
+            .fold(side_pressure, f64::max);
+        self.phase = BigMovePhase::Triggered;
+        self.active = Some(ActiveMove {
+            direction,
+            started_ms: now_ms,
+            peak_pressure,
+            sustained_snapshots: 0,
+        });
+
+        let signal = match direction {
+            BigMoveDirection::Bullish => BigMoveSignal::BullishBreakout {
+                avg_pressure: side_pressure,
+                total_notional: avg_notional,
+            },
+            BigMoveDirection::Bearish => BigMoveSignal::BearishBreakout {
+                avg_pressure: side_pressure,
+                total_notional: avg_notional,
+            },
+        };
+        self.explain(
+            signal,
+            format!(
+                "armed -> triggered: {} breakout: avg_pressure={:.1}% avg_notional={:.0} threshold={:.1}%",
+                direction.as_str().to_lowercase(),
+                side_pressure,
+                avg_notional,
+                threshold
+            ),
+        )
+    }
+
+    fn evaluate_active(&mut self, now_ms: u64) -> BigMoveEvaluation {
+        let Some(mut active) = self.active.take() else {
+            self.phase = BigMovePhase::Armed;
+            return self.evaluate_armed(now_ms);
+        };
+        let previous = self.phase;
+        let exit = self.exit_pressure_threshold();
+        let direction = active.direction;
+        let duration_ms = now_ms.saturating_sub(active.started_ms);
+        let recent = self.recent();
+        let latest_pressure = direction.pressure(recent[0].bid_pressure_pct);
+
+        if latest_pressure < exit {
+            self.phase = BigMovePhase::Exhausted;
+            self.exhausted_at_ms = now_ms;
+            return self.explain(
+                BigMoveSignal::Ended {
+                    direction,
+                    peak_pressure: active.peak_pressure,
+                    duration_ms,
                 },
-                self_explanation_log: format!(
-                    "[BIGMOVE][SELF_EXPLAIN] bullish breakout: avg_pressure={:.1}% avg_notional={:.0} threshold={:.1}%",
-                    avg_pressure, avg_notional, self.pressure_threshold
+                format!(
+                    "{} -> exhausted: {} pressure {:.1}% < exit {:.1}% peak={:.1}% after {}s",
+                    previous.as_str(),
+                    direction.as_str().to_lowercase(),
+                    latest_pressure,
+                    exit,
+                    active.peak_pressure,
+                    duration_ms / 1000
                 ),
+            );
+        }
+
+        let avg_pressure = recent
+            .iter()
+            .map(|s| direction.pressure(s.bid_pressure_pct))
+            .sum::<f64>()
+            / recent.len() as f64;
+        let avg_notional =
+            recent.iter().map(|s| s.total_notional).sum::<f64>() / recent.len() as f64;
+        active.peak_pressure = active.peak_pressure.max(latest_pressure);
+        active.sustained_snapshots += 1;
+        let due =
+            self.update_every > 0 && active.sustained_snapshots.is_multiple_of(self.update_every);
+        self.active = Some(active);
+        self.phase = BigMovePhase::Sustained;
+
+        let transition = if previous == BigMovePhase::Sustained {
+            "sustained".to_string()
+        } else {
+            format!("{} -> sustained", previous.as_str())
+        };
+        let signal = if due {
+            BigMoveSignal::Sustained {
+                direction,
+                avg_pressure,
+                total_notional: avg_notional,
+                duration_ms,
             }
         } else {
-            let bearish_pressure = 100.0 - avg_pressure;
-            BigMoveEvaluation {
-                signal: BigMoveSignal::BearishBreakout {
-                    avg_pressure: bearish_pressure,
-                    total_notional: avg_notional,
-                },
-                self_explanation_log: format!(
-                    "[BIGMOVE][SELF_EXPLAIN] bearish breakout: avg_pressure={:.1}% avg_notional={:.0} threshold={:.1}%",
-                    bearish_pressure, avg_notional, self.pressure_threshold
+            BigMoveSignal::None
+        };
+        self.explain(
+            signal,
+            format!(
+                "{}: {} pressure {:.1}% >= exit {:.1}% for {}s",
+                transition,
+                direction.as_str().to_lowercase(),
+                latest_pressure,
+                exit,
+                duration_ms / 1000
+            ),
+        )
+    }
+
+    fn evaluate_exhausted(&mut self, now_ms: u64) -> BigMoveEvaluation {
+        let elapsed = now_ms.saturating_sub(self.exhausted_at_ms);
+        if elapsed < self.cooldown_ms {
+            return self.explain(
+                BigMoveSignal::None,
+                format!(
+                    "exhausted: cooldown {}s remaining",
+                    (self.cooldown_ms - elapsed).div_ceil(1000)
                 ),
-            }
+            );
+        }
+
+        let threshold = self.effective_pressure_threshold();
+        let bid_pressure = self.window.back().map_or(50.0, |s| s.bid_pressure_pct);
+        let extreme = bid_pressure.max(100.0 - bid_pressure);
+        if extreme >= threshold {
+            return self.explain(
+                BigMoveSignal::None,
+                format!(
+                    "exhausted: waiting for pressure {:.1}% to fall back under {:.1}% before re-arming",
+                    extreme, threshold
+                ),
+            );
         }
+
+        self.phase = BigMovePhase::Armed;
+        self.explain(
+            BigMoveSignal::None,
+            format!(
+                "exhausted -> armed: reset (pressure {:.1}% < threshold {:.1}%)",
+                extreme, threshold
+            ),
+        )
+    }
+}
+
+/// `[BIGMOVE]` alert line for a start, update or end signal.
+pub fn format_big_move_alert(
+    symbol: &str,
+    profile: &str,
+    signal: &BigMoveSignal,
+) -> Option<String> {
+    let symbol = symbol.to_uppercase();
+    match signal {
+        BigMoveSignal::BullishBreakout {
+            avg_pressure,
+            total_notional,
+        }
+        | BigMoveSignal::BearishBreakout {
+            avg_pressure,
+            total_notional,
+        } => Some(format!(
+            "[BIGMOVE] {} {} BREAKOUT likely! profile={} avg_pressure={:.1}% notional={:.0}",
+            symbol,
+            signal.direction()?.as_str(),
+            profile,
+            avg_pressure,
+            total_notional
+        )),
+        BigMoveSignal::Sustained {
+            direction,
+            avg_pressure,
+            total_notional,
+            duration_ms,
+        } => Some(format!(
+            "[BIGMOVE] {} {} SUSTAINED profile={} avg_pressure={:.1}% notional={:.0} for {}s",
+            symbol,
+            direction.as_str(),
+            profile,
+            avg_pressure,
+            total_notional,
+            duration_ms / 1000
+        )),
+        BigMoveSignal::Ended {
+            direction,
+            peak_pressure,
+            duration_ms,
+        } => Some(format!(
+            "[BIGMOVE] {} {} ENDED profile={} peak_pressure={:.1}% after {}s",
+            symbol,
+            direction.as_str(),
+            profile,
+            peak_pressure,
+            duration_ms / 1000
+        )),
+        BigMoveSignal::None => None,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{BigMoveDetector, BigMoveSignal, DepthSnapshot};
+    use super::{
+        BigMoveDetector, BigMoveDirection, BigMovePhase, BigMoveProfile, BigMoveSignal,
+        DepthSnapshot, format_big_move_alert,
+    };
 
     #[test]
     fn self_explanation_reports_insufficient_history() {
@@ -157,32 +641,109 @@ mod tests {
         let result = detector.push_with_self_explanation(DepthSnapshot {
             bid_pressure_pct: 80.0,
             total_notional: 10_000.0,
+            timestamp_ms: 0,
         });
 
         assert_eq!(result.signal, BigMoveSignal::None);
         assert!(result.self_explanation_log.contains("insufficient history"));
     }
 
+    fn snap(bid_pressure_pct: f64, timestamp_ms: u64) -> DepthSnapshot {
+        DepthSnapshot {
+            bid_pressure_pct,
+            total_notional: 50_000.0,
+            timestamp_ms,
+        }
+    }
+
     #[test]
     fn self_explanation_reports_bullish_breakout() {
         let mut detector = BigMoveDetector::new(5, 75.0, 0.0, 3);
-        for pressure in [80.0, 82.0, 85.0] {
-            let _ = detector.push_with_self_explanation(DepthSnapshot {
-                bid_pressure_pct: pressure,
-                total_notional: 50_000.0,
-            });
+        for pressure in [80.0, 82.0] {
+            let _ = detector.push_with_self_explanation(snap(pressure, 0));
         }
 
-        let result = detector.push_with_self_explanation(DepthSnapshot {
-            bid_pressure_pct: 88.0,
-            total_notional: 50_000.0,
-        });
+        let result = detector.push_with_self_explanation(snap(85.0, 0));
 
         assert!(matches!(
             result.signal,
             BigMoveSignal::BullishBreakout { .. }
         ));
         assert!(result.self_explanation_log.contains("bullish breakout"));
+        assert_eq!(detector.phase(), BigMovePhase::Triggered);
+
+        // Held pressure does not fire a second breakout.
+        let result = detector.push_with_self_explanation(snap(88.0, 1_000));
+        assert_eq!(result.signal, BigMoveSignal::None);
+        assert!(
+            result
+                .self_explanation_log
+                .contains("triggered -> sustained")
+        );
+    }
+
+    #[test]
+    fn lifecycle_updates_ends_and_rearms_after_cooldown() {
+        let profile = BigMoveProfile {
+            update_every: 2,
+            cooldown_secs: 30,
+            ..BigMoveProfile::default()
+        };
+        let mut detector = BigMoveDetector::from_profile(&profile);
+        let mut signals = Vec::new();
+        for (i, pressure) in [80.0, 82.0, 85.0, 84.0, 78.0, 70.0, 60.0]
+            .into_iter()
+            .enumerate()
+        {
+            signals.push(detector.push(snap(pressure, i as u64 * 1_000)));
+        }
+        assert!(signals[2].is_start());
+        assert_eq!(signals[3], BigMoveSignal::None);
+        assert!(matches!(
+            signals[4],
+            BigMoveSignal::Sustained {
+                direction: BigMoveDirection::Bullish,
+                duration_ms: 2_000,
+                ..
+            }
+        ));
+        // 70% is still above the 65% exit threshold; 60% ends the move.
+        assert_eq!(signals[5], BigMoveSignal::None);
+        assert_eq!(
+            signals[6],
+            BigMoveSignal::Ended {
+                direction: BigMoveDirection::Bullish,
+                peak_pressure: 85.0,
+                duration_ms: 4_000,
+            }
+        );
+        let alert = format_big_move_alert("btcusdt", detector.profile(), &signals[6]).unwrap();
+        assert_eq!(
+            alert,
+            "[BIGMOVE] BTCUSDT BULLISH ENDED profile=default peak_pressure=85.0% after 4s"
+        );
+
+        // Extreme pressure during the cooldown is ignored.
+        for ts in [10_000, 20_000, 30_000] {
+            let result = detector.push_with_self_explanation(snap(90.0, ts));
+            assert_eq!(result.signal, BigMoveSignal::None);
+        }
+        assert_eq!(detector.phase(), BigMovePhase::Exhausted);
+
+        // After the cooldown the book must calm down before the detector re-arms.
+        let result = detector.push_with_self_explanation(snap(90.0, 37_000));
+        assert!(result.self_explanation_log.contains("waiting for pressure"));
+        let result = detector.push_with_self_explanation(snap(55.0, 38_000));
+        assert!(
+            result
+                .self_explanation_log
+                .contains("exhausted -> armed: reset")
+        );
+        let mut last = BigMoveSignal::None;
+        for ts in [39_000, 40_000, 41_000] {
+            last = detector.push(snap(20.0, ts));
+        }
+        assert!(matches!(last, BigMoveSignal::BearishBreakout { .. }));
     }
 
     #[test]
@@ -191,17 +752,85 @@ mod tests {
         let _ = detector.push_with_self_explanation(DepthSnapshot {
             bid_pressure_pct: 80.0,
             total_notional: 1_000.0,
+            timestamp_ms: 0,
         });
         let _ = detector.push_with_self_explanation(DepthSnapshot {
             bid_pressure_pct: 81.0,
             total_notional: 1_000.0,
+            timestamp_ms: 0,
         });
         let result = detector.push_with_self_explanation(DepthSnapshot {
             bid_pressure_pct: 82.0,
             total_notional: 1_000.0,
+            timestamp_ms: 0,
         });
 
         assert_eq!(result.signal, BigMoveSignal::None);
         assert!(result.self_explanation_log.contains("notional too small"));
     }
+
+    #[test]
+    fn pressure_scale_raises_threshold_in_high_volatility() {
+        let mut detector = BigMoveDetector::new(5, 75.0, 0.0, 3);
+        detector.set_pressure_scale(1.5);
+        assert_eq!(detector.effective_pressure_threshold(), 87.5);
+        let mut last = BigMoveSignal::None;
+        for pressure in [80.0, 82.0, 85.0] {
+            last = detector.push(DepthSnapshot {
+                bid_pressure_pct: pressure,
+                total_notional: 50_000.0,
+                timestamp_ms: 0,
+            });
+        }
+        assert_eq!(last, BigMoveSignal::None);
+
+        detector.set_pressure_scale(1.0);
+        assert!(matches!(
+            detector.push(DepthSnapshot {
+                bid_pressure_pct: 88.0,
+                total_notional: 50_000.0,
+                timestamp_ms: 0,
+            }),
+            BigMoveSignal::BullishBreakout { .. }
+        ));
+    }
+
+    #[test]
+    fn profiles_parse_with_fallback_and_name_the_output() {
+        let base = BigMoveProfile::default();
+        let fast = BigMoveProfile::parse("Fast:3:80:50000:2", &base).expect("full profile");
+        assert_eq!(fast.name, "fast");
+        assert_eq!(
+            (
+                fast.window_size,
+                fast.min_total_notional,
+                fast.min_consecutive
+            ),
+            (3, 50_000.0, 2)
+        );
+        let slow = BigMoveProfile::parse("slow:10:70", &base).expect("partial profile");
+        assert_eq!(slow.min_consecutive, base.min_consecutive);
+        assert!(BigMoveProfile::parse("bad:0", &base).is_none());
+        assert!(BigMoveProfile::parse("bad:5:40", &base).is_none());
+        assert!(BigMoveProfile::parse(":5", &base).is_none());
+
+        let mut detector = BigMoveDetector::from_profile(&fast);
+        let mut evaluation = None;
+        for _ in 0..2 {
+            evaluation = Some(detector.push_with_self_explanation(DepthSnapshot {
+                bid_pressure_pct: 15.0,
+                total_notional: 60_000.0,
+                timestamp_ms: 0,
+            }));
+        }
+        let evaluation = evaluation.unwrap();
+        assert!(
+            evaluation
+                .self_explanation_log
+                .starts_with("[BIGMOVE][SELF_EXPLAIN][fast]")
+        );
+        let alert = format_big_move_alert("btcusdt", detector.profile(), &evaluation.signal)
+            .expect("bearish breakout");
+        assert!(alert.starts_with("[BIGMOVE] BTCUSDT BEARISH BREAKOUT likely! profile=fast"));
+    }
 }
@@ -5,20 +5,39 @@ use serde_json::json;
 use tokio::sync::broadcast;
 
 use crate::{
-    binance::{AggTrade, calc_spike, log_and_broadcast},
+    binance::{AggTrade, log_and_broadcast},
     binance_depth::{
         DepthUpdate, collect_big_levels, format_notional_compact, format_pressure_visual,
         is_big_depth_update, passes_pressure_filter,
     },
-    binance_kline::{KlineEvent, build_quant_signal_from_kline},
+    binance_kline::{KlineEvent, build_quant_signal_from_kline, format_quant_log},
     config::{Config, SymbolConfig},
+    conviction::{ConvictionScorer, emit_conviction},
+    cross_asset::{CrossAssetTracker, MoveEvent, format_decomposition_log, format_matrix_log},
+    decimal::{Decimal, SymbolPrecision},
+    logging,
     news::{correlation::CorrelationService, store::NewsStore},
     notify::{NotificationFanout, build_signal_notification, telegram::TelegramNotifier},
+    outcomes::{OutcomeStore, OutcomeTracker, TrackedSignal, emit_outcomes, spawn_outcome_report},
+    paper::{PaperTrader, emit_paper_events, emit_paper_report},
+    rules::{RuleEngine, SharedRules, dispatch_rule_matches, init_rules},
+    snapshot::{SymbolState, collect_symbol_states, restore_big_move_detectors},
+    spike_window::{SpikeWindowDetector, WindowMove, format_spike_log},
+    threshold::AdaptiveThresholds,
+    trade_flow::{FlowSnapshot, TradeFlowTracker, format_flow_imbalance_log, format_flow_log},
+    volatility::{VolatilityTracker, format_vol_regime_log},
+    whale::{
+        BookSide, IcebergDetector, IcebergSignal, TakerSide, TradeCluster, WhaleClusterDetector,
+        format_iceberg_log, format_whale_log,
+    },
 };
 
-use self::big_move_detector::{BigMoveDetector, BigMoveSignal, DepthSnapshot};
+use self::big_move_detector::{BigMoveDetector, DepthSnapshot, format_big_move_alert};
 pub mod big_move_detector;
 
+/// `(price, qty)` pairs for depth levels that pass the big-level filters.
+type BigLevels = Vec<(f64, f64)>;
+
 /// Shared application state
 pub struct AppState {
     /// App configuration
@@ -28,7 +47,29 @@ pub struct AppState {
     /// Map of symbol to last trade price
     last_prices: HashMap<String, f64>,
     /// Map of symbol to big move detector
-    big_move_detectors: HashMap<String, BigMoveDetector>,
+    big_move_detectors: HashMap<String, Vec<BigMoveDetector>>,
+    /// Windowed high/low spike detection per symbol
+    spike_detector: SpikeWindowDetector,
+    /// Per-symbol big-trade quantity thresholds (notional, percentile or volume multiple)
+    big_trade_thresholds: AdaptiveThresholds,
+    /// Same-side aggressive trade clusters per symbol
+    whale_detector: WhaleClusterDetector,
+    /// Depth level refill tracking for iceberg detection
+    iceberg_detector: IcebergDetector,
+    /// Rolling taker flow per symbol, when enabled
+    trade_flow: Option<TradeFlowTracker>,
+    /// Return correlations and beta against the reference symbol, when enabled
+    cross_asset: Option<CrossAssetTracker>,
+    /// Realized volatility regimes per symbol, when enabled
+    volatility: Option<VolatilityTracker>,
+    /// Forward returns of big-move and quant signals, when enabled
+    outcomes: Option<(OutcomeTracker, OutcomeStore)>,
+    /// Simulated positions on tracked signals, when enabled
+    paper: Option<PaperTrader>,
+    /// Combined depth, flow and quant score per symbol, when enabled
+    conviction: Option<ConvictionScorer>,
+    /// User-defined alert rules, when configured
+    rules: Option<RuleEngine>,
     correlation_service: Option<CorrelationService>,
     notifier: NotificationFanout,
 }
@@ -40,17 +81,22 @@ impl AppState {
         let mut big_move_detectors = HashMap::new();
 
         for cfg in &config.symbols {
-            println!(
-                "Symbol: {} => Big Trade Qty: {}, Spike %: {}",
+            log::info!(
+                target: "config",
+                "Symbol: {} => Big Trade Qty: {} ({}), Spike %: {}, Whale notional: {}, Big-move profiles: {}",
                 cfg.symbol.to_uppercase(),
                 cfg.big_trade_qty,
-                cfg.spike_pct
+                cfg.big_trade_rule.as_label(),
+                cfg.spike_pct,
+                cfg.whale_notional,
+                cfg.big_move_profiles
+                    .iter()
+                    .map(|p| p.as_label())
+                    .collect::<Vec<_>>()
+                    .join(", ")
             );
             config_map.insert(cfg.symbol.clone(), cfg.clone());
-            big_move_detectors.insert(
-                cfg.symbol.to_lowercase(),
-                BigMoveDetector::new(5, 75.0, 0.0, 3),
-            );
+            big_move_detectors.insert(cfg.symbol.to_lowercase(), Self::big_move_detectors_for(cfg));
         }
 
         let correlation_service = Self::build_correlation_service(&config).ok();
@@ -59,14 +105,193 @@ impl AppState {
             .telegram
             .is_ready()
             .then(|| TelegramNotifier::new(config.telegram.clone()));
+        let spike_detector = SpikeWindowDetector::new(&config.spike_windows_secs);
+        let big_trade_thresholds = AdaptiveThresholds::new(
+            config.threshold.window_secs,
+            config.threshold.recalc_interval_secs,
+            config.threshold.min_samples,
+        );
+        let whale_detector =
+            WhaleClusterDetector::new(config.whale.cluster_window_ms, config.whale.adjacent_bps);
+        let iceberg_detector = IcebergDetector::new(
+            config.whale.iceberg_window_secs,
+            config.whale.iceberg_min_refills,
+            config.whale.iceberg_refill_ratio,
+        );
+        let trade_flow = config
+            .flow
+            .enabled
+            .then(|| TradeFlowTracker::new(config.flow.emit_interval_secs));
+        let cross_asset = config
+            .cross_asset
+            .enabled
+            .then(|| CrossAssetTracker::new(&config.cross_asset));
+        let volatility = config
+            .volatility
+            .enabled
+            .then(|| VolatilityTracker::new(&config.volatility));
+        let outcomes = config
+            .outcomes
+            .enabled
+            .then(|| match OutcomeStore::open(&config.outcomes.db_path) {
+                Ok(store) => Some((OutcomeTracker::new(&config.outcomes), store)),
+                Err(err) => {
+                    log::warn!(target: "outcomes", "tracking disabled, failed to open db: {err}");
+                    None
+                }
+            })
+            .flatten();
+        let paper = config
+            .paper
+            .enabled
+            .then(|| PaperTrader::new(&config.paper));
+        let conviction = config.conviction.enabled.then(|| {
+            ConvictionScorer::new(
+                &config.conviction,
+                config.funding_rate_alert_pct,
+                config.flow.imbalance_min_notional,
+            )
+        });
+        let rules = config
+            .rules
+            .enabled()
+            .then(|| RuleEngine::new(init_rules(&config.rules), &config.rules));
+        let notifier = NotificationFanout::new(telegram)
+            .with_digest(config.digest.clone(), &config.telegram)
+            .with_delivery_queue(&config.delivery);
 
         Self {
             config,
             config_map,
             last_prices: HashMap::new(),
             big_move_detectors,
+            spike_detector,
+            big_trade_thresholds,
+            whale_detector,
+            iceberg_detector,
+            trade_flow,
+            cross_asset,
+            volatility,
+            outcomes,
+            paper,
+            conviction,
+            rules,
             correlation_service,
-            notifier: NotificationFanout::new(telegram),
+            notifier,
+        }
+    }
+
+    /// Starts processing a symbol added by the dynamic universe.
+    pub fn add_symbol(&mut self, cfg: SymbolConfig) {
+        let symbol = cfg.symbol.to_lowercase();
+        self.big_move_detectors
+            .entry(symbol.clone())
+            .or_insert_with(|| Self::big_move_detectors_for(&cfg));
+        self.config_map.insert(symbol, cfg);
+    }
+
+    fn big_move_detectors_for(cfg: &SymbolConfig) -> Vec<BigMoveDetector> {
+        cfg.big_move_profiles
+            .iter()
+            .map(BigMoveDetector::from_profile)
+            .collect()
+    }
+
+    /// Stops processing a symbol and drops all of its detector state.
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        let symbol = symbol.to_lowercase();
+        self.config_map.remove(&symbol);
+        self.last_prices.remove(&symbol);
+        self.big_move_detectors.remove(&symbol);
+        self.spike_detector.remove_symbol(&symbol);
+        self.big_trade_thresholds.remove_symbol(&symbol);
+        self.whale_detector.remove_symbol(&symbol);
+        self.iceberg_detector.remove_symbol(&symbol);
+        if let Some(flow) = self.trade_flow.as_mut() {
+            flow.remove_symbol(&symbol);
+        }
+        if let Some(tracker) = self.cross_asset.as_mut() {
+            tracker.remove_symbol(&symbol);
+        }
+        if let Some(tracker) = self.volatility.as_mut() {
+            tracker.remove_symbol(&symbol);
+        }
+        if let Some(scorer) = self.conviction.as_mut() {
+            scorer.remove_symbol(&symbol);
+        }
+        if let Some(trader) = self.paper.as_mut() {
+            trader.remove_symbol(&symbol);
+        }
+        if let Some(rules) = self.rules.as_mut() {
+            rules.remove_symbol(&symbol);
+        }
+    }
+
+    /// Store behind the outcome stats API, when outcome tracking is enabled.
+    pub fn outcome_store(&self) -> Option<OutcomeStore> {
+        self.outcomes.as_ref().map(|(_, store)| store.clone())
+    }
+
+    /// Rule set behind the alert rules, for the reload task, when rules are configured.
+    pub fn alert_rules(&self) -> Option<SharedRules> {
+        self.rules.as_ref().map(|engine| engine.rules().clone())
+    }
+
+    /// Shutdown: emits open digests and waits for queued alerts until `deadline`. Returns
+    /// the number of alerts left undelivered.
+    pub async fn drain_notifications(
+        &self,
+        tx: &broadcast::Sender<String>,
+        deadline: tokio::time::Instant,
+    ) -> usize {
+        self.notifier.drain(tx, deadline).await
+    }
+
+    /// Per-symbol detector state for the state file.
+    pub fn export_state(&self) -> HashMap<String, SymbolState> {
+        collect_symbol_states(&self.last_prices, &self.big_move_detectors, &HashMap::new())
+    }
+
+    /// Restores state saved by a previous run for the symbols being processed.
+    pub fn restore_state(&mut self, symbols: HashMap<String, SymbolState>, now_ms: u64) {
+        let max_age_ms = self.config.state.max_age_secs.saturating_mul(1_000);
+        for (symbol, state) in symbols {
+            let Some(detectors) = self.big_move_detectors.get_mut(&symbol) else {
+                continue;
+            };
+            restore_big_move_detectors(detectors, state.big_move, now_ms, max_age_ms);
+            if let Some(price) = state.last_price {
+                self.last_prices.insert(symbol, price);
+            }
+        }
+    }
+
+    async fn observe_rules(
+        &mut self,
+        symbol: &str,
+        timestamp_ms: u64,
+        readings: &[(&str, f64)],
+        tx: &broadcast::Sender<String>,
+    ) {
+        let Some(engine) = self.rules.as_mut() else {
+            return;
+        };
+        let matches = engine.observe(symbol, timestamp_ms, readings);
+        dispatch_rule_matches(
+            matches,
+            tx,
+            self.correlation_service.as_ref(),
+            &self.notifier,
+        )
+        .await;
+    }
+
+    fn track_signal(&mut self, signal: TrackedSignal, tx: &broadcast::Sender<String>) {
+        if let Some(trader) = self.paper.as_mut() {
+            emit_paper_events(trader.on_signal(&signal), tx);
+        }
+        if let Some((tracker, _)) = self.outcomes.as_mut() {
+            tracker.track(signal);
         }
     }
 
@@ -76,7 +301,7 @@ impl AppState {
         Ok(CorrelationService::from_env(store))
     }
 
-    fn build_enriched_payload(
+    async fn build_enriched_payload(
         &self,
         signal_type: &str,
         symbol: &str,
@@ -84,7 +309,7 @@ impl AppState {
         move_metrics: serde_json::Value,
     ) -> Option<crate::notify::SignalNotification> {
         let service = self.correlation_service.as_ref()?;
-        let correlation = service.correlate(symbol, event_ts_ms).ok()?;
+        let correlation = service.correlate(symbol, event_ts_ms).await.ok()?;
 
         Some(build_signal_notification(
             signal_type,
@@ -104,8 +329,9 @@ impl AppState {
         event_ts_ms: i64,
         move_metrics: serde_json::Value,
     ) {
-        if let Some(payload) =
-            self.build_enriched_payload(signal_type, symbol, event_ts_ms, move_metrics)
+        if let Some(payload) = self
+            .build_enriched_payload(signal_type, symbol, event_ts_ms, move_metrics)
+            .await
         {
             self.notifier.dispatch(tx, payload).await;
         }
@@ -117,13 +343,64 @@ impl AppState {
             None => return,
         };
 
-        let current_price = agg.p.parse::<f64>().unwrap_or(0.0);
-        let prev_price = self.last_prices.get(&symbol).copied();
-        let spike = calc_spike(prev_price, current_price);
+        let current_price = agg.p.to_f64();
+        let qty = agg.q.to_f64();
+        let mut spike_pct = cfg.spike_pct;
+        if let Some(tracker) = self.volatility.as_mut() {
+            if let Some(change) = tracker.record_trade(&symbol, agg.t, current_price) {
+                let msg = format_vol_regime_log(&change);
+                logging::signal("vol_regime", &msg);
+                let _ = tx.send(msg);
+                let _ = tx.send(change.to_event_json());
+            }
+            spike_pct *= tracker.threshold_scale(&symbol);
+        }
+        let evaluation = self
+            .spike_detector
+            .push(&symbol, agg.t, current_price, qty, spike_pct);
+        let big_trade_qty = self.big_trade_thresholds.observe(
+            &symbol,
+            agg.t,
+            current_price,
+            qty,
+            cfg.big_trade_rule,
+            cfg.big_trade_qty,
+        );
+        let spike = evaluation
+            .spikes
+            .iter()
+            .map(|s| s.move_pct.abs())
+            .fold(0.0, f64::max);
 
         self.last_prices.insert(symbol.clone(), current_price);
 
-        log_and_broadcast(tx, agg, spike, cfg).await;
+        if let Some((tracker, store)) = self.outcomes.as_mut() {
+            let mut completed = tracker.on_trade(&symbol, agg.t, current_price);
+            if tracker.report_due(agg.t) {
+                completed.extend(tracker.expire(agg.t));
+                spawn_outcome_report(store.clone(), tx.clone());
+            }
+            emit_outcomes(completed, store, tx);
+        }
+        if let Some(trader) = self.paper.as_mut() {
+            emit_paper_events(trader.on_trade(&symbol, agg.t, current_price), tx);
+            if trader.report_due(agg.t) {
+                emit_paper_report(trader, tx);
+            }
+        }
+
+        let matrix = self.cross_asset.as_mut().and_then(|tracker| {
+            tracker.record(&symbol, agg.t, current_price);
+            tracker.matrix_if_due(agg.t)
+        });
+        if let Some(matrix) = matrix {
+            let msg = format_matrix_log(&matrix);
+            logging::signal("cross_asset", &msg);
+            let _ = tx.send(msg);
+            let _ = tx.send(matrix.to_event_json());
+        }
+
+        log_and_broadcast(tx, agg, spike, big_trade_qty, spike_pct).await;
 
         self.send_enriched_payload(
             tx,
@@ -131,10 +408,196 @@ impl AppState {
             &symbol,
             agg.t as i64,
             json!({
-                "price": current_price,
-                "quantity": agg.q.parse::<f64>().unwrap_or(0.0),
-                "spike_pct": spike,
+                "price": agg.p,
+                "quantity": agg.q,
+                "spike_pct": evaluation.strongest_move_pct,
                 "buyer_maker": agg.m,
+                "big_trade_qty": big_trade_qty,
+            }),
+        )
+        .await;
+
+        for window_spike in &evaluation.spikes {
+            self.emit_spike(&symbol, agg.t, window_spike, tx).await;
+        }
+
+        let whale_notional = cfg.whale_notional;
+        let mut whale_reading = 0.0;
+        if let Some(cluster) =
+            self.whale_detector
+                .push(&symbol, agg.t, current_price, qty, agg.m, whale_notional)
+        {
+            whale_reading = match cluster.side {
+                TakerSide::Buy => cluster.notional,
+                TakerSide::Sell => -cluster.notional,
+            };
+            self.emit_whale(&cluster, whale_notional, agg.t, tx).await;
+        }
+
+        let mut readings = vec![
+            ("price", current_price),
+            ("trade_qty", qty),
+            ("trade_notional", current_price * qty),
+            ("spike_pct", evaluation.strongest_move_pct),
+            ("whale_notional", whale_reading),
+        ];
+
+        let flow_snapshot = self.trade_flow.as_mut().and_then(|tracker| {
+            tracker.record(&symbol, agg.t, current_price, qty, agg.m);
+            tracker.snapshot_if_due(&symbol, agg.t)
+        });
+        if let Some(snapshot) = flow_snapshot {
+            self.emit_trade_flow(&snapshot, tx).await;
+            if let Some(scorer) = self.conviction.as_mut() {
+                emit_conviction(scorer.observe_flow(&snapshot), tx);
+            }
+            let imbalance = snapshot
+                .strongest_imbalance(0.0, self.config.flow.imbalance_min_notional)
+                .map_or(0.0, |imbalance| imbalance.imbalance_pct);
+            readings.push(("flow_imbalance_pct", imbalance));
+            readings.push(("cvd", snapshot.cvd));
+        }
+
+        self.observe_rules(&symbol, agg.t, &readings, tx).await;
+    }
+
+    async fn emit_whale(
+        &self,
+        cluster: &TradeCluster,
+        threshold_notional: f64,
+        event_ts_ms: u64,
+        tx: &broadcast::Sender<String>,
+    ) {
+        let whale_msg = format_whale_log(
+            cluster,
+            threshold_notional,
+            &self.precision_for(&cluster.symbol),
+        );
+        logging::signal("whale", &whale_msg);
+        let _ = tx.send(whale_msg);
+
+        self.send_enriched_payload(
+            tx,
+            "whale_cluster",
+            &cluster.symbol,
+            event_ts_ms as i64,
+            json!({
+                "side": cluster.side.as_label(),
+                "notional": cluster.notional,
+                "quantity": cluster.qty,
+                "trade_count": cluster.trade_count,
+                "min_price": cluster.min_price,
+                "max_price": cluster.max_price,
+                "span_ms": cluster.span_ms(),
+            }),
+        )
+        .await;
+    }
+
+    async fn emit_iceberg(
+        &self,
+        iceberg: &IcebergSignal,
+        event_ts_ms: u64,
+        tx: &broadcast::Sender<String>,
+    ) {
+        let iceberg_msg = format_iceberg_log(iceberg, &self.precision_for(&iceberg.symbol));
+        logging::signal("iceberg", &iceberg_msg);
+        let _ = tx.send(iceberg_msg);
+
+        self.send_enriched_payload(
+            tx,
+            "iceberg",
+            &iceberg.symbol,
+            event_ts_ms as i64,
+            json!({
+                "side": iceberg.side.as_label(),
+                "price": iceberg.price,
+                "refills": iceberg.refills,
+                "peak_qty": iceberg.peak_qty,
+                "current_qty": iceberg.current_qty,
+            }),
+        )
+        .await;
+    }
+
+    async fn emit_spike(
+        &self,
+        symbol: &str,
+        event_ts_ms: u64,
+        window_spike: &WindowMove,
+        tx: &broadcast::Sender<String>,
+    ) {
+        let spike_msg = format_spike_log(symbol, window_spike);
+        logging::signal("spike", &spike_msg);
+        let _ = tx.send(spike_msg);
+        self.emit_decomposition(
+            &MoveEvent {
+                symbol: symbol.to_string(),
+                source: "price_spike",
+                start_ms: window_spike.start_ms,
+                end_ms: window_spike.end_ms,
+                move_pct: Some(window_spike.move_pct),
+            },
+            tx,
+        );
+
+        self.send_enriched_payload(
+            tx,
+            "price_spike",
+            symbol,
+            event_ts_ms as i64,
+            json!({
+                "spike_pct": window_spike.move_pct,
+                "window": window_spike.window_label(),
+                "start_price": window_spike.start_price,
+                "end_price": window_spike.end_price,
+                "high": window_spike.high,
+                "low": window_spike.low,
+                "notional": window_spike.notional,
+                "trade_count": window_spike.trade_count,
+            }),
+        )
+        .await;
+    }
+
+    /// Broadcasts how much of a move the reference symbol explains, when cross-asset
+    /// tracking is enabled and has enough history.
+    fn emit_decomposition(&self, event: &MoveEvent, tx: &broadcast::Sender<String>) {
+        let Some(decomposition) = self.cross_asset.as_ref().and_then(|t| t.decompose(event)) else {
+            return;
+        };
+        let msg = format_decomposition_log(&decomposition);
+        logging::signal("cross_asset", &msg);
+        let _ = tx.send(msg);
+        let _ = tx.send(decomposition.to_event_json());
+    }
+
+    async fn emit_trade_flow(&self, snapshot: &FlowSnapshot, tx: &broadcast::Sender<String>) {
+        let flow_msg = format_flow_log(snapshot);
+        logging::signal("trade_flow", &flow_msg);
+        let _ = tx.send(flow_msg);
+
+        let Some(imbalance) = snapshot.strongest_imbalance(
+            self.config.flow.imbalance_alert_pct,
+            self.config.flow.imbalance_min_notional,
+        ) else {
+            return;
+        };
+
+        let alert = format_flow_imbalance_log(snapshot, &imbalance);
+        logging::signal("flow_imbalance", &alert);
+        let _ = tx.send(alert);
+
+        self.send_enriched_payload(
+            tx,
+            "trade_flow_imbalance",
+            &snapshot.symbol,
+            snapshot.timestamp_ms as i64,
+            json!({
+                "imbalance_pct": imbalance.imbalance_pct,
+                "window": imbalance.label,
+                "total_notional": imbalance.total_notional,
+                "cvd": snapshot.cvd,
             }),
         )
         .await;
@@ -146,9 +609,33 @@ impl AppState {
         tx: &broadcast::Sender<String>,
     ) {
         let symbol = depth.symbol.to_lowercase();
-        let cfg = match self.config_map.get(&symbol) {
-            Some(c) => c,
-            None => return,
+        if !self.config_map.contains_key(&symbol) {
+            return;
+        }
+
+        if let Some(trader) = self.paper.as_mut() {
+            trader.on_depth(depth);
+        }
+
+        // Iceberg refills are tracked on every level update, before the big-level filters.
+        let mut icebergs = self.iceberg_detector.on_depth_levels(
+            &symbol,
+            BookSide::Bid,
+            &depth.bids,
+            depth.event_time,
+        );
+        icebergs.extend(self.iceberg_detector.on_depth_levels(
+            &symbol,
+            BookSide::Ask,
+            &depth.asks,
+            depth.event_time,
+        ));
+        for iceberg in &icebergs {
+            self.emit_iceberg(iceberg, depth.event_time, tx).await;
+        }
+
+        let Some(cfg) = self.config_map.get(&symbol) else {
+            return;
         };
 
         let matched_bids = collect_big_levels(&depth.bids, cfg.big_trade_qty, 3);
@@ -175,9 +662,15 @@ impl AppState {
             return;
         }
 
-        let depth_msg = Self::format_depth_message(depth, &big_bids, &big_asks, bid_pressure_pct);
+        let depth_msg = Self::format_depth_message(
+            depth,
+            &big_bids,
+            &big_asks,
+            bid_pressure_pct,
+            &self.precision_for(&symbol),
+        );
 
-        println!("{}", depth_msg);
+        logging::signal("depth", &depth_msg);
         let _ = tx.send(depth_msg.clone());
 
         self.send_enriched_payload(
@@ -195,7 +688,24 @@ impl AppState {
         )
         .await;
 
-        self.detect_big_move(&symbol, bid_pressure_pct, total_notional, depth, tx);
+        if let Some(scorer) = self.conviction.as_mut() {
+            emit_conviction(
+                scorer.observe_depth(&symbol, depth.event_time, bid_pressure_pct),
+                tx,
+            );
+        }
+        let big_move = self.detect_big_move(&symbol, bid_pressure_pct, total_notional, depth, tx);
+        self.observe_rules(
+            &symbol,
+            depth.event_time,
+            &[
+                ("bid_pressure_pct", bid_pressure_pct),
+                ("depth_notional", total_notional),
+                ("big_move", big_move),
+            ],
+            tx,
+        )
+        .await;
     }
 
     fn is_level_big(&self, price: f64, qty: f64) -> bool {
@@ -210,13 +720,19 @@ impl AppState {
         qty_ok || notional_ok
     }
 
-    fn extract_big_levels(&self, depth: &DepthUpdate) -> (Vec<(f64, f64)>, Vec<(f64, f64)>) {
-        let extract = |levels: &[[String; 2]]| {
+    fn precision_for(&self, symbol: &str) -> SymbolPrecision {
+        self.config_map
+            .get(symbol)
+            .map(|cfg| cfg.precision)
+            .unwrap_or_default()
+    }
+
+    fn extract_big_levels(&self, depth: &DepthUpdate) -> (BigLevels, BigLevels) {
+        let extract = |levels: &[[Decimal; 2]]| {
             levels
                 .iter()
                 .filter_map(|level| {
-                    let price = level[0].parse::<f64>().ok()?;
-                    let qty = level[1].parse::<f64>().ok()?;
+                    let (price, qty) = (level[0].to_f64(), level[1].to_f64());
                     if !price.is_finite() || !qty.is_finite() || price <= 0.0 || qty <= 0.0 {
                         return None;
                     }
@@ -253,6 +769,7 @@ impl AppState {
         big_bids: &[(f64, f64)],
         big_asks: &[(f64, f64)],
         bid_pressure_pct: f64,
+        precision: &SymbolPrecision,
     ) -> String {
         let sell_pressure_pct = (100.0 - bid_pressure_pct).clamp(0.0, 100.0);
         let dominant_side = if bid_pressure_pct > sell_pressure_pct {
@@ -265,11 +782,23 @@ impl AppState {
 
         let top_bid = big_bids
             .first()
-            .map(|(price, qty)| format!("{:.2}x{:.3}", price, qty))
+            .map(|(price, qty)| {
+                format!(
+                    "{}x{}",
+                    precision.format_price(*price),
+                    precision.format_qty(*qty)
+                )
+            })
             .unwrap_or_else(|| "-".to_string());
         let top_ask = big_asks
             .first()
-            .map(|(price, qty)| format!("{:.2}x{:.3}", price, qty))
+            .map(|(price, qty)| {
+                format!(
+                    "{}x{}",
+                    precision.format_price(*price),
+                    precision.format_qty(*qty)
+                )
+            })
             .unwrap_or_else(|| "-".to_string());
 
         let pressure_bar = format_pressure_visual(bid_pressure_pct, 12);
@@ -289,39 +818,41 @@ impl AppState {
             top_ask
         )
     }
-    pub async fn process_kline_event(&self, event: &KlineEvent, tx: &broadcast::Sender<String>) {
+    pub async fn process_kline_event(
+        &mut self,
+        event: &KlineEvent,
+        tx: &broadcast::Sender<String>,
+    ) {
         let symbol = event.symbol.to_lowercase();
-        if !self.config_map.contains_key(&symbol) {
+        let Some(precision) = self.config_map.get(&symbol).map(|cfg| cfg.precision) else {
             return;
-        }
+        };
 
         if let Some(signal) = build_quant_signal_from_kline(event) {
-            let direction = if signal.return_pct > 0.0 {
-                "BULLISH"
-            } else if signal.return_pct < 0.0 {
-                "BEARISH"
-            } else {
-                "FLAT"
-            };
-
-            let msg = format!(
-                "[QUANT4H] {} {} | window={}..{} | O:{:.2} C:{:.2} H:{:.2} L:{:.2} ret={:+.2}% range={:.2}% taker_buy={:.1}% qvol={:.0} trades={}",
-                signal.symbol.to_uppercase(),
-                direction,
-                signal.interval_start_ms,
-                signal.interval_end_ms,
-                signal.open,
-                signal.close,
-                signal.high,
-                signal.low,
-                signal.return_pct,
-                signal.range_pct,
-                signal.taker_buy_ratio_pct,
-                signal.quote_volume,
-                signal.trade_count
-            );
-            println!("{}", msg);
+            if let Some(scorer) = self.conviction.as_mut() {
+                emit_conviction(
+                    scorer.observe_quant(&symbol, signal.interval_end_ms, signal.return_pct),
+                    tx,
+                );
+            }
+            let msg = format_quant_log(&signal, &precision);
+            logging::signal("quant4h", &msg);
             let _ = tx.send(msg);
+            self.track_signal(
+                TrackedSignal {
+                    signal_type: "quant4h",
+                    symbol: symbol.clone(),
+                    direction: if signal.return_pct > 0.0 {
+                        1
+                    } else if signal.return_pct < 0.0 {
+                        -1
+                    } else {
+                        0
+                    },
+                    timestamp_ms: signal.interval_end_ms,
+                },
+                tx,
+            );
 
             self.send_enriched_payload(
                 tx,
@@ -337,9 +868,22 @@ impl AppState {
                 }),
             )
             .await;
+
+            self.observe_rules(
+                &symbol,
+                signal.interval_end_ms,
+                &[
+                    ("quant_return_pct", signal.return_pct),
+                    ("quant_range_pct", signal.range_pct),
+                    ("taker_buy_pct", signal.taker_buy_ratio_pct),
+                ],
+                tx,
+            )
+            .await;
         }
     }
 
+    /// Returns +1/-1 when a breakout started on this update, otherwise 0.
     fn detect_big_move(
         &mut self,
         symbol: &str,
@@ -347,45 +891,58 @@ impl AppState {
         total_notional: f64,
         depth: &DepthUpdate,
         tx: &broadcast::Sender<String>,
-    ) {
-        if let Some(detector) = self.big_move_detectors.get_mut(symbol) {
-            let snap = DepthSnapshot {
-                bid_pressure_pct,
-                total_notional,
-            };
+    ) -> f64 {
+        let Some(detectors) = self.big_move_detectors.get_mut(symbol) else {
+            return 0.0;
+        };
+        let scale = self
+            .volatility
+            .as_ref()
+            .map_or(1.0, |v| v.threshold_scale(symbol));
+        let snap = DepthSnapshot {
+            bid_pressure_pct,
+            total_notional,
+            timestamp_ms: depth.event_time,
+        };
 
-            let evaluation = detector.push_with_self_explanation(snap);
-            println!("{}", evaluation.self_explanation_log);
-
-            match evaluation.signal {
-                BigMoveSignal::BullishBreakout {
-                    avg_pressure,
-                    total_notional,
-                } => {
-                    let alert = format!(
-                        "[BIGMOVE] {} BULLISH BREAKOUT likely! avg_pressure={:.1}% notional={:.0}",
-                        depth.symbol.to_uppercase(),
-                        avg_pressure,
-                        total_notional
-                    );
-                    println!("{}", alert);
-                    let _ = tx.send(alert);
-                }
-                BigMoveSignal::BearishBreakout {
-                    avg_pressure,
-                    total_notional,
-                } => {
-                    let alert = format!(
-                        "[BIGMOVE] {} BEARISH BREAKOUT likely! avg_pressure={:.1}% notional={:.0}",
-                        depth.symbol.to_uppercase(),
-                        avg_pressure,
-                        total_notional
-                    );
-                    println!("{}", alert);
-                    let _ = tx.send(alert);
+        let mut started = None;
+        for detector in detectors.iter_mut() {
+            detector.set_pressure_scale(scale);
+            let evaluation = detector.push_with_self_explanation(snap.clone());
+            log::debug!(target: "big_move", "{}", evaluation.self_explanation_log);
+
+            if let Some(alert) =
+                format_big_move_alert(&depth.symbol, detector.profile(), &evaluation.signal)
+            {
+                if evaluation.signal.is_start() {
+                    started = evaluation.signal.direction();
                 }
-                BigMoveSignal::None => {}
+                logging::signal("big_move", &alert);
+                let _ = tx.send(alert);
             }
         }
+
+        if let Some(direction) = started {
+            self.track_signal(
+                TrackedSignal {
+                    signal_type: "big_move",
+                    symbol: symbol.to_string(),
+                    direction: direction.sign(),
+                    timestamp_ms: depth.event_time,
+                },
+                tx,
+            );
+            self.emit_decomposition(
+                &MoveEvent {
+                    symbol: symbol.to_string(),
+                    source: "big_move",
+                    start_ms: depth.event_time,
+                    end_ms: depth.event_time,
+                    move_pct: None,
+                },
+                tx,
+            );
+        }
+        started.map_or(0.0, |direction| direction.sign() as f64)
     }
 }
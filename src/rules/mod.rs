@@ -0,0 +1,706 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use serde_json::{Map, Value, json};
+use tokio::sync::broadcast;
+
+use crate::config::RuleConfig;
+use crate::logging;
+use crate::news::correlation::CorrelationService;
+use crate::notify::{NotificationFanout, build_signal_notification};
+
+use self::parser::{CompareOp, Expr, METRICS, Metric, metric_index, parse_expression};
+
+pub mod parser;
+
+/// One named rule from `ALERT_RULES` or the rule file.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    /// Expression text as written, shown in alerts.
+    pub expression: String,
+    expr: Expr,
+    /// Metrics the expression reads, in first-use order.
+    metrics: Vec<Metric>,
+}
+
+impl Rule {
+    fn reads(&self, metric: Metric) -> bool {
+        self.metrics.contains(&metric)
+    }
+}
+
+/// A validated set of rules, replaced as a whole on reload.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+    /// Longest `within` window per metric; 0 keeps only the latest value.
+    retention_ms: [u64; METRICS.len()],
+}
+
+impl RuleSet {
+    /// Parses one `name: expression` rule per line (`#` outside quotes starts a comment).
+    /// Every invalid line is reported as `origin:line:col: message` with the source line and
+    /// a caret.
+    pub fn parse(source: &str, origin: &str) -> Result<Self, Vec<String>> {
+        let mut set = RuleSet::default();
+        set.extend(source.lines(), origin)?;
+        Ok(set)
+    }
+
+    fn extend<'a>(
+        &mut self,
+        lines: impl Iterator<Item = &'a str>,
+        origin: &str,
+    ) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        for (index, line) in lines.enumerate() {
+            let location = format!("{}:{}", origin, index + 1);
+            let content = strip_comment(line);
+            if content.trim().is_empty() {
+                continue;
+            }
+            match self.parse_line(content) {
+                Ok(rule) => {
+                    for (metric, window_ms) in rule_windows(&rule.expr) {
+                        self.retention_ms[metric] = self.retention_ms[metric].max(window_ms);
+                    }
+                    self.rules.push(rule);
+                }
+                Err((column, message)) => errors.push(format!(
+                    "{location}:{column}: {message}\n  {}\n  {}^",
+                    content.trim_end(),
+                    " ".repeat(column.saturating_sub(1))
+                )),
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> Result<Rule, (usize, String)> {
+        let indent = line.len() - line.trim_start().len();
+        let Some((name, expression)) = line.split_once(':') else {
+            return Err((indent + 1, "expected 'name: expression'".to_string()));
+        };
+        let name = name.trim();
+        let valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'));
+        if !valid_name {
+            return Err((
+                indent + 1,
+                format!("invalid rule name '{name}' (use letters, digits, '_' or '-')"),
+            ));
+        }
+        if self.rules.iter().any(|rule| rule.name == name) {
+            return Err((indent + 1, format!("duplicate rule name '{name}'")));
+        }
+        // Columns from the expression parser are relative to the text after the colon.
+        let offset = line[..line.len() - expression.len()].chars().count();
+        let expr =
+            parse_expression(expression).map_err(|err| (offset + err.column, err.message))?;
+        let mut metrics = Vec::new();
+        expr.for_each_compare(&mut |metric, _| {
+            if !metrics.contains(&metric) {
+                metrics.push(metric);
+            }
+        });
+        Ok(Rule {
+            name: name.to_string(),
+            expression: expression.trim().to_string(),
+            expr,
+            metrics,
+        })
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+/// Cuts a `#` comment, ignoring `#` inside quoted strings.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '#') => return &line[..index],
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            _ => {}
+        }
+    }
+    line
+}
+
+fn rule_windows(expr: &Expr) -> Vec<(Metric, u64)> {
+    let mut windows = Vec::new();
+    expr.for_each_compare(&mut |metric, window_ms| {
+        if let Some(window_ms) = window_ms {
+            windows.push((metric, window_ms));
+        }
+    });
+    windows
+}
+
+/// Inline `ALERT_RULES` (entries separated by `;`) followed by the rule file, if any.
+/// Returns the valid rules together with an error for every invalid one.
+pub fn load_rules(config: &RuleConfig) -> (RuleSet, Vec<String>) {
+    let mut set = RuleSet::default();
+    let mut errors = Vec::new();
+    if let Some(inline) = config.inline.as_deref()
+        && let Err(mut errs) = set.extend(inline.split(';'), "ALERT_RULES")
+    {
+        errors.append(&mut errs);
+    }
+    if let Some(path) = config.path.as_deref() {
+        match std::fs::read_to_string(path) {
+            Ok(source) => {
+                if let Err(mut errs) = set.extend(source.lines(), path) {
+                    errors.append(&mut errs);
+                }
+            }
+            Err(err) => errors.push(format!("{path}: {err}")),
+        }
+    }
+    (set, errors)
+}
+
+/// Rule set shared by every rule engine and swapped by the reload task.
+#[derive(Debug, Clone, Default)]
+pub struct SharedRules {
+    inner: Arc<RwLock<Arc<RuleSet>>>,
+}
+
+impl SharedRules {
+    pub fn new(rules: RuleSet) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Arc::new(rules))),
+        }
+    }
+
+    pub fn snapshot(&self) -> Arc<RuleSet> {
+        self.inner.read().expect("alert rules poisoned").clone()
+    }
+
+    pub fn replace(&self, rules: RuleSet) {
+        *self.inner.write().expect("alert rules poisoned") = Arc::new(rules);
+    }
+}
+
+/// Loads the configured rules. Invalid rules are logged as errors and skipped; the valid
+/// ones are active from the start.
+pub fn init_rules(config: &RuleConfig) -> SharedRules {
+    let (rules, errors) = load_rules(config);
+    for error in &errors {
+        log::error!(target: "rules", "{error}");
+    }
+    if errors.is_empty() {
+        log::info!(target: "rules", "loaded {} alert rules", rules.len());
+    } else {
+        log::error!(
+            target: "rules",
+            "{} invalid rules skipped, {} alert rules active",
+            errors.len(),
+            rules.len()
+        );
+    }
+    SharedRules::new(rules)
+}
+
+/// Re-reads the rule file whenever its modification time changes. An invalid file is
+/// reported and the previous rules stay active.
+pub fn spawn_rule_reload(config: RuleConfig, rules: SharedRules) {
+    let Some(path) = config.path.clone() else {
+        return;
+    };
+    if config.reload_secs == 0 {
+        return;
+    }
+    let modified = |path: &str| -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    };
+    tokio::spawn(async move {
+        let mut last_modified = modified(&path);
+        let mut ticker = tokio::time::interval(Duration::from_secs(config.reload_secs));
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let current = modified(&path);
+            if current == last_modified {
+                continue;
+            }
+            last_modified = current;
+            match load_rules(&config) {
+                (next, errors) if errors.is_empty() => {
+                    log::info!(
+                        target: "rules",
+                        "reloaded {} alert rules from {}",
+                        next.len(),
+                        path,
+                    );
+                    rules.replace(next);
+                }
+                (_, errors) => {
+                    for error in &errors {
+                        log::warn!(target: "rules", "{error}");
+                    }
+                    log::warn!(
+                        target: "rules",
+                        "reload of {} failed, keeping {} previous rules",
+                        path,
+                        rules.snapshot().len()
+                    );
+                }
+            }
+        }
+    });
+}
+
+/// Per-second min/max of one metric.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    second: u64,
+    min: f64,
+    max: f64,
+}
+
+#[derive(Debug, Default)]
+struct MetricSeries {
+    last: Option<f64>,
+    buckets: VecDeque<Bucket>,
+}
+
+impl MetricSeries {
+    fn record(&mut self, timestamp_ms: u64, value: f64, retention_ms: u64) {
+        self.last = Some(value);
+        if retention_ms == 0 {
+            self.buckets.clear();
+            return;
+        }
+        let second = timestamp_ms / 1_000;
+        match self.buckets.back_mut() {
+            Some(bucket) if bucket.second >= second => {
+                bucket.min = bucket.min.min(value);
+                bucket.max = bucket.max.max(value);
+            }
+            _ => self.buckets.push_back(Bucket {
+                second,
+                min: value,
+                max: value,
+            }),
+        }
+        let oldest = timestamp_ms.saturating_sub(retention_ms) / 1_000;
+        while self.buckets.front().is_some_and(|b| b.second < oldest) {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// `>`/`>=` test the window maximum, `<`/`<=` the minimum and `==`/`!=` either.
+    fn matches(&self, op: CompareOp, value: f64, window_ms: Option<u64>, now_ms: u64) -> bool {
+        let Some(window_ms) = window_ms else {
+            return self.last.is_some_and(|last| op.apply(last, value));
+        };
+        let oldest = now_ms.saturating_sub(window_ms) / 1_000;
+        self.buckets
+            .iter()
+            .rev()
+            .take_while(|b| b.second >= oldest)
+            .any(|b| match op {
+                CompareOp::Gt | CompareOp::Ge => op.apply(b.max, value),
+                CompareOp::Lt | CompareOp::Le => op.apply(b.min, value),
+                CompareOp::Eq | CompareOp::Ne => op.apply(b.min, value) || op.apply(b.max, value),
+            })
+    }
+}
+
+#[derive(Debug, Default)]
+struct SymbolRuleState {
+    series: HashMap<Metric, MetricSeries>,
+    /// Rules whose condition held at the last evaluation.
+    active: HashMap<String, bool>,
+    last_fired_ms: HashMap<String, u64>,
+}
+
+impl SymbolRuleState {
+    fn eval(&self, expr: &Expr, symbol: &str, now_ms: u64) -> bool {
+        match expr {
+            Expr::And(items) => items.iter().all(|e| self.eval(e, symbol, now_ms)),
+            Expr::Or(items) => items.iter().any(|e| self.eval(e, symbol, now_ms)),
+            Expr::Not(inner) => !self.eval(inner, symbol, now_ms),
+            Expr::Symbol { symbols, negate } => symbols.iter().any(|s| s == symbol) != *negate,
+            Expr::Compare {
+                metric,
+                op,
+                value,
+                window_ms,
+            } => self
+                .series
+                .get(metric)
+                .is_some_and(|series| series.matches(*op, *value, *window_ms, now_ms)),
+        }
+    }
+}
+
+/// A rule whose condition became true for a symbol.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleMatch {
+    pub rule: String,
+    pub expression: String,
+    pub symbol: String,
+    pub timestamp_ms: u64,
+    /// Latest value of every metric the rule reads.
+    pub values: Vec<(&'static str, f64)>,
+}
+
+impl RuleMatch {
+    fn metrics_json(&self) -> Map<String, Value> {
+        self.values
+            .iter()
+            .map(|(name, value)| (name.to_string(), json!(value)))
+            .collect()
+    }
+
+    pub fn to_event_json(&self) -> String {
+        json!({
+            "event": "rule_match",
+            "rule": self.rule,
+            "expression": self.expression,
+            "symbol": self.symbol.to_uppercase(),
+            "timestamp_ms": self.timestamp_ms,
+            "values": self.metrics_json(),
+        })
+        .to_string()
+    }
+}
+
+/// Evaluates the shared rule set against per-symbol metric readings.
+#[derive(Debug)]
+pub struct RuleEngine {
+    rules: SharedRules,
+    current: Arc<RuleSet>,
+    cooldown_ms: u64,
+    symbols: HashMap<String, SymbolRuleState>,
+}
+
+impl RuleEngine {
+    pub fn new(rules: SharedRules, config: &RuleConfig) -> Self {
+        Self {
+            current: rules.snapshot(),
+            rules,
+            cooldown_ms: config.cooldown_secs.saturating_mul(1_000),
+            symbols: HashMap::new(),
+        }
+    }
+
+    pub fn rules(&self) -> &SharedRules {
+        &self.rules
+    }
+
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        self.symbols.remove(&symbol.to_lowercase());
+    }
+
+    /// Records `(metric, value)` readings for a symbol and returns the rules that read
+    /// one of them and just turned true. A rule fires again only after it was false and
+    /// the cooldown has passed.
+    pub fn observe(
+        &mut self,
+        symbol: &str,
+        timestamp_ms: u64,
+        readings: &[(&str, f64)],
+    ) -> Vec<RuleMatch> {
+        let latest = self.rules.snapshot();
+        if !Arc::ptr_eq(&latest, &self.current) {
+            for state in self.symbols.values_mut() {
+                state
+                    .active
+                    .retain(|name, _| latest.rules.iter().any(|r| &r.name == name));
+            }
+            self.current = latest;
+        }
+        let rules = self.current.clone();
+        if rules.is_empty() {
+            return Vec::new();
+        }
+
+        let symbol = symbol.to_lowercase();
+        let state = self.symbols.entry(symbol.clone()).or_default();
+        let mut updated = Vec::with_capacity(readings.len());
+        for (name, value) in readings {
+            let Some(metric) = metric_index(name) else {
+                debug_assert!(false, "unknown rule metric {name}");
+                continue;
+            };
+            if !value.is_finite() {
+                continue;
+            }
+            state.series.entry(metric).or_default().record(
+                timestamp_ms,
+                *value,
+                rules.retention_ms[metric],
+            );
+            updated.push(metric);
+        }
+
+        let mut matches = Vec::new();
+        for rule in rules.rules.iter() {
+            if !updated.iter().any(|metric| rule.reads(*metric)) {
+                continue;
+            }
+            let holds = state.eval(&rule.expr, &symbol, timestamp_ms);
+            let was_active = state.active.insert(rule.name.clone(), holds) == Some(true);
+            if !holds || was_active {
+                continue;
+            }
+            let cooling = state
+                .last_fired_ms
+                .get(&rule.name)
+                .is_some_and(|last| timestamp_ms.saturating_sub(*last) < self.cooldown_ms);
+            if cooling {
+                continue;
+            }
+            state.last_fired_ms.insert(rule.name.clone(), timestamp_ms);
+            matches.push(RuleMatch {
+                rule: rule.name.clone(),
+                expression: rule.expression.clone(),
+                symbol: symbol.clone(),
+                timestamp_ms,
+                values: rule
+                    .metrics
+                    .iter()
+                    .filter_map(|metric| {
+                        let last = state.series.get(metric)?.last?;
+                        Some((METRICS[*metric].0, last))
+                    })
+                    .collect(),
+            });
+        }
+        matches
+    }
+}
+
+pub fn format_rule_log(rule_match: &RuleMatch) -> String {
+    let values: Vec<String> = rule_match
+        .values
+        .iter()
+        .map(|(name, value)| format!("{name}={value:.4}"))
+        .collect();
+    format!(
+        "[RULE] {} {} matched: {} | {}",
+        rule_match.rule,
+        rule_match.symbol.to_uppercase(),
+        values.join(" "),
+        rule_match.expression
+    )
+}
+
+/// Logs and broadcasts each match, then sends it through the notifier as a
+/// `rule:<name>` signal with any correlated headlines.
+pub async fn dispatch_rule_matches(
+    matches: Vec<RuleMatch>,
+    tx: &broadcast::Sender<String>,
+    correlation_service: Option<&CorrelationService>,
+    notifier: &NotificationFanout,
+) {
+    for rule_match in matches {
+        let msg = format_rule_log(&rule_match);
+        logging::signal(&format!("rule:{}", rule_match.rule), &msg);
+        let _ = tx.send(msg);
+        let _ = tx.send(rule_match.to_event_json());
+
+        let event_ts = rule_match.timestamp_ms as i64;
+        let correlation = match correlation_service {
+            Some(service) => service.correlate(&rule_match.symbol, event_ts).await.ok(),
+            None => None,
+        };
+        let (news, score) = correlation
+            .map(|c| (c.matches, c.score))
+            .unwrap_or_default();
+        let mut move_metrics = rule_match.metrics_json();
+        move_metrics.insert("rule".to_string(), json!(rule_match.rule));
+        move_metrics.insert("rule_expression".to_string(), json!(rule_match.expression));
+        let mut notification = build_signal_notification(
+            &format!("rule:{}", rule_match.rule),
+            &rule_match.symbol,
+            event_ts,
+            Value::Object(move_metrics),
+            &news,
+            score,
+        );
+        // The user asked for this alert explicitly, so it is not held back by
+        // TELEGRAM_MIN_CORRELATION_SCORE; the message still shows the real score.
+        notification.correlation_score = 1.0;
+        notifier.dispatch(tx, notification).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine(source: &str) -> RuleEngine {
+        let rules = RuleSet::parse(source, "rules.txt").expect("valid rules");
+        RuleEngine::new(
+            SharedRules::new(rules),
+            &RuleConfig {
+                cooldown_secs: 600,
+                ..RuleConfig::default()
+            },
+        )
+    }
+
+    #[test]
+    fn reports_parse_errors_with_location_and_caret() {
+        let errors = RuleSet::parse(
+            "ok: spike_pct > 1\nbad: symbol in [btcusdt] and spkie_pct > 1\n# comment\nok: price > 1\nworse: funding_rate_pct > 0.1 within 2d",
+            "rules.txt",
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].starts_with(
+            "rules.txt:2:30: unknown field 'spkie_pct'; known fields: symbol, price,"
+        ));
+        assert!(errors[0].ends_with(&format!(
+            "\n  bad: symbol in [btcusdt] and spkie_pct > 1\n  {}^",
+            " ".repeat(29)
+        )));
+        assert_eq!(
+            errors[1].lines().next(),
+            Some("rules.txt:4:1: duplicate rule name 'ok'")
+        );
+        assert!(errors[2].starts_with(
+            "rules.txt:5:38: expected a duration such as 30s, 5m or 1h after 'within', found '2d'"
+        ));
+
+        let err = parse_expression("funding_rate_pct in [btcusdt]").unwrap_err();
+        assert_eq!(
+            (err.column, err.message.as_str()),
+            (18, "only 'symbol' supports 'in'")
+        );
+        assert!(parse_expression("(spike_pct > 1 or price < 2").is_err());
+        assert!(parse_expression("not symbol in [1000pepeusdt] and cvd >= -5").is_ok());
+    }
+
+    #[test]
+    fn error_columns_count_from_line_start_after_name_prefix() {
+        let errors = RuleSet::parse(
+            "  indented: cvd > 0 within 2h\nx: symbol == \"ßtc\" and bad > 1",
+            "rules.txt",
+        )
+        .unwrap_err();
+        assert_eq!(
+            errors[0].lines().next(),
+            Some("rules.txt:1:28: window must be between 1s and 1h")
+        );
+        assert!(errors[0].ends_with(&format!("\n  {}^", " ".repeat(27))));
+        // Columns count characters, not bytes.
+        assert!(errors[1].starts_with("rules.txt:2:24: unknown field 'bad'"));
+    }
+
+    #[test]
+    fn comments_do_not_cut_quoted_symbols() {
+        let rules = RuleSet::parse(
+            "# header\nhash: symbol in [\"odd#usdt\", 'b#'] # trailing\nplain: cvd > 0 #note",
+            "rules.txt",
+        )
+        .expect("valid rules");
+        assert_eq!(rules.len(), 2);
+        assert_eq!(
+            rules.rules()[0].expression,
+            "symbol in [\"odd#usdt\", 'b#']"
+        );
+        assert_eq!(rules.rules()[1].expression, "cvd > 0");
+    }
+
+    #[test]
+    fn startup_keeps_valid_rules_when_one_is_invalid() {
+        let config = RuleConfig {
+            inline: Some("good: spike_pct > 1; bad: spkie_pct > 1".to_string()),
+            ..RuleConfig::default()
+        };
+        let (rules, errors) = load_rules(&config);
+        assert_eq!(rules.len(), 1);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("ALERT_RULES:2:"));
+
+        let shared = init_rules(&config);
+        assert_eq!(shared.snapshot().rules()[0].name, "good");
+    }
+
+    #[test]
+    fn fires_on_rising_edge_with_window_and_cooldown() {
+        let mut engine = engine(
+            "spike_funding: symbol in [btcusdt, ethusdt] and spike_pct > 0.8 and funding_rate_pct > 0.05 within 5m",
+        );
+        assert!(
+            engine
+                .observe("BTCUSDT", 0, &[("funding_rate_pct", 0.06)])
+                .is_empty()
+        );
+        // Funding fell back, but the 0.06 reading is still inside the 5m window.
+        assert!(
+            engine
+                .observe("BTCUSDT", 60_000, &[("funding_rate_pct", 0.01)])
+                .is_empty()
+        );
+        assert!(
+            engine
+                .observe("SOLUSDT", 61_000, &[("spike_pct", 1.2)])
+                .is_empty()
+        );
+
+        let matches = engine.observe("BTCUSDT", 61_000, &[("spike_pct", 1.2)]);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            format_rule_log(&matches[0]),
+            "[RULE] spike_funding BTCUSDT matched: spike_pct=1.2000 funding_rate_pct=0.0100 | symbol in [btcusdt, ethusdt] and spike_pct > 0.8 and funding_rate_pct > 0.05 within 5m"
+        );
+        // Still true: no repeat until the condition resets.
+        assert!(
+            engine
+                .observe("BTCUSDT", 62_000, &[("spike_pct", 0.1)])
+                .is_empty()
+        );
+        // The funding reading left the window, so the rule resets; rising again inside the
+        // cooldown is suppressed.
+        assert!(
+            engine
+                .observe("BTCUSDT", 400_000, &[("funding_rate_pct", 0.0)])
+                .is_empty()
+        );
+        assert!(
+            engine
+                .observe("BTCUSDT", 401_000, &[("funding_rate_pct", 0.07)])
+                .is_empty()
+        );
+        assert!(
+            engine
+                .observe("BTCUSDT", 750_000, &[("spike_pct", 0.0)])
+                .is_empty()
+        );
+        assert_eq!(
+            engine
+                .observe(
+                    "BTCUSDT",
+                    751_000,
+                    &[("spike_pct", 0.9), ("funding_rate_pct", 0.07)]
+                )
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,654 @@
+use std::fmt;
+
+/// Longest `within` window a rule may use.
+pub const MAX_WINDOW_MS: u64 = 3_600_000;
+
+/// Per-symbol values a rule can test, with the event that updates each.
+pub const METRICS: [(&str, &str); 14] = [
+    ("price", "agg trade price"),
+    ("trade_qty", "agg trade quantity"),
+    ("trade_notional", "agg trade notional"),
+    ("spike_pct", "strongest spike-window move, signed"),
+    (
+        "flow_imbalance_pct",
+        "strongest taker-flow imbalance, signed",
+    ),
+    ("cvd", "cumulative volume delta"),
+    (
+        "bid_pressure_pct",
+        "big-level bid pressure of a depth update",
+    ),
+    ("depth_notional", "big-level notional of a depth update"),
+    ("big_move", "+1/-1 when a big-move breakout starts"),
+    ("quant_return_pct", "closed 4h kline return"),
+    ("quant_range_pct", "closed 4h kline range"),
+    ("taker_buy_pct", "closed 4h kline taker buy ratio"),
+    ("funding_rate_pct", "funding rate update"),
+    ("whale_notional", "whale cluster notional, signed by side"),
+];
+
+/// Index into `METRICS`.
+pub type Metric = usize;
+
+pub fn metric_index(name: &str) -> Option<Metric> {
+    METRICS.iter().position(|(metric, _)| *metric == name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+impl CompareOp {
+    pub fn apply(&self, left: f64, right: f64) -> bool {
+        match self {
+            CompareOp::Gt => left > right,
+            CompareOp::Ge => left >= right,
+            CompareOp::Lt => left < right,
+            CompareOp::Le => left <= right,
+            CompareOp::Eq => left == right,
+            CompareOp::Ne => left != right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    /// `symbol in [...]`, `symbol == x` and their negations.
+    Symbol {
+        symbols: Vec<String>,
+        negate: bool,
+    },
+    /// Compares the latest value, or any value seen in the last `window_ms`.
+    Compare {
+        metric: Metric,
+        op: CompareOp,
+        value: f64,
+        window_ms: Option<u64>,
+    },
+}
+
+impl Expr {
+    /// Calls `f` with the metric and window of every comparison in the expression.
+    pub fn for_each_compare(&self, f: &mut impl FnMut(Metric, Option<u64>)) {
+        match self {
+            Expr::And(items) | Expr::Or(items) => items.iter().for_each(|e| e.for_each_compare(f)),
+            Expr::Not(inner) => inner.for_each_compare(f),
+            Expr::Symbol { .. } => {}
+            Expr::Compare {
+                metric, window_ms, ..
+            } => f(*metric, *window_ms),
+        }
+    }
+
+    /// Gives comparisons without their own window the enclosing `within`.
+    fn apply_window(&mut self, window: u64) {
+        match self {
+            Expr::And(items) | Expr::Or(items) => {
+                items.iter_mut().for_each(|e| e.apply_window(window))
+            }
+            Expr::Not(inner) => inner.apply_window(window),
+            Expr::Symbol { .. } => {}
+            Expr::Compare { window_ms, .. } => {
+                window_ms.get_or_insert(window);
+            }
+        }
+    }
+}
+
+/// Parse failure with its position in the rule text (1-based column).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleParseError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "col {}: {}", self.column, self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(f64),
+    /// Number with an `s`, `m` or `h` suffix, in milliseconds.
+    Duration(u64),
+    Str(String),
+    Op(CompareOp),
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    Comma,
+    End,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Word(w) => format!("'{w}'"),
+            Token::Number(n) => format!("number {n}"),
+            Token::Duration(ms) => format!("duration {}s", ms / 1_000),
+            Token::Str(s) => format!("\"{s}\""),
+            Token::Op(_) => "comparison operator".to_string(),
+            Token::LBracket => "'['".to_string(),
+            Token::RBracket => "']'".to_string(),
+            Token::LParen => "'('".to_string(),
+            Token::RParen => "')'".to_string(),
+            Token::Comma => "','".to_string(),
+            Token::End => "end of rule".to_string(),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, RuleParseError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let error = |column: usize, message: String| RuleParseError { column, message };
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let single = match c {
+            '[' => Some(Token::LBracket),
+            ']' => Some(Token::RBracket),
+            '(' => Some(Token::LParen),
+            ')' => Some(Token::RParen),
+            ',' => Some(Token::Comma),
+            _ => None,
+        };
+        if let Some(token) = single {
+            tokens.push((token, column));
+            i += 1;
+            continue;
+        }
+        if matches!(c, '>' | '<' | '=' | '!') {
+            let next_eq = chars.get(i + 1) == Some(&'=');
+            let op = match (c, next_eq) {
+                ('>', true) => CompareOp::Ge,
+                ('>', false) => CompareOp::Gt,
+                ('<', true) => CompareOp::Le,
+                ('<', false) => CompareOp::Lt,
+                ('=', _) => CompareOp::Eq,
+                ('!', true) => CompareOp::Ne,
+                _ => return Err(error(column, "expected '!='".to_string())),
+            };
+            tokens.push((Token::Op(op), column));
+            i += if next_eq { 2 } else { 1 };
+            continue;
+        }
+        if c == '"' || c == '\'' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|ch| *ch == c)
+                .ok_or_else(|| error(column, "unterminated string".to_string()))?;
+            tokens.push((
+                Token::Str(chars[i + 1..i + 1 + end].iter().collect()),
+                column,
+            ));
+            i += end + 2;
+            continue;
+        }
+        let numeric = c.is_ascii_digit()
+            || ((c == '-' || c == '.') && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit()));
+        if numeric {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let number: f64 = text
+                .parse()
+                .map_err(|_| error(column, format!("invalid number '{text}'")))?;
+            let unit_start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let unit: String = chars[unit_start..i].iter().collect();
+            let token = match unit.as_str() {
+                "" => Token::Number(number),
+                "s" => Token::Duration((number * 1_000.0) as u64),
+                "m" => Token::Duration((number * 60_000.0) as u64),
+                "h" => Token::Duration((number * 3_600_000.0) as u64),
+                // Symbols such as 1000pepeusdt start with digits.
+                _ => Token::Word(chars[start..i].iter().collect::<String>().to_lowercase()),
+            };
+            tokens.push((token, column));
+            continue;
+        }
+        if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '-')) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push((Token::Word(word.to_lowercase()), column));
+            continue;
+        }
+        return Err(error(column, format!("unexpected character '{c}'")));
+    }
+    tokens.push((Token::End, chars.len() + 1));
+    Ok(tokens)
+}
+
+const KEYWORDS: [&str; 5] = ["and", "or", "not", "in", "within"];
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn column(&self) -> usize {
+        self.tokens[self.pos].1
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, RuleParseError> {
+        Err(RuleParseError {
+            column: self.column(),
+            message,
+        })
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Token::Word(w) if w == keyword) {
+            self.next();
+            return true;
+        }
+        false
+    }
+
+    fn expression(&mut self) -> Result<Expr, RuleParseError> {
+        let mut items = vec![self.conjunction()?];
+        while self.keyword("or") {
+            items.push(self.conjunction()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Expr::Or(items)
+        })
+    }
+
+    fn conjunction(&mut self) -> Result<Expr, RuleParseError> {
+        let mut items = vec![self.unary()?];
+        while self.keyword("and") {
+            items.push(self.unary()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Expr::And(items)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Expr, RuleParseError> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if !matches!(self.peek(), Token::LParen) {
+            return self.condition();
+        }
+        self.next();
+        let mut expr = self.expression()?;
+        if !matches!(self.peek(), Token::RParen) {
+            return self.error(format!("expected ')', found {}", self.peek().describe()));
+        }
+        self.next();
+        self.window(&mut expr)?;
+        Ok(expr)
+    }
+
+    /// Optional `within DURATION` applying to every comparison of `expr` without one.
+    fn window(&mut self, expr: &mut Expr) -> Result<(), RuleParseError> {
+        if self.keyword("within") {
+            let window = self.duration()?;
+            expr.apply_window(window);
+        }
+        Ok(())
+    }
+
+    fn duration(&mut self) -> Result<u64, RuleParseError> {
+        match self.peek().clone() {
+            Token::Duration(ms) if (1_000..=MAX_WINDOW_MS).contains(&ms) => {
+                self.next();
+                Ok(ms)
+            }
+            Token::Duration(_) => self.error("window must be between 1s and 1h".to_string()),
+            other => self.error(format!(
+                "expected a duration such as 30s, 5m or 1h after 'within', found {}",
+                other.describe()
+            )),
+        }
+    }
+
+    fn condition(&mut self) -> Result<Expr, RuleParseError> {
+        let field = match self.peek().clone() {
+            Token::Word(w) if !KEYWORDS.contains(&w.as_str()) => w,
+            other => {
+                return self.error(format!(
+                    "expected a field such as symbol or spike_pct, found {}",
+                    other.describe()
+                ));
+            }
+        };
+        let field_column = self.column();
+        self.next();
+
+        if field == "symbol" {
+            return self.symbol_condition();
+        }
+        let Some(metric) = metric_index(&field) else {
+            let known: Vec<&str> = METRICS.iter().map(|(name, _)| *name).collect();
+            return Err(RuleParseError {
+                column: field_column,
+                message: format!(
+                    "unknown field '{field}'; known fields: symbol, {}",
+                    known.join(", ")
+                ),
+            });
+        };
+        let op = match self.peek() {
+            Token::Op(op) => *op,
+            Token::Word(w) if w == "in" || w == "not" => {
+                return self.error("only 'symbol' supports 'in'".to_string());
+            }
+            other => {
+                return self.error(format!(
+                    "expected a comparison operator after '{field}', found {}",
+                    other.describe()
+                ));
+            }
+        };
+        self.next();
+        let value = match self.peek() {
+            Token::Number(n) => *n,
+            other => {
+                return self.error(format!(
+                    "expected a number after the operator, found {}",
+                    other.describe()
+                ));
+            }
+        };
+        self.next();
+        Ok(Expr::Compare {
+            metric,
+            op,
+            value,
+            window_ms: None,
+        })
+    }
+
+    fn symbol_condition(&mut self) -> Result<Expr, RuleParseError> {
+        match self.peek().clone() {
+            Token::Op(op @ (CompareOp::Eq | CompareOp::Ne)) => {
+                self.next();
+                let symbol = self.symbol_value()?;
+                Ok(Expr::Symbol {
+                    symbols: vec![symbol],
+                    negate: op == CompareOp::Ne,
+                })
+            }
+            Token::Word(w) if w == "in" || w == "not" => {
+                let negate = self.keyword("not");
+                if !self.keyword("in") {
+                    return self.error(format!("expected 'in', found {}", self.peek().describe()));
+                }
+                if !matches!(self.peek(), Token::LBracket) {
+                    return self.error(format!(
+                        "expected '[' to start the symbol list, found {}",
+                        self.peek().describe()
+                    ));
+                }
+                self.next();
+                let mut symbols = vec![self.symbol_value()?];
+                loop {
+                    match self.next() {
+                        Token::Comma => symbols.push(self.symbol_value()?),
+                        Token::RBracket => break,
+                        other => {
+                            self.pos -= 1;
+                            return self.error(format!(
+                                "expected ',' or ']' in the symbol list, found {}",
+                                other.describe()
+                            ));
+                        }
+                    }
+                }
+                Ok(Expr::Symbol { symbols, negate })
+            }
+            other => self.error(format!(
+                "expected '==', '!=' or 'in' after 'symbol', found {}",
+                other.describe()
+            )),
+        }
+    }
+
+    fn symbol_value(&mut self) -> Result<String, RuleParseError> {
+        match self.peek().clone() {
+            Token::Word(w) | Token::Str(w) if !w.is_empty() => {
+                self.next();
+                Ok(w.to_lowercase())
+            }
+            other => self.error(format!("expected a symbol, found {}", other.describe())),
+        }
+    }
+}
+
+/// Parses one rule expression, e.g.
+/// `symbol in [btcusdt, ethusdt] and spike_pct > 0.8 and funding_rate_pct > 0.05 within 5m`.
+pub fn parse_expression(source: &str) -> Result<Expr, RuleParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+    };
+    if matches!(parser.peek(), Token::End) {
+        return parser.error("empty rule".to_string());
+    }
+    let mut expr = parser.expression()?;
+    let windowed = matches!(parser.peek(), Token::Word(w) if w == "within");
+    parser.window(&mut expr)?;
+    if !matches!(parser.peek(), Token::End) {
+        let found = parser.peek().describe();
+        return if windowed {
+            parser.error(format!(
+                "expected end of rule after the window, found {found}; wrap part of a rule in parentheses to give it its own window"
+            ))
+        } else {
+            parser.error(format!(
+                "expected 'and', 'or' or end of rule, found {found}"
+            ))
+        };
+    }
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compare(field: &str, op: CompareOp, value: f64, window_ms: Option<u64>) -> Expr {
+        Expr::Compare {
+            metric: metric_index(field).expect("known field"),
+            op,
+            value,
+            window_ms,
+        }
+    }
+
+    fn error(source: &str) -> (usize, String) {
+        let err = parse_expression(source).unwrap_err();
+        (err.column, err.message)
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            parse_expression("spike_pct > 1 or price > 2 and cvd > 3").unwrap(),
+            Expr::Or(vec![
+                compare("spike_pct", CompareOp::Gt, 1.0, None),
+                Expr::And(vec![
+                    compare("price", CompareOp::Gt, 2.0, None),
+                    compare("cvd", CompareOp::Gt, 3.0, None),
+                ]),
+            ])
+        );
+        assert_eq!(
+            parse_expression("not price <= 2 and cvd != 0").unwrap(),
+            Expr::And(vec![
+                Expr::Not(Box::new(compare("price", CompareOp::Le, 2.0, None))),
+                compare("cvd", CompareOp::Ne, 0.0, None),
+            ])
+        );
+    }
+
+    #[test]
+    fn within_applies_to_its_group_and_keeps_inner_windows() {
+        assert_eq!(
+            parse_expression("(spike_pct > 1 and cvd > 0) within 5m or price = 2").unwrap(),
+            Expr::Or(vec![
+                Expr::And(vec![
+                    compare("spike_pct", CompareOp::Gt, 1.0, Some(300_000)),
+                    compare("cvd", CompareOp::Gt, 0.0, Some(300_000)),
+                ]),
+                compare("price", CompareOp::Eq, 2.0, None),
+            ])
+        );
+        assert_eq!(
+            parse_expression("(funding_rate_pct > 0.05) within 1h and spike_pct < -1 within 30s")
+                .unwrap(),
+            Expr::And(vec![
+                compare("funding_rate_pct", CompareOp::Gt, 0.05, Some(3_600_000)),
+                compare("spike_pct", CompareOp::Lt, -1.0, Some(30_000)),
+            ])
+        );
+        let (column, message) = error("spike_pct > 1 within 5m and cvd > 0");
+        assert_eq!(column, 25);
+        assert!(message.contains("wrap part of a rule in parentheses"));
+    }
+
+    #[test]
+    fn within_rejects_windows_outside_one_second_to_one_hour() {
+        for source in [
+            "cvd > 0 within 0s",
+            "cvd > 0 within 2h",
+            "cvd > 0 within 0.5s",
+        ] {
+            assert_eq!(
+                error(source),
+                (16, "window must be between 1s and 1h".to_string()),
+                "{source}"
+            );
+        }
+        assert!(parse_expression("cvd > 0 within 1h").is_ok());
+        assert!(parse_expression("cvd > 0 within 1s").is_ok());
+    }
+
+    #[test]
+    fn unknown_field_lists_every_known_field() {
+        let (column, message) = error("symbol == btcusdt and spkie_pct > 1");
+        assert_eq!(column, 23);
+        assert!(message.starts_with("unknown field 'spkie_pct'; known fields: symbol, price,"));
+        for (field, _) in METRICS {
+            assert!(message.contains(field), "{field} missing from: {message}");
+        }
+    }
+
+    #[test]
+    fn only_symbol_supports_in() {
+        assert_eq!(
+            error("spike_pct in [1, 2]"),
+            (11, "only 'symbol' supports 'in'".to_string())
+        );
+        assert_eq!(
+            error("spike_pct not in [1]"),
+            (11, "only 'symbol' supports 'in'".to_string())
+        );
+        assert_eq!(
+            error("symbol in [btcusdt ethusdt]").1,
+            "expected ',' or ']' in the symbol list, found 'ethusdt'"
+        );
+    }
+
+    #[test]
+    fn tokenizer_reports_bad_strings_and_operators() {
+        assert_eq!(
+            error("symbol == \"btcusdt"),
+            (11, "unterminated string".to_string())
+        );
+        assert_eq!(error("spike_pct ! 1"), (11, "expected '!='".to_string()));
+        assert_eq!(
+            error("spike_pct > 1 & cvd > 0"),
+            (15, "unexpected character '&'".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_negative_numbers_and_digit_leading_symbols() {
+        assert_eq!(
+            parse_expression("cvd >= -5 and spike_pct < -0.5").unwrap(),
+            Expr::And(vec![
+                compare("cvd", CompareOp::Ge, -5.0, None),
+                compare("spike_pct", CompareOp::Lt, -0.5, None),
+            ])
+        );
+        assert_eq!(
+            error("cvd > - 5"),
+            (7, "unexpected character '-'".to_string())
+        );
+        assert_eq!(
+            parse_expression("symbol in [1000PEPEUSDT, '1000shibusdt'] or symbol != 1inchusdt")
+                .unwrap(),
+            Expr::Or(vec![
+                Expr::Symbol {
+                    symbols: vec!["1000pepeusdt".to_string(), "1000shibusdt".to_string()],
+                    negate: false,
+                },
+                Expr::Symbol {
+                    symbols: vec!["1inchusdt".to_string()],
+                    negate: true,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn empty_rule_is_an_error() {
+        assert_eq!(error(""), (1, "empty rule".to_string()));
+        assert_eq!(error("   "), (4, "empty rule".to_string()));
+        assert_eq!(
+            error("()"),
+            (
+                2,
+                "expected a field such as symbol or spike_pct, found ')'".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Notify;
+
+use crate::binance::AggTrade;
+use crate::binance_depth::DepthUpdate;
+use crate::binance_funding::FundingRateUpdate;
+use crate::binance_kline::KlineEvent;
+use crate::notify::queue::OverflowPolicy;
+
+/// A parsed per-symbol market data message routed to a shard worker.
+#[derive(Debug)]
+pub enum MarketMessage {
+    AggTrade(AggTrade),
+    Depth(DepthUpdate),
+    Kline(Box<KlineEvent>),
+    Funding(FundingRateUpdate),
+}
+
+impl MarketMessage {
+    pub fn symbol(&self) -> &str {
+        match self {
+            Self::AggTrade(agg) => &agg.s,
+            Self::Depth(depth) => &depth.symbol,
+            Self::Kline(kline) => &kline.symbol,
+            Self::Funding(funding) => &funding.symbol,
+        }
+    }
+
+    /// Stream name attached to log records while the message is processed.
+    pub fn stream(&self) -> &'static str {
+        match self {
+            Self::AggTrade(_) => "agg_trade",
+            Self::Depth(_) => "depth",
+            Self::Kline(_) => "kline",
+            Self::Funding(_) => "funding",
+        }
+    }
+}
+
+/// Stable shard for a symbol (FNV-1a over the lowercase name), so a symbol always lands
+/// on the same worker across restarts.
+pub fn shard_index(symbol: &str, shard_count: usize) -> usize {
+    if shard_count <= 1 {
+        return 0;
+    }
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in symbol.bytes() {
+        hash ^= byte.to_ascii_lowercase() as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    (hash % shard_count as u64) as usize
+}
+
+/// Backlog and overflow counters for one shard queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShardStats {
+    pub depth: usize,
+    pub dropped: u64,
+    pub coalesced: u64,
+}
+
+#[derive(Debug)]
+struct ShardQueue {
+    capacity: usize,
+    policy: OverflowPolicy,
+    messages: Mutex<VecDeque<MarketMessage>>,
+    notify: Notify,
+    /// Set once the router is gone, so the worker stops after its backlog.
+    closed: AtomicBool,
+    /// Cleared when the worker's receiver is dropped.
+    open: AtomicBool,
+    dropped: AtomicU64,
+    coalesced: AtomicU64,
+}
+
+impl ShardQueue {
+    fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            policy,
+            messages: Mutex::new(VecDeque::new()),
+            notify: Notify::new(),
+            closed: AtomicBool::new(false),
+            open: AtomicBool::new(true),
+            dropped: AtomicU64::new(0),
+            coalesced: AtomicU64::new(0),
+        }
+    }
+
+    fn push(&self, message: MarketMessage) {
+        {
+            let mut messages = self.messages.lock().expect("shard queue poisoned");
+            if messages.len() < self.capacity {
+                messages.push_back(message);
+            } else {
+                let coalesce_at = match self.policy {
+                    OverflowPolicy::Coalesce => messages
+                        .iter()
+                        .rposition(|queued| supersedes(&message, queued)),
+                    OverflowPolicy::DropOldest => None,
+                };
+                match coalesce_at {
+                    Some(index) => {
+                        messages[index] = message;
+                        self.coalesced.fetch_add(1, Ordering::Relaxed);
+                    }
+                    None => {
+                        messages.pop_front();
+                        messages.push_back(message);
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+        }
+        self.notify.notify_one();
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.notify.notify_one();
+    }
+
+    fn stats(&self) -> ShardStats {
+        ShardStats {
+            depth: self.messages.lock().expect("shard queue poisoned").len(),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Whether `next` carries the latest state of `queued`, so a full queue can replace it
+/// instead of evicting older data. Only funding updates and still-open klines qualify;
+/// trades, depth diffs and closed klines are never coalesced.
+fn supersedes(next: &MarketMessage, queued: &MarketMessage) -> bool {
+    match (next, queued) {
+        (MarketMessage::Funding(next), MarketMessage::Funding(queued)) => {
+            next.symbol == queued.symbol
+        }
+        (MarketMessage::Kline(next), MarketMessage::Kline(queued)) => {
+            !queued.kline.is_closed
+                && next.symbol == queued.symbol
+                && next.kline.interval == queued.kline.interval
+        }
+        _ => false,
+    }
+}
+
+/// Receiving end of one shard queue, owned by the shard worker.
+#[derive(Debug)]
+pub struct ShardReceiver {
+    queue: Arc<ShardQueue>,
+}
+
+impl ShardReceiver {
+    /// Waits for the next message. Returns `None` once the router is dropped and the
+    /// backlog is empty.
+    pub async fn recv(&mut self) -> Option<MarketMessage> {
+        loop {
+            let message = self
+                .queue
+                .messages
+                .lock()
+                .expect("shard queue poisoned")
+                .pop_front();
+            if message.is_some() {
+                return message;
+            }
+            if self.queue.closed.load(Ordering::Acquire) {
+                return None;
+            }
+            self.queue.notify.notified().await;
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Option<MarketMessage> {
+        self.queue
+            .messages
+            .lock()
+            .expect("shard queue poisoned")
+            .pop_front()
+    }
+}
+
+impl Drop for ShardReceiver {
+    fn drop(&mut self) {
+        self.queue.open.store(false, Ordering::Release);
+    }
+}
+
+#[derive(Debug)]
+struct ShardQueues(Vec<Arc<ShardQueue>>);
+
+impl Drop for ShardQueues {
+    fn drop(&mut self) {
+        for queue in &self.0 {
+            queue.close();
+        }
+    }
+}
+
+/// Routes market messages to per-shard bounded queues.
+///
+/// Every message for a symbol goes through the same FIFO queue, so per-symbol ordering is
+/// preserved. Routing never waits: a full shard applies its overflow policy and counts the
+/// loss, so a burst on one shard does not delay symbols on the others.
+#[derive(Debug, Clone)]
+pub struct ShardRouter {
+    queues: Arc<ShardQueues>,
+}
+
+impl ShardRouter {
+    pub fn new(
+        shard_count: usize,
+        queue_capacity: usize,
+        policy: OverflowPolicy,
+    ) -> (Self, Vec<ShardReceiver>) {
+        let queues: Vec<_> = (0..shard_count.max(1))
+            .map(|_| Arc::new(ShardQueue::new(queue_capacity, policy)))
+            .collect();
+        let receivers = queues
+            .iter()
+            .map(|queue| ShardReceiver {
+                queue: queue.clone(),
+            })
+            .collect();
+        (
+            Self {
+                queues: Arc::new(ShardQueues(queues)),
+            },
+            receivers,
+        )
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.queues.0.len()
+    }
+
+    /// Queues the message on its shard without waiting.
+    /// Returns `false` when the shard worker has stopped.
+    pub fn route(&self, message: MarketMessage) -> bool {
+        let queue = &self.queues.0[shard_index(message.symbol(), self.queues.0.len())];
+        if !queue.open.load(Ordering::Acquire) {
+            return false;
+        }
+        queue.push(message);
+        true
+    }
+
+    /// Messages currently queued per shard.
+    pub fn queue_depths(&self) -> Vec<usize> {
+        self.stats().iter().map(|stats| stats.depth).collect()
+    }
+
+    /// Backlog and overflow counters per shard.
+    pub fn stats(&self) -> Vec<ShardStats> {
+        self.queues.0.iter().map(|queue| queue.stats()).collect()
+    }
+}
+
+/// One line summarising every shard, e.g. `depth=[0, 12] dropped=[0, 3] coalesced=[0, 1]`.
+pub fn format_shard_stats(stats: &[ShardStats]) -> String {
+    let column = |f: fn(&ShardStats) -> String| stats.iter().map(f).collect::<Vec<_>>().join(", ");
+    format!(
+        "depth=[{}] dropped=[{}] coalesced=[{}]",
+        column(|s| s.depth.to_string()),
+        column(|s| s.dropped.to_string()),
+        column(|s| s.coalesced.to_string())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(symbol: &str, t: u64) -> MarketMessage {
+        MarketMessage::AggTrade(AggTrade {
+            s: symbol.to_string(),
+            p: "1.0".parse().unwrap(),
+            q: "1.0".parse().unwrap(),
+            t,
+            m: false,
+        })
+    }
+
+    #[test]
+    fn shard_index_is_stable_and_case_insensitive() {
+        for symbol in ["btcusdt", "ethusdt", "solusdt", "dogeusdt"] {
+            let index = shard_index(symbol, 4);
+            assert!(index < 4);
+            assert_eq!(index, shard_index(&symbol.to_uppercase(), 4));
+        }
+        assert_eq!(shard_index("btcusdt", 1), 0);
+    }
+
+    #[test]
+    fn routes_symbol_to_one_shard_in_order() {
+        let (router, mut receivers) = ShardRouter::new(3, 16, OverflowPolicy::DropOldest);
+        for t in 0..5 {
+            assert!(router.route(trade("BTCUSDT", t)));
+        }
+        let index = shard_index("btcusdt", 3);
+        assert_eq!(router.queue_depths()[index], 5);
+
+        let mut seen = Vec::new();
+        while let Some(MarketMessage::AggTrade(agg)) = receivers[index].try_recv() {
+            seen.push(agg.t);
+        }
+        assert_eq!(seen, vec![0, 1, 2, 3, 4]);
+        assert!(
+            receivers
+                .iter_mut()
+                .enumerate()
+                .filter(|(i, _)| *i != index)
+                .all(|(_, rx)| rx.try_recv().is_none())
+        );
+    }
+
+    #[tokio::test]
+    async fn full_shard_drops_oldest_without_stalling_other_shards() {
+        let (router, mut receivers) = ShardRouter::new(2, 2, OverflowPolicy::DropOldest);
+        let busy = shard_index("btcusdt", 2);
+        let other = ["ethusdt", "solusdt", "dogeusdt", "xrpusdt"]
+            .into_iter()
+            .find(|symbol| shard_index(symbol, 2) != busy)
+            .expect("a symbol on the other shard");
+
+        for t in 0..5 {
+            assert!(router.route(trade("btcusdt", t)));
+        }
+        assert!(router.route(trade(other, 99)));
+
+        let quiet = 1 - busy;
+        match receivers[quiet].recv().await {
+            Some(MarketMessage::AggTrade(agg)) => assert_eq!(agg.t, 99),
+            other => panic!("unexpected message: {other:?}"),
+        }
+        let stats = router.stats();
+        assert_eq!(stats[busy].depth, 2);
+        assert_eq!(stats[busy].dropped, 3);
+        assert_eq!(stats[quiet].dropped, 0);
+
+        let mut kept = Vec::new();
+        while let Some(MarketMessage::AggTrade(agg)) = receivers[busy].try_recv() {
+            kept.push(agg.t);
+        }
+        assert_eq!(kept, vec![3, 4]);
+
+        drop(router);
+        assert!(receivers[busy].recv().await.is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+/// Exit status after a clean shutdown on SIGTERM / Ctrl-C.
+pub const EXIT_OK: i32 = 0;
+/// Exit status when the upstream stream ended on its own, so a supervisor restarts us.
+pub const EXIT_UPSTREAM_CLOSED: i32 = 1;
+/// Exit status when the drain deadline passed with work left, e.g. undelivered alerts.
+pub const EXIT_DRAIN_INCOMPLETE: i32 = 2;
+
+/// Shutdown flag shared by long-running tasks: the warp server, websocket clients and the
+/// news ingest loop stop once it is triggered.
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    tx: Arc<watch::Sender<bool>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self {
+            tx: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    pub fn trigger(&self) {
+        self.tx.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.tx.borrow()
+    }
+
+    /// Resolves once `trigger` has been called, immediately if it already was.
+    pub async fn wait(&self) {
+        let mut rx = self.tx.subscribe();
+        let _ = rx.wait_for(|triggered| *triggered).await;
+    }
+}
+
+/// Waits for SIGTERM or Ctrl-C and returns the signal name.
+pub async fn wait_for_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => tokio::select! {
+                _ = sigterm.recv() => "SIGTERM",
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+            },
+            Err(err) => {
+                log::warn!(target: "shutdown", "cannot listen for SIGTERM: {err}");
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "SIGINT"
+    }
+}
+
+/// Why the main loop stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownCause {
+    Signal(&'static str),
+    UpstreamClosed,
+}
+
+impl ShutdownCause {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Signal(name) => name,
+            Self::UpstreamClosed => "upstream_closed",
+        }
+    }
+}
+
+/// Outcome of a shutdown, logged before exiting with `exit_code`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShutdownReport {
+    pub cause: ShutdownCause,
+    /// Alerts still queued or in flight when the drain deadline passed.
+    pub undelivered: usize,
+    /// Stages that did not finish before the drain deadline.
+    pub timed_out: Vec<&'static str>,
+}
+
+impl ShutdownReport {
+    pub fn new(cause: ShutdownCause) -> Self {
+        Self {
+            cause,
+            undelivered: 0,
+            timed_out: Vec::new(),
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        if self.cause == ShutdownCause::UpstreamClosed {
+            EXIT_UPSTREAM_CLOSED
+        } else if self.undelivered > 0 || !self.timed_out.is_empty() {
+            EXIT_DRAIN_INCOMPLETE
+        } else {
+            EXIT_OK
+        }
+    }
+}
+
+pub fn format_shutdown_log(report: &ShutdownReport) -> String {
+    format!(
+        "complete cause={} undelivered_alerts={} timed_out={} exit={}",
+        report.cause.as_str(),
+        report.undelivered,
+        if report.timed_out.is_empty() {
+            "none".to_string()
+        } else {
+            report.timed_out.join(",")
+        },
+        report.exit_code()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_code_reflects_cause_and_drain() {
+        let mut report = ShutdownReport::new(ShutdownCause::Signal("SIGTERM"));
+        assert_eq!(report.exit_code(), EXIT_OK);
+
+        report.undelivered = 2;
+        assert_eq!(report.exit_code(), EXIT_DRAIN_INCOMPLETE);
+        report.undelivered = 0;
+        report.timed_out.push("news");
+        assert_eq!(report.exit_code(), EXIT_DRAIN_INCOMPLETE);
+        assert_eq!(
+            format_shutdown_log(&report),
+            "complete cause=SIGTERM undelivered_alerts=0 timed_out=news exit=2"
+        );
+
+        let report = ShutdownReport::new(ShutdownCause::UpstreamClosed);
+        assert_eq!(report.exit_code(), EXIT_UPSTREAM_CLOSED);
+    }
+
+    #[tokio::test]
+    async fn wait_resolves_for_tasks_started_before_and_after_trigger() {
+        let shutdown = Shutdown::new();
+        let waiter = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.wait().await }
+        });
+        assert!(!shutdown.is_triggered());
+        shutdown.trigger();
+        waiter.await.unwrap();
+        shutdown.wait().await;
+        assert!(shutdown.is_triggered());
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::correlation::engine::CorrelationState;
+use crate::refactor::big_move_detector::{BigMoveDetector, BigMoveDetectorState};
+
+/// Per-symbol detector state kept across restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolState {
+    pub last_price: Option<f64>,
+    /// One entry per big-move profile.
+    pub big_move: Vec<BigMoveDetectorState>,
+    pub last_funding_alert_ms: Option<u64>,
+}
+
+impl SymbolState {
+    fn is_empty(&self) -> bool {
+        self.last_price.is_none()
+            && self.big_move.is_empty()
+            && self.last_funding_alert_ms.is_none()
+    }
+}
+
+/// Contents of the state file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub saved_at_ms: u64,
+    pub symbols: HashMap<String, SymbolState>,
+    pub correlation: Option<CorrelationState>,
+}
+
+impl StateSnapshot {
+    /// Reads the state file; `None` when it does not exist yet.
+    pub fn read(path: &str) -> Result<Option<Self>> {
+        let raw = match std::fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).with_context(|| format!("read {path}")),
+        };
+        let snapshot = serde_json::from_str(&raw).with_context(|| format!("parse {path}"))?;
+        Ok(Some(snapshot))
+    }
+
+    /// Drops last prices saved more than `max_age_ms` ago and funding cooldowns that have
+    /// run out. Big-move windows and correlation queues are trimmed by their owners.
+    pub fn prune(&mut self, now_ms: u64, max_age_ms: u64, funding_cooldown_ms: u64) {
+        let stale = now_ms.saturating_sub(self.saved_at_ms) > max_age_ms;
+        for state in self.symbols.values_mut() {
+            if stale {
+                state.last_price = None;
+            }
+            state.last_funding_alert_ms = state
+                .last_funding_alert_ms
+                .filter(|last| now_ms.saturating_sub(*last) < funding_cooldown_ms);
+        }
+        self.symbols.retain(|_, state| !state.is_empty());
+    }
+}
+
+/// Loads and prunes the configured state file, logging what was restored. Unreadable
+/// files are reported and ignored.
+pub fn load_state(config: &Config, now_ms: u64) -> Option<StateSnapshot> {
+    if !config.state.enabled {
+        return None;
+    }
+    let mut snapshot = match StateSnapshot::read(&config.state.path) {
+        Ok(Some(snapshot)) => snapshot,
+        Ok(None) => return None,
+        Err(err) => {
+            log::warn!(target: "state", "ignoring state file: {err:#}");
+            return None;
+        }
+    };
+    snapshot.prune(
+        now_ms,
+        config.state.max_age_secs.saturating_mul(1_000),
+        config.funding_rate_cooldown_secs.saturating_mul(1_000),
+    );
+    log::info!(
+        target: "state",
+        "restoring {} symbols from {} saved {}s ago",
+        snapshot.symbols.len(),
+        config.state.path,
+        now_ms.saturating_sub(snapshot.saved_at_ms) / 1_000
+    );
+    Some(snapshot)
+}
+
+/// Collects the per-symbol state of one owner (a shard worker or `AppState`).
+pub fn collect_symbol_states(
+    last_prices: &HashMap<String, f64>,
+    big_move_detectors: &HashMap<String, Vec<BigMoveDetector>>,
+    last_funding_alert_ms: &HashMap<String, u64>,
+) -> HashMap<String, SymbolState> {
+    let mut symbols: HashMap<String, SymbolState> = HashMap::new();
+    for (symbol, price) in last_prices {
+        symbols.entry(symbol.clone()).or_default().last_price = Some(*price);
+    }
+    for (symbol, detectors) in big_move_detectors {
+        symbols.entry(symbol.clone()).or_default().big_move =
+            detectors.iter().map(BigMoveDetector::state).collect();
+    }
+    for (symbol, last) in last_funding_alert_ms {
+        symbols
+            .entry(symbol.clone())
+            .or_default()
+            .last_funding_alert_ms = Some(*last);
+    }
+    symbols
+}
+
+/// Restores each detector from the saved state of the same profile.
+pub fn restore_big_move_detectors(
+    detectors: &mut [BigMoveDetector],
+    states: Vec<BigMoveDetectorState>,
+    now_ms: u64,
+    max_age_ms: u64,
+) {
+    for state in states {
+        if let Some(detector) = detectors.iter_mut().find(|d| d.profile() == state.profile) {
+            detector.restore(state, now_ms, max_age_ms);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct StateParts {
+    /// Symbol state keyed by owner, so a symbol that moved or left is dropped with its
+    /// owner's next update.
+    symbols: HashMap<usize, HashMap<String, SymbolState>>,
+    correlation: Option<CorrelationState>,
+}
+
+/// Latest state published by each owner, written to the state file as one snapshot.
+/// `save_async` writes on Tokio's blocking thread pool.
+#[derive(Debug, Clone)]
+pub struct StateStore {
+    path: String,
+    parts: Arc<Mutex<StateParts>>,
+}
+
+impl StateStore {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            parts: Arc::default(),
+        }
+    }
+
+    pub fn update_symbols(&self, owner: usize, symbols: HashMap<String, SymbolState>) {
+        let mut parts = self.parts.lock().expect("state parts poisoned");
+        parts.symbols.insert(owner, symbols);
+    }
+
+    pub fn update_correlation(&self, state: CorrelationState) {
+        self.parts.lock().expect("state parts poisoned").correlation = Some(state);
+    }
+
+    /// Writes the snapshot through a temporary file so a crash never leaves a torn file.
+    pub fn save(&self, now_ms: u64) -> Result<usize> {
+        let snapshot = {
+            let parts = self.parts.lock().expect("state parts poisoned");
+            StateSnapshot {
+                saved_at_ms: now_ms,
+                symbols: parts
+                    .symbols
+                    .values()
+                    .flat_map(|symbols| symbols.clone())
+                    .collect(),
+                correlation: parts.correlation.clone(),
+            }
+        };
+        let tmp = format!("{}.tmp", self.path);
+        std::fs::write(&tmp, serde_json::to_vec(&snapshot)?)
+            .with_context(|| format!("write {tmp}"))?;
+        std::fs::rename(&tmp, &self.path).with_context(|| format!("rename {tmp}"))?;
+        Ok(snapshot.symbols.len())
+    }
+
+    pub async fn save_async(&self, now_ms: u64) -> Result<usize> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.save(now_ms)).await?
+    }
+}
+
+/// Writes the store every `interval_secs`.
+pub fn spawn_state_writer(store: StateStore, interval_secs: u64) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+            if let Err(err) = store.save_async(now_ms).await {
+                log::error!(target: "state", "snapshot failed: {err:#}");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::correlation::engine::CorrelationEngine;
+    use crate::correlation::model::NewsEvent;
+    use crate::refactor::big_move_detector::{BigMovePhase, BigMoveProfile, DepthSnapshot};
+
+    fn detector() -> BigMoveDetector {
+        BigMoveDetector::from_profile(&BigMoveProfile {
+            window_size: 3,
+            min_consecutive: 2,
+            pressure_threshold: 70.0,
+            min_total_notional: 0.0,
+            ..BigMoveProfile::default()
+        })
+    }
+
+    fn depth(bid_pressure_pct: f64, timestamp_ms: u64) -> DepthSnapshot {
+        DepthSnapshot {
+            bid_pressure_pct,
+            total_notional: 1_000.0,
+            timestamp_ms,
+        }
+    }
+
+    #[test]
+    fn round_trips_state_and_drops_what_aged_out() {
+        let path = std::env::temp_dir().join(format!("state_snapshot_{}.json", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let store = StateStore::new(&path);
+
+        let mut detectors = vec![detector()];
+        for t in [1_000, 2_000, 3_000] {
+            detectors[0].push(depth(90.0, t));
+        }
+        assert_eq!(detectors[0].phase(), BigMovePhase::Sustained);
+        store.update_symbols(
+            0,
+            collect_symbol_states(
+                &HashMap::from([("btcusdt".to_string(), 100.0)]),
+                &HashMap::from([("btcusdt".to_string(), detectors)]),
+                &HashMap::from([("ethusdt".to_string(), 1_000)]),
+            ),
+        );
+        let mut engine = CorrelationEngine::new(0.1, 60, 0.0);
+        engine.ingest_news(NewsEvent {
+            symbol: "btcusdt".to_string(),
+            timestamp_ms: 2_000,
+            headline: "ETF inflows".to_string(),
+            sentiment: None,
+        });
+        store.update_correlation(engine.state());
+        assert_eq!(store.save(3_000).unwrap(), 2);
+
+        // Restarted shortly after: everything is still relevant.
+        let mut snapshot = StateSnapshot::read(&path).unwrap().unwrap();
+        snapshot.prune(10_000, 60_000, 30_000);
+        let btc = snapshot.symbols.remove("btcusdt").unwrap();
+        assert_eq!(btc.last_price, Some(100.0));
+        let mut restored = vec![detector()];
+        restore_big_move_detectors(&mut restored, btc.big_move, 10_000, 60_000);
+        assert_eq!(restored[0].phase(), BigMovePhase::Sustained);
+        assert_eq!(
+            snapshot.symbols["ethusdt"].last_funding_alert_ms,
+            Some(1_000)
+        );
+        let mut engine = CorrelationEngine::new(0.1, 60, 0.0);
+        engine.restore(snapshot.correlation.clone().unwrap(), 10_000);
+        assert_eq!(engine.state().news["btcusdt"].len(), 1);
+
+        // Restarted much later: prices, cooldowns and queues are gone, and the move that
+        // was active is not reported as a fresh breakout.
+        let mut snapshot = StateSnapshot::read(&path).unwrap().unwrap();
+        let later = 2 * 3_600_000;
+        snapshot.prune(later, 60_000, 30_000);
+        assert!(!snapshot.symbols.contains_key("ethusdt"));
+        let btc = snapshot.symbols.remove("btcusdt").unwrap();
+        assert_eq!(btc.last_price, None);
+        let mut restored = vec![detector()];
+        restore_big_move_detectors(&mut restored, btc.big_move, later, 60_000);
+        assert_eq!(restored[0].phase(), BigMovePhase::Exhausted);
+        let mut engine = CorrelationEngine::new(0.1, 60, 0.0);
+        engine.restore(snapshot.correlation.unwrap(), later);
+        assert!(engine.state().news.is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn corrupt_or_partial_state_files_are_ignored() {
+        let path = std::env::temp_dir().join(format!("state_corrupt_{}.json", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let mut config = Config::default();
+        config.state.enabled = true;
+        config.state.path = path.clone();
+        config.state.max_age_secs = 3_600;
+
+        assert!(load_state(&config, 10_000).is_none(), "missing file");
+
+        // A file cut short mid-write, one that is not JSON, and one of the wrong shape.
+        let store = StateStore::new(&path);
+        store.update_symbols(
+            0,
+            HashMap::from([(
+                "btcusdt".to_string(),
+                SymbolState {
+                    last_price: Some(100.0),
+                    ..SymbolState::default()
+                },
+            )]),
+        );
+        store.save(5_000).unwrap();
+        let full = std::fs::read_to_string(&path).unwrap();
+        for raw in [
+            &full[..full.len() / 2],
+            "",
+            "\0\0\0",
+            r#"{"symbols":[1,2]}"#,
+        ] {
+            std::fs::write(&path, raw).unwrap();
+            assert!(load_state(&config, 10_000).is_none(), "loaded {raw:?}");
+        }
+
+        // The next save replaces the bad file.
+        store.save(6_000).unwrap();
+        let snapshot = load_state(&config, 10_000).expect("restored");
+        assert_eq!(snapshot.symbols["btcusdt"].last_price, Some(100.0));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
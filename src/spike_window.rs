@@ -0,0 +1,350 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Upper bound on trades kept per symbol per window, so a burst can't grow memory unbounded.
+const MAX_TRADES_PER_WINDOW: usize = 50_000;
+
+#[derive(Debug, Clone, Copy)]
+struct WindowTrade {
+    seq: u64,
+    timestamp_ms: u64,
+    price: f64,
+    notional: f64,
+}
+
+/// One rolling time window over a symbol's trades with O(1) amortized high/low tracking.
+#[derive(Debug)]
+struct RollingWindow {
+    window_ms: u64,
+    trades: VecDeque<WindowTrade>,
+    /// Monotonic decreasing prices; front is the window high.
+    highs: VecDeque<WindowTrade>,
+    /// Monotonic increasing prices; front is the window low.
+    lows: VecDeque<WindowTrade>,
+    notional: f64,
+    last_alert_ms: Option<u64>,
+}
+
+impl RollingWindow {
+    fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms,
+            trades: VecDeque::new(),
+            highs: VecDeque::new(),
+            lows: VecDeque::new(),
+            notional: 0.0,
+            last_alert_ms: None,
+        }
+    }
+
+    fn push(&mut self, trade: WindowTrade) {
+        while self.highs.back().is_some_and(|t| t.price <= trade.price) {
+            self.highs.pop_back();
+        }
+        self.highs.push_back(trade);
+        while self.lows.back().is_some_and(|t| t.price >= trade.price) {
+            self.lows.pop_back();
+        }
+        self.lows.push_back(trade);
+
+        self.trades.push_back(trade);
+        self.notional += trade.notional;
+
+        while let Some(front) = self.trades.front().copied() {
+            let expired = trade.timestamp_ms.saturating_sub(front.timestamp_ms) > self.window_ms;
+            if !expired && self.trades.len() <= MAX_TRADES_PER_WINDOW {
+                break;
+            }
+            self.trades.pop_front();
+            self.notional -= front.notional;
+            if self.highs.front().is_some_and(|t| t.seq == front.seq) {
+                self.highs.pop_front();
+            }
+            if self.lows.front().is_some_and(|t| t.seq == front.seq) {
+                self.lows.pop_front();
+            }
+        }
+    }
+
+    fn summarize(&self) -> Option<WindowMove> {
+        let start = self.trades.front()?;
+        let end = self.trades.back()?;
+        let high = self.highs.front()?;
+        let low = self.lows.front()?;
+        if low.price <= 0.0 {
+            return None;
+        }
+
+        let range_pct = (high.price - low.price) / low.price * 100.0;
+        // The move points from the older extreme towards the newer one.
+        let move_pct = if high.seq >= low.seq {
+            range_pct
+        } else {
+            -range_pct
+        };
+
+        Some(WindowMove {
+            window_ms: self.window_ms,
+            start_ms: start.timestamp_ms,
+            end_ms: end.timestamp_ms,
+            start_price: start.price,
+            end_price: end.price,
+            high: high.price,
+            low: low.price,
+            move_pct,
+            notional: self.notional.max(0.0),
+            trade_count: self.trades.len(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowMove {
+    pub window_ms: u64,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub start_price: f64,
+    pub end_price: f64,
+    pub high: f64,
+    pub low: f64,
+    /// High/low range in %, positive when the low printed before the high.
+    pub move_pct: f64,
+    pub notional: f64,
+    pub trade_count: usize,
+}
+
+impl WindowMove {
+    pub fn window_label(&self) -> String {
+        format_window_label(self.window_ms)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpikeEvaluation {
+    /// Largest absolute move across all windows, signed.
+    pub strongest_move_pct: f64,
+    /// Windows that crossed the threshold and are outside their alert cooldown.
+    pub spikes: Vec<WindowMove>,
+}
+
+/// Per-symbol ring buffers of trades for several time windows (e.g. 10s, 1m, 5m).
+///
+/// Each window reports its high/low move; a window that crosses the symbol threshold
+/// alerts once and then stays quiet for one window length.
+#[derive(Debug)]
+pub struct SpikeWindowDetector {
+    windows_ms: Vec<u64>,
+    by_symbol: HashMap<String, Vec<RollingWindow>>,
+    next_seq: u64,
+}
+
+impl SpikeWindowDetector {
+    pub fn new(windows_secs: &[u64]) -> Self {
+        let mut windows_ms: Vec<u64> = windows_secs
+            .iter()
+            .filter(|secs| **secs > 0)
+            .map(|secs| secs.saturating_mul(1_000))
+            .collect();
+        windows_ms.sort_unstable();
+        windows_ms.dedup();
+
+        Self {
+            windows_ms,
+            by_symbol: HashMap::new(),
+            next_seq: 0,
+        }
+    }
+
+    /// Drops all state for a symbol that left the watched universe.
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        self.by_symbol.remove(&symbol.to_lowercase());
+    }
+
+    pub fn push(
+        &mut self,
+        symbol: &str,
+        timestamp_ms: u64,
+        price: f64,
+        qty: f64,
+        threshold_pct: f64,
+    ) -> SpikeEvaluation {
+        if !price.is_finite() || !qty.is_finite() || price <= 0.0 || qty < 0.0 {
+            return SpikeEvaluation::default();
+        }
+
+        let windows_ms = &self.windows_ms;
+        let windows = self
+            .by_symbol
+            .entry(symbol.to_lowercase())
+            .or_insert_with(|| {
+                windows_ms
+                    .iter()
+                    .map(|ms| RollingWindow::new(*ms))
+                    .collect()
+            });
+
+        let trade = WindowTrade {
+            seq: self.next_seq,
+            timestamp_ms,
+            price,
+            notional: price * qty,
+        };
+        self.next_seq += 1;
+
+        let mut evaluation = SpikeEvaluation::default();
+        for window in windows.iter_mut() {
+            window.push(trade);
+            let Some(summary) = window.summarize() else {
+                continue;
+            };
+
+            if summary.move_pct.abs() > evaluation.strongest_move_pct.abs() {
+                evaluation.strongest_move_pct = summary.move_pct;
+            }
+
+            if threshold_pct <= 0.0 || summary.move_pct.abs() < threshold_pct {
+                continue;
+            }
+            if let Some(last) = window.last_alert_ms
+                && timestamp_ms.saturating_sub(last) < window.window_ms
+            {
+                continue;
+            }
+            window.last_alert_ms = Some(timestamp_ms);
+            evaluation.spikes.push(summary);
+        }
+
+        evaluation
+    }
+}
+
+pub fn format_window_label(window_ms: u64) -> String {
+    let secs = window_ms / 1_000;
+    if secs >= 3_600 && secs.is_multiple_of(3_600) {
+        format!("{}h", secs / 3_600)
+    } else if secs >= 60 && secs.is_multiple_of(60) {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Parses a window list such as `10s,1m,5m` (bare numbers are seconds). Invalid, zero and
+/// overflowing entries are skipped.
+pub fn parse_window_list(raw: &str) -> Vec<u64> {
+    raw.split(',')
+        .filter_map(|token| {
+            let token = token.trim().to_lowercase();
+            if token.is_empty() {
+                return None;
+            }
+            let (digits, multiplier) = if let Some(v) = token.strip_suffix('h') {
+                (v, 3_600)
+            } else if let Some(v) = token.strip_suffix('m') {
+                (v, 60)
+            } else if let Some(v) = token.strip_suffix('s') {
+                (v, 1)
+            } else {
+                (token.as_str(), 1)
+            };
+            let value = digits.trim().parse::<u64>().ok().filter(|v| *v > 0)?;
+            let secs = value.checked_mul(multiplier);
+            if secs.is_none() {
+                log::warn!(target: "config", "spike window '{token}' is too long, ignored");
+            }
+            secs
+        })
+        .collect()
+}
+
+pub fn format_spike_log(symbol: &str, spike: &WindowMove) -> String {
+    let direction = if spike.move_pct >= 0.0 { "UP" } else { "DOWN" };
+    format!(
+        "[SPIKE] {} {} window={} move={:+.3}% start={} end={} high={} low={} notional={:.0} trades={}",
+        symbol.to_uppercase(),
+        direction,
+        spike.window_label(),
+        spike.move_pct,
+        spike.start_price,
+        spike.end_price,
+        spike.high,
+        spike.low,
+        spike.notional,
+        spike.trade_count
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_gradual_move_that_tick_to_tick_would_miss() {
+        let mut detector = SpikeWindowDetector::new(&[10, 60]);
+        let mut fired = Vec::new();
+        // 100 -> 102 over 30s in 0.1% steps.
+        for i in 0..=20_u64 {
+            let price = 100.0 * (1.0 + 0.001 * i as f64);
+            let eval = detector.push("btcusdt", i * 1_500, price, 1.0, 1.45);
+            fired.extend(eval.spikes);
+        }
+
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].window_label(), "1m");
+        assert!(fired[0].move_pct >= 1.45);
+        assert_eq!(fired[0].start_price, 100.0);
+    }
+
+    #[test]
+    fn reports_downward_move_and_notional() {
+        let mut detector = SpikeWindowDetector::new(&[10]);
+        detector.push("ethusdt", 0, 100.0, 2.0, 5.0);
+        let eval = detector.push("ethusdt", 1_000, 90.0, 1.0, 5.0);
+
+        assert_eq!(eval.spikes.len(), 1);
+        let spike = &eval.spikes[0];
+        assert!((spike.move_pct + 11.111).abs() < 0.01);
+        assert_eq!(spike.notional, 290.0);
+        assert_eq!(spike.end_price, 90.0);
+        assert!(format_spike_log("ethusdt", spike).starts_with("[SPIKE] ETHUSDT DOWN window=10s"));
+    }
+
+    #[test]
+    fn window_evicts_old_extremes_and_respects_cooldown() {
+        let mut detector = SpikeWindowDetector::new(&[10]);
+        detector.push("solusdt", 0, 50.0, 1.0, 1.0);
+        let eval = detector.push("solusdt", 1_000, 100.0, 1.0, 1.0);
+        assert_eq!(eval.spikes.len(), 1);
+
+        // Still inside cooldown.
+        let eval = detector.push("solusdt", 2_000, 110.0, 1.0, 1.0);
+        assert!(eval.spikes.is_empty());
+
+        // 50 and 100 have left the window; the remaining range is 110..120.
+        let eval = detector.push("solusdt", 11_500, 120.0, 1.0, 1.0);
+        assert!((eval.strongest_move_pct - 9.0909).abs() < 1e-3);
+        assert_eq!(eval.spikes.len(), 1);
+    }
+
+    #[test]
+    fn parses_window_lists() {
+        assert_eq!(parse_window_list("10s, 1m,5m,,bad,0"), vec![10, 60, 300]);
+        assert_eq!(parse_window_list("30"), vec![30]);
+    }
+
+    #[test]
+    fn formats_window_labels() {
+        assert_eq!(format_window_label(3_600_000), "1h");
+        assert_eq!(format_window_label(300_000), "5m");
+        assert_eq!(format_window_label(90_000), "90s");
+        assert_eq!(format_window_label(5_400_000), "90m");
+    }
+
+    #[test]
+    fn skips_windows_that_overflow_seconds() {
+        assert_eq!(
+            parse_window_list("99999999999999999h,5m,18446744073709551615m"),
+            vec![300]
+        );
+        assert_eq!(parse_window_list("18446744073709551615s"), vec![u64::MAX]);
+    }
+}
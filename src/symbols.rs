@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::config::{Config, RegistryConfig};
+use crate::decimal::{Decimal, SymbolPrecision};
+
+/// Where the symbol registry comes from at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RegistryMode {
+    /// No registry: `SYMBOLS` is used as-is.
+    #[default]
+    Off,
+    /// Fetch `exchangeInfo` (or reuse a fresh on-disk cache), falling back to a stale cache.
+    Live,
+    /// Read the cache/fixture file only; never touches the network.
+    Offline,
+}
+
+impl RegistryMode {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "off" | "disabled" | "false" => Some(Self::Off),
+            "live" | "on" | "true" => Some(Self::Live),
+            "offline" | "fixture" => Some(Self::Offline),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketType {
+    Spot,
+    Perpetual,
+    Delivery,
+}
+
+impl MarketType {
+    pub fn as_label(self) -> &'static str {
+        match self {
+            Self::Spot => "spot",
+            Self::Perpetual => "perpetual",
+            Self::Delivery => "delivery",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolInfo {
+    /// Lowercase, as used in stream names and `SymbolConfig`.
+    pub symbol: String,
+    pub base_asset: String,
+    pub quote_asset: String,
+    /// Exchange status, e.g. `TRADING` or `BREAK`.
+    pub status: String,
+    pub market_type: MarketType,
+    pub tick_size: Option<Decimal>,
+    pub step_size: Option<Decimal>,
+}
+
+impl SymbolInfo {
+    pub fn is_trading(&self) -> bool {
+        self.status.eq_ignore_ascii_case("TRADING")
+    }
+
+    pub fn precision(&self) -> SymbolPrecision {
+        SymbolPrecision {
+            tick_size: self.tick_size,
+            step_size: self.step_size,
+        }
+    }
+}
+
+/// Why a configured symbol was dropped at startup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolIssue {
+    Unknown(String),
+    NotTrading { symbol: String, status: String },
+}
+
+impl SymbolIssue {
+    pub fn symbol(&self) -> &str {
+        match self {
+            Self::Unknown(symbol) => symbol,
+            Self::NotTrading { symbol, .. } => symbol,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ExchangeInfo {
+    symbols: Vec<RawSymbol>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSymbol {
+    symbol: String,
+    status: String,
+    base_asset: String,
+    quote_asset: String,
+    #[serde(default)]
+    contract_type: Option<String>,
+    #[serde(default)]
+    filters: Vec<RawFilter>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawFilter {
+    filter_type: String,
+    #[serde(default)]
+    tick_size: Option<Decimal>,
+    #[serde(default)]
+    step_size: Option<Decimal>,
+}
+
+/// Exchange metadata per symbol, keyed by lowercase symbol.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolRegistry {
+    symbols: HashMap<String, SymbolInfo>,
+}
+
+impl SymbolRegistry {
+    /// Parses a spot or futures `exchangeInfo` response.
+    pub fn from_exchange_info(raw: &str) -> Result<Self> {
+        let info: ExchangeInfo =
+            serde_json::from_str(raw).context("exchangeInfo payload did not parse")?;
+        let symbols = info
+            .symbols
+            .into_iter()
+            .map(|raw| {
+                let filter = |kind: &str| raw.filters.iter().find(|f| f.filter_type == kind);
+                let market_type = match raw.contract_type.as_deref() {
+                    None | Some("") => MarketType::Spot,
+                    Some("PERPETUAL") => MarketType::Perpetual,
+                    Some(_) => MarketType::Delivery,
+                };
+                let info = SymbolInfo {
+                    symbol: raw.symbol.to_lowercase(),
+                    tick_size: filter("PRICE_FILTER").and_then(|f| f.tick_size),
+                    step_size: filter("LOT_SIZE").and_then(|f| f.step_size),
+                    base_asset: raw.base_asset,
+                    quote_asset: raw.quote_asset,
+                    status: raw.status,
+                    market_type,
+                };
+                (info.symbol.clone(), info)
+            })
+            .collect();
+        Ok(Self { symbols })
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&SymbolInfo> {
+        self.symbols.get(&symbol.to_lowercase())
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SymbolInfo> {
+        self.symbols.values()
+    }
+
+    pub fn validate(&self, symbols: &[String]) -> Vec<SymbolIssue> {
+        symbols
+            .iter()
+            .filter_map(|symbol| match self.get(symbol) {
+                None => Some(SymbolIssue::Unknown(symbol.clone())),
+                Some(info) if !info.is_trading() => Some(SymbolIssue::NotTrading {
+                    symbol: symbol.clone(),
+                    status: info.status.clone(),
+                }),
+                Some(_) => None,
+            })
+            .collect()
+    }
+
+    /// Lowercase base asset -> the trading symbol news about it should be tagged with.
+    ///
+    /// A configured symbol wins, then a USDT quote, then the alphabetically first pair.
+    pub fn base_asset_map(&self, preferred: &[String]) -> HashMap<String, String> {
+        let rank = |info: &SymbolInfo| {
+            (
+                !preferred.contains(&info.symbol),
+                !info.quote_asset.eq_ignore_ascii_case("USDT"),
+                info.symbol.clone(),
+            )
+        };
+        let mut best: HashMap<String, &SymbolInfo> = HashMap::new();
+        for info in self.symbols.values().filter(|info| info.is_trading()) {
+            best.entry(info.base_asset.to_lowercase())
+                .and_modify(|current| {
+                    if rank(info) < rank(current) {
+                        *current = info;
+                    }
+                })
+                .or_insert(info);
+        }
+        best.into_iter()
+            .map(|(base, info)| (base, info.symbol.clone()))
+            .collect()
+    }
+
+    /// Drops configured symbols that are unknown or not trading and fills in tick/step sizes
+    /// that were not set through the environment. Returns the dropped symbols.
+    pub fn apply_to_config(&self, config: &mut Config) -> Vec<SymbolIssue> {
+        let configured: Vec<String> = config.symbols.iter().map(|s| s.symbol.clone()).collect();
+        let issues = self.validate(&configured);
+        config
+            .symbols
+            .retain(|cfg| !issues.iter().any(|issue| issue.symbol() == cfg.symbol));
+
+        for cfg in &mut config.symbols {
+            if let Some(info) = self.get(&cfg.symbol) {
+                cfg.precision.tick_size = cfg.precision.tick_size.or(info.tick_size);
+                cfg.precision.step_size = cfg.precision.step_size.or(info.step_size);
+            }
+        }
+        issues
+    }
+}
+
+fn cache_is_fresh(path: &Path, ttl: Duration) -> bool {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age <= ttl)
+}
+
+fn read_cache(path: &Path) -> Result<SymbolRegistry> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    SymbolRegistry::from_exchange_info(&raw)
+}
+
+async fn fetch_exchange_info(client: &Client, url: &str) -> Result<String> {
+    let response = client.get(url).send().await?.error_for_status()?;
+    Ok(response.text().await?)
+}
+
+/// Loads the registry according to `config.mode`. `Ok(None)` means the registry is off.
+pub async fn load_registry(
+    config: &RegistryConfig,
+    client: &Client,
+) -> Result<Option<SymbolRegistry>> {
+    let path = Path::new(&config.cache_path);
+    match config.mode {
+        RegistryMode::Off => Ok(None),
+        RegistryMode::Offline => read_cache(path).map(Some),
+        RegistryMode::Live => {
+            if cache_is_fresh(path, Duration::from_secs(config.cache_ttl_secs)) {
+                return read_cache(path).map(Some);
+            }
+
+            match fetch_exchange_info(client, &config.exchange_info_url).await {
+                Ok(raw) => {
+                    let registry = SymbolRegistry::from_exchange_info(&raw)?;
+                    if let Err(err) = std::fs::write(path, &raw) {
+                        log::warn!(
+                            target: "symbols",
+                            "failed to write cache {}: {}",
+                            path.display(),
+                            err
+                        );
+                    }
+                    Ok(Some(registry))
+                }
+                Err(err) if path.exists() => {
+                    log::warn!(
+                        target: "symbols",
+                        "exchangeInfo fetch failed ({}); using stale cache {}",
+                        err,
+                        path.display()
+                    );
+                    read_cache(path).map(Some)
+                }
+                Err(err) => Err(err.context("exchangeInfo fetch failed and no cache exists")),
+            }
+        }
+    }
+}
+
+/// Loads the registry for startup and applies it to `config`, logging what was dropped.
+/// A registry that fails to load is reported and skipped, leaving `SYMBOLS` unvalidated.
+pub async fn init_registry(config: &mut Config) -> Option<SymbolRegistry> {
+    let client = Client::builder()
+        .user_agent("feeder-service-symbols/0.1")
+        .timeout(Duration::from_secs(20))
+        .build()
+        .ok()?;
+    let registry = match load_registry(&config.registry, &client).await {
+        Ok(Some(registry)) => registry,
+        Ok(None) => return None,
+        Err(err) => {
+            log::warn!(target: "symbols", "registry unavailable, SYMBOLS not validated: {err:#}");
+            return None;
+        }
+    };
+
+    for issue in registry.apply_to_config(config) {
+        match issue {
+            SymbolIssue::Unknown(symbol) => {
+                log::warn!(target: "symbols", "dropping {symbol}: not listed in exchangeInfo")
+            }
+            SymbolIssue::NotTrading { symbol, status } => {
+                log::warn!(target: "symbols", "dropping {symbol}: status {status}")
+            }
+        }
+    }
+    log::info!(
+        target: "symbols",
+        "registry loaded: {} listed, {} configured",
+        registry.len(),
+        config.symbols.len()
+    );
+    Some(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/exchange_info.json");
+
+    fn registry() -> SymbolRegistry {
+        SymbolRegistry::from_exchange_info(FIXTURE).expect("fixture parses")
+    }
+
+    #[test]
+    fn parses_filters_status_and_market_type() {
+        let registry = registry();
+        let pepe = registry.get("PEPEUSDT").expect("pepe listed");
+        assert_eq!(pepe.base_asset, "PEPE");
+        assert_eq!(pepe.quote_asset, "USDT");
+        assert_eq!(pepe.market_type, MarketType::Spot);
+        assert_eq!(pepe.tick_size.map(|t| t.precision()), Some(8));
+        assert_eq!(pepe.step_size.map(|s| s.precision()), Some(0));
+        assert!(!registry.get("lunausdt").expect("luna listed").is_trading());
+
+        let futures = SymbolRegistry::from_exchange_info(
+            r#"{"symbols":[{"symbol":"BTCUSDT","status":"TRADING","baseAsset":"BTC","quoteAsset":"USDT","contractType":"PERPETUAL","filters":[]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            futures.get("btcusdt").map(|s| s.market_type),
+            Some(MarketType::Perpetual)
+        );
+    }
+
+    #[test]
+    fn validates_symbols_and_maps_base_assets() {
+        let registry = registry();
+        let issues = registry.validate(&[
+            "btcusdt".to_string(),
+            "lunausdt".to_string(),
+            "nopeusdt".to_string(),
+        ]);
+        assert_eq!(
+            issues,
+            vec![
+                SymbolIssue::NotTrading {
+                    symbol: "lunausdt".to_string(),
+                    status: "BREAK".to_string(),
+                },
+                SymbolIssue::Unknown("nopeusdt".to_string()),
+            ]
+        );
+
+        let map = registry.base_asset_map(&[]);
+        assert_eq!(map.get("eth").map(String::as_str), Some("ethusdt"));
+        assert!(!map.contains_key("luna"));
+        let map = registry.base_asset_map(&["ethbtc".to_string()]);
+        assert_eq!(map.get("eth").map(String::as_str), Some("ethbtc"));
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Upper bound on trade sizes kept per symbol for percentile/average calculations.
+const MAX_SAMPLES_PER_SYMBOL: usize = 20_000;
+/// Shortest interval between percentile re-sorts, whatever `BIG_TRADE_RECALC_SECS` says.
+const MIN_RECALC_MS: u64 = 1_000;
+
+/// How a symbol's big-trade quantity threshold is derived.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BigTradeRule {
+    /// Fixed base-asset quantity (`big_trade_qty`).
+    #[default]
+    FixedQty,
+    /// Quote notional; the quantity threshold follows the current price.
+    Notional(f64),
+    /// Percentile (0-100) of recent trade sizes.
+    Percentile(f64),
+    /// Multiple of the recent average trade size.
+    VolumeMultiple(f64),
+}
+
+impl BigTradeRule {
+    /// Parses `qty`, `notional:250000`, `percentile:99` or `volume_multiple:5`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim().to_lowercase();
+        let (mode, value) = match raw.split_once(':') {
+            Some((mode, value)) => (mode.trim(), value.trim().parse::<f64>().ok()),
+            None => (raw.as_str(), None),
+        };
+        let value = value.filter(|v| v.is_finite() && *v > 0.0);
+
+        match mode {
+            "qty" | "fixed" => Some(Self::FixedQty),
+            "notional" => value.map(Self::Notional),
+            "percentile" | "pct" => value.filter(|v| *v <= 100.0).map(Self::Percentile),
+            "volume_multiple" | "avg_multiple" => value.map(Self::VolumeMultiple),
+            _ => None,
+        }
+    }
+
+    pub fn as_label(&self) -> String {
+        match self {
+            Self::FixedQty => "qty".to_string(),
+            Self::Notional(v) => format!("notional:{v}"),
+            Self::Percentile(v) => format!("percentile:{v}"),
+            Self::VolumeMultiple(v) => format!("volume_multiple:{v}"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct SymbolSamples {
+    trades: VecDeque<(u64, f64)>,
+    qty_sum: f64,
+    cached_percentile: Option<f64>,
+    last_recalc_ms: Option<u64>,
+}
+
+impl SymbolSamples {
+    fn evict(&mut self, now_ms: u64, window_ms: u64) {
+        while let Some((ts, qty)) = self.trades.front().copied() {
+            if now_ms.saturating_sub(ts) <= window_ms && self.trades.len() <= MAX_SAMPLES_PER_SYMBOL
+            {
+                break;
+            }
+            self.trades.pop_front();
+            self.qty_sum -= qty;
+        }
+    }
+
+    fn percentile(&self, pct: f64) -> Option<f64> {
+        let mut sizes: Vec<f64> = self.trades.iter().map(|(_, qty)| *qty).collect();
+        sizes.sort_unstable_by(f64::total_cmp);
+        let last = sizes.len().checked_sub(1)?;
+        let rank = ((pct / 100.0) * last as f64).round() as usize;
+        sizes.get(rank.min(last)).copied()
+    }
+}
+
+/// Per-symbol big-trade thresholds that follow price and the recent trade size distribution.
+///
+/// Until a symbol has `min_samples` trades in the window, adaptive rules fall back to the
+/// configured fixed quantity.
+#[derive(Debug)]
+pub struct AdaptiveThresholds {
+    window_ms: u64,
+    recalc_ms: u64,
+    min_samples: usize,
+    by_symbol: HashMap<String, SymbolSamples>,
+}
+
+impl AdaptiveThresholds {
+    pub fn new(window_secs: u64, recalc_interval_secs: u64, min_samples: usize) -> Self {
+        Self {
+            window_ms: window_secs.max(1).saturating_mul(1_000),
+            recalc_ms: recalc_interval_secs
+                .saturating_mul(1_000)
+                .max(MIN_RECALC_MS),
+            min_samples: min_samples.max(1),
+            by_symbol: HashMap::new(),
+        }
+    }
+
+    /// Drops all state for a symbol that left the watched universe.
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        self.by_symbol.remove(&symbol.to_lowercase());
+    }
+
+    /// Returns the quantity threshold for this trade, then records the trade. The trade itself
+    /// never moves its own threshold. Only the percentile and volume-multiple rules keep
+    /// samples; fixed and notional rules record nothing.
+    pub fn observe(
+        &mut self,
+        symbol: &str,
+        timestamp_ms: u64,
+        price: f64,
+        qty: f64,
+        rule: BigTradeRule,
+        fallback_qty: f64,
+    ) -> f64 {
+        match rule {
+            BigTradeRule::FixedQty => fallback_qty,
+            BigTradeRule::Notional(notional) if price.is_finite() && price > 0.0 => {
+                notional / price
+            }
+            BigTradeRule::Notional(_) => fallback_qty,
+            BigTradeRule::Percentile(pct) => {
+                let recalc_ms = self.recalc_ms;
+                self.record(symbol, timestamp_ms, qty, fallback_qty, |samples| {
+                    let due = samples
+                        .last_recalc_ms
+                        .is_none_or(|last| timestamp_ms.saturating_sub(last) >= recalc_ms);
+                    if due || samples.cached_percentile.is_none() {
+                        samples.cached_percentile = samples.percentile(pct);
+                        samples.last_recalc_ms = Some(timestamp_ms);
+                    }
+                    samples.cached_percentile.unwrap_or(fallback_qty)
+                })
+            }
+            BigTradeRule::VolumeMultiple(multiple) => {
+                self.record(symbol, timestamp_ms, qty, fallback_qty, |samples| {
+                    samples.qty_sum.max(0.0) / samples.trades.len() as f64 * multiple
+                })
+            }
+        }
+    }
+
+    /// Threshold from the symbol's samples once warmed up, before this trade is added.
+    fn record(
+        &mut self,
+        symbol: &str,
+        timestamp_ms: u64,
+        qty: f64,
+        fallback_qty: f64,
+        threshold: impl FnOnce(&mut SymbolSamples) -> f64,
+    ) -> f64 {
+        let samples = self.by_symbol.entry(symbol.to_lowercase()).or_default();
+        samples.evict(timestamp_ms, self.window_ms);
+        let threshold = if samples.trades.len() >= self.min_samples {
+            threshold(samples)
+        } else {
+            fallback_qty
+        };
+
+        if qty.is_finite() && qty > 0.0 {
+            samples.trades.push_back((timestamp_ms, qty));
+            samples.qty_sum += qty;
+        }
+
+        threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rules() {
+        assert_eq!(BigTradeRule::parse("qty"), Some(BigTradeRule::FixedQty));
+        assert_eq!(
+            BigTradeRule::parse("Notional:250000"),
+            Some(BigTradeRule::Notional(250_000.0))
+        );
+        assert_eq!(
+            BigTradeRule::parse("percentile:99"),
+            Some(BigTradeRule::Percentile(99.0))
+        );
+        assert_eq!(
+            BigTradeRule::parse("volume_multiple:5"),
+            Some(BigTradeRule::VolumeMultiple(5.0))
+        );
+        assert_eq!(BigTradeRule::parse("percentile:150"), None);
+        assert_eq!(BigTradeRule::parse("notional"), None);
+        assert_eq!(BigTradeRule::parse("bogus:1"), None);
+    }
+
+    #[test]
+    fn notional_rule_follows_price() {
+        let mut thresholds = AdaptiveThresholds::new(60, 10, 10);
+        let rule = BigTradeRule::Notional(100_000.0);
+        let at_50k = thresholds.observe("btcusdt", 0, 50_000.0, 0.1, rule, 20.0);
+        let at_25k = thresholds.observe("btcusdt", 1, 25_000.0, 0.1, rule, 20.0);
+        assert!((at_50k - 2.0).abs() < 1e-9);
+        assert!((at_25k - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stateless_rules_keep_no_samples() {
+        let mut thresholds = AdaptiveThresholds::new(60, 10, 10);
+        for t in 0..1_000 {
+            thresholds.observe("btcusdt", t, 50_000.0, 0.1, BigTradeRule::FixedQty, 20.0);
+            thresholds.observe(
+                "ethusdt",
+                t,
+                3_000.0,
+                0.1,
+                BigTradeRule::Notional(100_000.0),
+                20.0,
+            );
+        }
+        assert!(thresholds.by_symbol.is_empty());
+    }
+
+    #[test]
+    fn percentile_is_recalculated_at_most_once_per_second() {
+        let mut thresholds = AdaptiveThresholds::new(60, 0, 3);
+        let pct = BigTradeRule::Percentile(100.0);
+        for t in 0..3 {
+            thresholds.observe("solusdt", t, 150.0, 1.0, pct, 20.0);
+        }
+        assert_eq!(
+            thresholds.observe("solusdt", 3, 150.0, 50.0, pct, 20.0),
+            1.0
+        );
+        // The 50-lot trade is in the window, but the cached value is less than 1s old.
+        assert_eq!(
+            thresholds.observe("solusdt", 999, 150.0, 1.0, pct, 20.0),
+            1.0
+        );
+        assert_eq!(
+            thresholds.observe("solusdt", 1_003, 150.0, 1.0, pct, 20.0),
+            50.0
+        );
+    }
+
+    #[test]
+    fn adaptive_rules_fall_back_until_warm_and_then_track_distribution() {
+        let mut thresholds = AdaptiveThresholds::new(60, 0, 100);
+        let pct = BigTradeRule::Percentile(90.0);
+        assert_eq!(
+            thresholds.observe("ethusdt", 0, 3_000.0, 1.0, pct, 20.0),
+            20.0
+        );
+        for i in 1..=100_u64 {
+            thresholds.observe("ethusdt", i, 3_000.0, i as f64, pct, 20.0);
+        }
+        let p90 = thresholds.observe("ethusdt", 200, 3_000.0, 1.0, pct, 20.0);
+        assert!((89.0..=92.0).contains(&p90), "p90={p90}");
+
+        let avg_x3 = thresholds.observe(
+            "ethusdt",
+            201,
+            3_000.0,
+            1.0,
+            BigTradeRule::VolumeMultiple(3.0),
+            20.0,
+        );
+        assert!(avg_x3 > 140.0 && avg_x3 < 155.0, "avg_x3={avg_x3}");
+
+        // Everything above has aged out of the 60s window.
+        let cold = thresholds.observe("ethusdt", 120_000, 3_000.0, 1.0, pct, 20.0);
+        assert_eq!(cold, 20.0);
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::{HashMap, VecDeque};
+
+pub const FLOW_WINDOWS_MS: [(&str, u64); 3] =
+    [("1m", 60_000), ("5m", 5 * 60_000), ("15m", 15 * 60_000)];
+
+#[derive(Debug, Clone)]
+struct FlowTrade {
+    timestamp_ms: u64,
+    qty: f64,
+    notional: f64,
+    /// `true` when the aggressor (taker) was the buyer, i.e. `buyer_maker == false`.
+    taker_buy: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowWindowStats {
+    pub label: &'static str,
+    pub window_ms: u64,
+    pub buy_notional: f64,
+    pub sell_notional: f64,
+    /// Taker buy qty minus taker sell qty inside the window (base asset).
+    pub volume_delta: f64,
+    pub trade_count: usize,
+    pub avg_trade_notional: f64,
+    /// `(buy - sell) / (buy + sell) * 100`, in [-100, 100].
+    pub imbalance_pct: f64,
+}
+
+impl FlowWindowStats {
+    pub fn total_notional(&self) -> f64 {
+        self.buy_notional + self.sell_notional
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowSnapshot {
+    pub symbol: String,
+    pub timestamp_ms: u64,
+    /// Cumulative volume delta since the tracker first saw this symbol (base asset).
+    pub cvd: f64,
+    pub windows: Vec<FlowWindowStats>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowImbalance {
+    pub label: &'static str,
+    pub imbalance_pct: f64,
+    pub total_notional: f64,
+}
+
+impl FlowSnapshot {
+    /// Returns the strongest window whose taker imbalance and notional pass the thresholds.
+    pub fn strongest_imbalance(
+        &self,
+        min_imbalance_pct: f64,
+        min_notional: f64,
+    ) -> Option<FlowImbalance> {
+        self.windows
+            .iter()
+            .filter(|w| {
+                w.imbalance_pct.abs() >= min_imbalance_pct && w.total_notional() >= min_notional
+            })
+            // `rev` so ties resolve to the shortest window.
+            .rev()
+            .max_by(|a, b| a.imbalance_pct.abs().total_cmp(&b.imbalance_pct.abs()))
+            .map(|w| FlowImbalance {
+                label: w.label,
+                imbalance_pct: w.imbalance_pct,
+                total_notional: w.total_notional(),
+            })
+    }
+}
+
+#[derive(Debug, Default)]
+struct SymbolFlow {
+    trades: VecDeque<FlowTrade>,
+    cvd: f64,
+    last_emit_ms: Option<u64>,
+}
+
+/// Rolling per-symbol taker flow over 1m/5m/15m windows.
+#[derive(Debug)]
+pub struct TradeFlowTracker {
+    emit_interval_ms: u64,
+    by_symbol: HashMap<String, SymbolFlow>,
+}
+
+impl TradeFlowTracker {
+    pub fn new(emit_interval_secs: u64) -> Self {
+        Self {
+            emit_interval_ms: emit_interval_secs.max(1).saturating_mul(1_000),
+            by_symbol: HashMap::new(),
+        }
+    }
+
+    /// Drops all state for a symbol that left the watched universe.
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        self.by_symbol.remove(&symbol.to_lowercase());
+    }
+
+    /// Records one aggregated trade. Non-finite or non-positive prices/quantities are ignored.
+    pub fn record(
+        &mut self,
+        symbol: &str,
+        timestamp_ms: u64,
+        price: f64,
+        qty: f64,
+        buyer_maker: bool,
+    ) {
+        if !price.is_finite() || !qty.is_finite() || price <= 0.0 || qty <= 0.0 {
+            return;
+        }
+
+        let flow = self.by_symbol.entry(symbol.to_lowercase()).or_default();
+        let taker_buy = !buyer_maker;
+        flow.cvd += if taker_buy { qty } else { -qty };
+        flow.trades.push_back(FlowTrade {
+            timestamp_ms,
+            qty,
+            notional: price * qty,
+            taker_buy,
+        });
+
+        let max_window_ms = FLOW_WINDOWS_MS[FLOW_WINDOWS_MS.len() - 1].1;
+        while let Some(front) = flow.trades.front() {
+            if timestamp_ms.saturating_sub(front.timestamp_ms) > max_window_ms {
+                flow.trades.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    pub fn snapshot(&self, symbol: &str, now_ms: u64) -> Option<FlowSnapshot> {
+        let flow = self.by_symbol.get(&symbol.to_lowercase())?;
+
+        let windows = FLOW_WINDOWS_MS
+            .iter()
+            .map(|(label, window_ms)| {
+                let mut stats = FlowWindowStats {
+                    label,
+                    window_ms: *window_ms,
+                    buy_notional: 0.0,
+                    sell_notional: 0.0,
+                    volume_delta: 0.0,
+                    trade_count: 0,
+                    avg_trade_notional: 0.0,
+                    imbalance_pct: 0.0,
+                };
+
+                for trade in flow
+                    .trades
+                    .iter()
+                    .filter(|t| now_ms.saturating_sub(t.timestamp_ms) <= *window_ms)
+                {
+                    stats.trade_count += 1;
+                    if trade.taker_buy {
+                        stats.buy_notional += trade.notional;
+                        stats.volume_delta += trade.qty;
+                    } else {
+                        stats.sell_notional += trade.notional;
+                        stats.volume_delta -= trade.qty;
+                    }
+                }
+
+                let total = stats.total_notional();
+                if stats.trade_count > 0 {
+                    stats.avg_trade_notional = total / stats.trade_count as f64;
+                }
+                if total > 0.0 {
+                    stats.imbalance_pct = ((stats.buy_notional - stats.sell_notional) / total
+                        * 100.0)
+                        .clamp(-100.0, 100.0);
+                }
+                stats
+            })
+            .collect();
+
+        Some(FlowSnapshot {
+            symbol: symbol.to_lowercase(),
+            timestamp_ms: now_ms,
+            cvd: flow.cvd,
+            windows,
+        })
+    }
+
+    /// Returns a snapshot when the symbol's emit interval has elapsed since the last one.
+    pub fn snapshot_if_due(&mut self, symbol: &str, now_ms: u64) -> Option<FlowSnapshot> {
+        let key = symbol.to_lowercase();
+        let flow = self.by_symbol.get_mut(&key)?;
+        if let Some(last) = flow.last_emit_ms
+            && now_ms.saturating_sub(last) < self.emit_interval_ms
+        {
+            return None;
+        }
+        flow.last_emit_ms = Some(now_ms);
+        self.snapshot(&key, now_ms)
+    }
+}
+
+pub fn format_flow_log(snapshot: &FlowSnapshot) -> String {
+    let windows = snapshot
+        .windows
+        .iter()
+        .map(|w| {
+            format!(
+                "{}: buy={:.0} sell={:.0} delta={:+.4} imb={:+.1}% trades={} avg={:.0}",
+                w.label,
+                w.buy_notional,
+                w.sell_notional,
+                w.volume_delta,
+                w.imbalance_pct,
+                w.trade_count,
+                w.avg_trade_notional
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" | ");
+
+    format!(
+        "[FLOW] {} cvd={:+.4} | {}",
+        snapshot.symbol.to_uppercase(),
+        snapshot.cvd,
+        windows
+    )
+}
+
+pub fn format_flow_imbalance_log(snapshot: &FlowSnapshot, imbalance: &FlowImbalance) -> String {
+    let side = if imbalance.imbalance_pct >= 0.0 {
+        "BUY"
+    } else {
+        "SELL"
+    };
+    format!(
+        "[FLOW][IMBALANCE] {} {} taker imbalance={:+.1}% window={} notional={:.0} cvd={:+.4}",
+        snapshot.symbol.to_uppercase(),
+        side,
+        imbalance.imbalance_pct,
+        imbalance.label,
+        imbalance.total_notional,
+        snapshot.cvd
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_taker_flow_per_window() {
+        let mut tracker = TradeFlowTracker::new(10);
+        // taker buy 2 @ 100, taker sell 1 @ 100 six minutes ago, taker buy 1 @ 100 now
+        tracker.record("BTCUSDT", 0, 100.0, 2.0, false);
+        tracker.record("BTCUSDT", 540_000, 100.0, 1.0, true);
+        tracker.record("BTCUSDT", 900_000, 100.0, 1.0, false);
+
+        let snap = tracker.snapshot("btcusdt", 900_000).expect("snapshot");
+        assert_eq!(snap.cvd, 2.0);
+
+        let one_min = &snap.windows[0];
+        assert_eq!(one_min.trade_count, 1);
+        assert_eq!(one_min.imbalance_pct, 100.0);
+
+        let fifteen = &snap.windows[2];
+        assert_eq!(fifteen.trade_count, 3);
+        assert_eq!(fifteen.buy_notional, 300.0);
+        assert_eq!(fifteen.sell_notional, 100.0);
+        assert_eq!(fifteen.volume_delta, 2.0);
+        assert!((fifteen.avg_trade_notional - 133.333).abs() < 0.01);
+        assert_eq!(fifteen.imbalance_pct, 50.0);
+    }
+
+    #[test]
+    fn drops_trades_older_than_longest_window_but_keeps_cvd() {
+        let mut tracker = TradeFlowTracker::new(10);
+        tracker.record("ethusdt", 0, 10.0, 5.0, true);
+        tracker.record("ethusdt", 20 * 60_000, 10.0, 1.0, false);
+
+        let snap = tracker.snapshot("ethusdt", 20 * 60_000).expect("snapshot");
+        assert_eq!(snap.windows[2].trade_count, 1);
+        assert_eq!(snap.cvd, -4.0);
+    }
+
+    #[test]
+    fn snapshot_if_due_respects_emit_interval() {
+        let mut tracker = TradeFlowTracker::new(10);
+        tracker.record("solusdt", 0, 10.0, 1.0, false);
+
+        assert!(tracker.snapshot_if_due("solusdt", 0).is_some());
+        assert!(tracker.snapshot_if_due("solusdt", 9_999).is_none());
+        assert!(tracker.snapshot_if_due("solusdt", 10_000).is_some());
+        assert!(tracker.snapshot_if_due("xrpusdt", 10_000).is_none());
+    }
+
+    #[test]
+    fn strongest_imbalance_applies_thresholds() {
+        let mut tracker = TradeFlowTracker::new(10);
+        tracker.record("btcusdt", 0, 100.0, 1.0, true);
+        tracker.record("btcusdt", 500_000, 100.0, 9.0, false);
+
+        let snap = tracker.snapshot("btcusdt", 500_000).expect("snapshot");
+        let hit = snap.strongest_imbalance(60.0, 500.0).expect("imbalance");
+        assert_eq!(hit.label, "1m");
+        assert_eq!(hit.imbalance_pct, 100.0);
+
+        assert!(snap.strongest_imbalance(60.0, 5_000.0).is_none());
+        assert!(format_flow_log(&snap).starts_with("[FLOW] BTCUSDT cvd=+8.0000"));
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use serde::Deserialize;
+
+use crate::config::{SymbolConfig, UniverseConfig};
+use crate::decimal::Decimal;
+use crate::symbols::SymbolRegistry;
+
+/// All-market 24h mini ticker stream.
+pub const MINI_TICKER_STREAM: &str = "!miniTicker@arr";
+
+/// Tickers not refreshed for this long (relative to the newest one) are left out of the
+/// ranking, so halted or delisted pairs age out.
+pub(crate) const STALE_TICKER_MS: u64 = 15 * 60 * 1_000;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MiniTicker {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "c")]
+    pub close: Decimal,
+    /// Price 24h ago.
+    #[serde(rename = "o")]
+    pub open: Decimal,
+    /// 24h quote-asset volume.
+    #[serde(rename = "q")]
+    pub quote_volume: Decimal,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UniverseChange {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl UniverseChange {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TickerVolume {
+    quote_volume: f64,
+    event_ms: u64,
+}
+
+/// Ranks pairs by 24h quote volume and keeps a top-N set with hysteresis: a pair joins when
+/// it ranks within `top_n` and only leaves once it drops below `exit_rank`.
+///
+/// Pinned symbols (the static `SYMBOLS` list) count towards the ranking but are never
+/// added or removed.
+#[derive(Debug)]
+pub struct UniverseRanker {
+    top_n: usize,
+    exit_rank: usize,
+    quote_suffix: String,
+    rebalance_ms: u64,
+    warmup_ms: u64,
+    exclude: HashSet<String>,
+    pinned: HashSet<String>,
+    /// Trading pairs with the configured quote asset, when a registry is loaded.
+    allowed: Option<HashSet<String>>,
+    volumes: HashMap<String, TickerVolume>,
+    members: BTreeSet<String>,
+    first_seen_ms: Option<u64>,
+    last_rebalance_ms: Option<u64>,
+}
+
+impl UniverseRanker {
+    pub fn new(config: &UniverseConfig, pinned: &[String]) -> Self {
+        Self {
+            top_n: config.top_n.max(1),
+            exit_rank: config.exit_rank.max(config.top_n.max(1)),
+            quote_suffix: config.quote_asset.to_lowercase(),
+            rebalance_ms: config.rebalance_interval_secs.max(1).saturating_mul(1_000),
+            warmup_ms: config.warmup_secs.saturating_mul(1_000),
+            exclude: config.exclude.iter().map(|s| s.to_lowercase()).collect(),
+            pinned: pinned.iter().map(|s| s.to_lowercase()).collect(),
+            allowed: None,
+            volumes: HashMap::new(),
+            members: BTreeSet::new(),
+            first_seen_ms: None,
+            last_rebalance_ms: None,
+        }
+    }
+
+    /// Restricts the universe to pairs the registry lists as trading with the quote asset.
+    pub fn with_registry(mut self, registry: &SymbolRegistry) -> Self {
+        let quote = self.quote_suffix.clone();
+        self.allowed = Some(
+            registry
+                .iter()
+                .filter(|info| info.is_trading() && info.quote_asset.eq_ignore_ascii_case(&quote))
+                .map(|info| info.symbol.clone())
+                .collect(),
+        );
+        self
+    }
+
+    fn is_eligible(&self, symbol: &str) -> bool {
+        if self.exclude.contains(symbol) {
+            return false;
+        }
+        match &self.allowed {
+            Some(allowed) => allowed.contains(symbol),
+            None => symbol.len() > self.quote_suffix.len() && symbol.ends_with(&self.quote_suffix),
+        }
+    }
+
+    pub fn observe(&mut self, tickers: &[MiniTicker]) {
+        for ticker in tickers {
+            let symbol = ticker.symbol.to_lowercase();
+            if !self.is_eligible(&symbol) {
+                continue;
+            }
+            self.first_seen_ms.get_or_insert(ticker.event_time);
+            self.volumes.insert(
+                symbol,
+                TickerVolume {
+                    quote_volume: ticker.quote_volume.to_f64(),
+                    event_ms: ticker.event_time,
+                },
+            );
+        }
+    }
+
+    /// Eligible symbols by descending quote volume.
+    pub fn ranking(&self) -> Vec<(String, f64)> {
+        let newest = self.volumes.values().map(|v| v.event_ms).max().unwrap_or(0);
+        let mut ranked: Vec<(String, f64)> = self
+            .volumes
+            .iter()
+            .filter(|(_, v)| newest.saturating_sub(v.event_ms) <= STALE_TICKER_MS)
+            .map(|(symbol, v)| (symbol.clone(), v.quote_volume))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    pub fn rebalance(&mut self) -> UniverseChange {
+        let ranked = self.ranking();
+        let rank_of: HashMap<&str, usize> = ranked
+            .iter()
+            .enumerate()
+            .map(|(index, (symbol, _))| (symbol.as_str(), index + 1))
+            .collect();
+
+        let removed: Vec<String> = self
+            .members
+            .iter()
+            .filter(|symbol| {
+                rank_of
+                    .get(symbol.as_str())
+                    .is_none_or(|rank| *rank > self.exit_rank)
+            })
+            .cloned()
+            .collect();
+        let added: Vec<String> = ranked
+            .iter()
+            .take(self.top_n)
+            .map(|(symbol, _)| symbol)
+            .filter(|symbol| !self.members.contains(*symbol) && !self.pinned.contains(*symbol))
+            .cloned()
+            .collect();
+
+        for symbol in &removed {
+            self.members.remove(symbol);
+        }
+        self.members.extend(added.iter().cloned());
+        UniverseChange { added, removed }
+    }
+
+    /// Rebalances once the warmup has passed and then every rebalance interval, using the
+    /// ticker event clock.
+    pub fn rebalance_if_due(&mut self, now_ms: u64) -> Option<UniverseChange> {
+        let first_seen = self.first_seen_ms?;
+        let due = match self.last_rebalance_ms {
+            None => now_ms.saturating_sub(first_seen) >= self.warmup_ms,
+            Some(last) => now_ms.saturating_sub(last) >= self.rebalance_ms,
+        };
+        if !due {
+            return None;
+        }
+        self.last_rebalance_ms = Some(now_ms);
+        Some(self.rebalance())
+    }
+
+    /// Dynamically added symbols (excludes pinned ones).
+    pub fn members(&self) -> impl Iterator<Item = &String> {
+        self.members.iter()
+    }
+}
+
+/// Which per-symbol streams the pipeline subscribes to.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StreamSet {
+    pub depth: bool,
+    pub kline: bool,
+    pub funding: bool,
+}
+
+impl StreamSet {
+    pub fn streams_for(&self, symbol: &str) -> Vec<String> {
+        let symbol = symbol.to_lowercase();
+        let mut streams = vec![format!("{symbol}@aggTrade")];
+        if self.depth {
+            streams.push(format!("{symbol}@depth@100ms"));
+        }
+        if self.kline {
+            streams.push(format!("{symbol}@kline_4h"));
+        }
+        if self.funding {
+            streams.push(format!("{symbol}@markPrice@1s"));
+        }
+        streams
+    }
+
+    pub fn streams_for_all<'a>(
+        &self,
+        symbols: impl IntoIterator<Item = &'a String>,
+    ) -> Vec<String> {
+        symbols
+            .into_iter()
+            .flat_map(|symbol| self.streams_for(symbol))
+            .collect()
+    }
+}
+
+/// A `SUBSCRIBE` / `UNSUBSCRIBE` request for a live combined-stream connection.
+pub fn subscription_frame(method: &str, streams: &[String], id: u64) -> String {
+    serde_json::json!({ "method": method, "params": streams, "id": id }).to_string()
+}
+
+/// Symbol configs shared by every shard worker. Readers take a cheap `Arc` snapshot per
+/// message; the universe replaces the map on change (copy-on-write).
+#[derive(Debug, Clone, Default)]
+pub struct SharedSymbolConfigs {
+    inner: Arc<RwLock<Arc<HashMap<String, SymbolConfig>>>>,
+}
+
+impl SharedSymbolConfigs {
+    pub fn new(configs: HashMap<String, SymbolConfig>) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Arc::new(configs))),
+        }
+    }
+
+    pub fn snapshot(&self) -> Arc<HashMap<String, SymbolConfig>> {
+        self.inner.read().expect("symbol configs poisoned").clone()
+    }
+
+    pub fn insert(&self, config: SymbolConfig) {
+        let mut guard = self.inner.write().expect("symbol configs poisoned");
+        let mut next = HashMap::clone(&guard);
+        next.insert(config.symbol.clone(), config);
+        *guard = Arc::new(next);
+    }
+
+    pub fn remove(&self, symbol: &str) -> bool {
+        let mut guard = self.inner.write().expect("symbol configs poisoned");
+        if !guard.contains_key(symbol) {
+            return false;
+        }
+        let mut next = HashMap::clone(&guard);
+        next.remove(symbol);
+        *guard = Arc::new(next);
+        true
+    }
+}
+
+pub fn format_universe_log(change: &UniverseChange, member_count: usize) -> String {
+    let list = |symbols: &[String]| {
+        if symbols.is_empty() {
+            "-".to_string()
+        } else {
+            symbols
+                .iter()
+                .map(|s| s.to_uppercase())
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+    };
+    format!(
+        "[UNIVERSE] added={} removed={} dynamic_members={}",
+        list(&change.added),
+        list(&change.removed),
+        member_count
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(top_n: usize, exit_rank: usize) -> UniverseConfig {
+        UniverseConfig {
+            enabled: true,
+            top_n,
+            exit_rank,
+            quote_asset: "USDT".to_string(),
+            rebalance_interval_secs: 60,
+            warmup_secs: 5,
+            exclude: vec!["usdcusdt".to_string()],
+        }
+    }
+
+    fn ticker(symbol: &str, quote_volume: &str, event_time: u64) -> MiniTicker {
+        MiniTicker {
+            symbol: symbol.to_string(),
+            event_time,
+            close: Decimal::ZERO,
+            open: Decimal::ZERO,
+            quote_volume: quote_volume.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn joins_top_n_and_leaves_only_past_exit_rank() {
+        let mut ranker = UniverseRanker::new(&config(2, 3), &["btcusdt".to_string()]);
+        ranker.observe(&[
+            ticker("BTCUSDT", "900", 1),
+            ticker("ETHUSDT", "800", 1),
+            ticker("SOLUSDT", "700", 1),
+            ticker("USDCUSDT", "5000", 1),
+            ticker("ETHBTC", "9999", 1),
+        ]);
+        // BTC is pinned, so only ETH is added from the top 2.
+        let change = ranker.rebalance();
+        assert_eq!(change.added, vec!["ethusdt".to_string()]);
+        assert!(change.removed.is_empty());
+
+        // ETH slips to rank 3: inside the exit band, so it stays and SOL joins.
+        ranker.observe(&[ticker("SOLUSDT", "850", 2), ticker("ETHUSDT", "840", 2)]);
+        let change = ranker.rebalance();
+        assert_eq!(change.added, vec!["solusdt".to_string()]);
+        assert!(change.removed.is_empty());
+
+        // ETH falls to rank 4, past the exit rank.
+        ranker.observe(&[ticker("DOGEUSDT", "845", 3)]);
+        let change = ranker.rebalance();
+        assert_eq!(change.removed, vec!["ethusdt".to_string()]);
+        assert!(change.added.is_empty());
+        assert_eq!(
+            ranker.members().cloned().collect::<Vec<_>>(),
+            vec!["solusdt".to_string()]
+        );
+    }
+
+    #[test]
+    fn rebalance_waits_for_warmup_then_interval() {
+        let mut ranker = UniverseRanker::new(&config(1, 1), &[]);
+        assert!(ranker.rebalance_if_due(0).is_none());
+        ranker.observe(&[ticker("ETHUSDT", "1", 1_000)]);
+        assert!(ranker.rebalance_if_due(3_000).is_none());
+        assert_eq!(
+            ranker.rebalance_if_due(6_000).map(|c| c.added),
+            Some(vec!["ethusdt".to_string()])
+        );
+        assert!(ranker.rebalance_if_due(30_000).is_none());
+        assert!(
+            ranker
+                .rebalance_if_due(66_000)
+                .is_some_and(|c| c.is_empty())
+        );
+    }
+
+    #[test]
+    fn stream_set_and_subscription_frames() {
+        let set = StreamSet {
+            depth: true,
+            kline: false,
+            funding: true,
+        };
+        assert_eq!(
+            set.streams_for("SOLUSDT"),
+            vec![
+                "solusdt@aggTrade",
+                "solusdt@depth@100ms",
+                "solusdt@markPrice@1s"
+            ]
+        );
+        assert_eq!(
+            subscription_frame("SUBSCRIBE", &["a@aggTrade".to_string()], 7),
+            r#"{"id":7,"method":"SUBSCRIBE","params":["a@aggTrade"]}"#
+        );
+    }
+
+    #[test]
+    fn universe_log_lists_changes_and_dynamic_member_count() {
+        let change = UniverseChange {
+            added: vec!["pepeusdt".to_string(), "wifusdt".to_string()],
+            removed: Vec::new(),
+        };
+        assert_eq!(
+            format_universe_log(&change, 30),
+            "[UNIVERSE] added=PEPEUSDT,WIFUSDT removed=- dynamic_members=30"
+        );
+    }
+}
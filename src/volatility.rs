@@ -0,0 +1,465 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+
+use crate::config::VolatilityConfig;
+
+const YEAR_MS: f64 = 365.0 * 24.0 * 3_600_000.0;
+
+/// Current-to-baseline volatility ratios separating the regimes.
+const CALM_BELOW_RATIO: f64 = 0.6;
+const ELEVATED_FROM_RATIO: f64 = 1.5;
+const EXTREME_FROM_RATIO: f64 = 2.5;
+
+/// Consecutive bar closes that must agree before the regime changes.
+const CONFIRM_BARS: u32 = 2;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VolRegime {
+    Calm,
+    #[default]
+    Normal,
+    Elevated,
+    Extreme,
+}
+
+impl VolRegime {
+    pub fn from_ratio(ratio: f64) -> Self {
+        if ratio < CALM_BELOW_RATIO {
+            Self::Calm
+        } else if ratio < ELEVATED_FROM_RATIO {
+            Self::Normal
+        } else if ratio < EXTREME_FROM_RATIO {
+            Self::Elevated
+        } else {
+            Self::Extreme
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Calm => "calm",
+            Self::Normal => "normal",
+            Self::Elevated => "elevated",
+            Self::Extreme => "extreme",
+        }
+    }
+
+    /// Multiplier applied to move thresholds when threshold scaling is enabled.
+    pub fn threshold_scale(&self) -> f64 {
+        match self {
+            Self::Calm => 0.8,
+            Self::Normal => 1.0,
+            Self::Elevated => 1.5,
+            Self::Extreme => 2.0,
+        }
+    }
+}
+
+/// Annualized realized volatility in percent from each estimator.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VolEstimate {
+    /// Close-to-close over the last window of trade-built bars.
+    pub close_to_close_pct: Option<f64>,
+    /// Parkinson (high/low range) over the same bars.
+    pub parkinson_pct: Option<f64>,
+    /// Sum of squared trade-to-trade log returns over the window's duration.
+    pub tick_pct: Option<f64>,
+    /// Close-to-close over closed klines from the kline stream.
+    pub kline_close_to_close_pct: Option<f64>,
+    /// Parkinson over closed klines from the kline stream.
+    pub kline_parkinson_pct: Option<f64>,
+    /// Same estimator as the regime, over all retained bars.
+    pub baseline_pct: Option<f64>,
+    /// Current over baseline volatility.
+    pub ratio: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VolRegimeChange {
+    pub symbol: String,
+    pub timestamp_ms: u64,
+    pub from: VolRegime,
+    pub to: VolRegime,
+    pub estimate: VolEstimate,
+}
+
+impl VolRegimeChange {
+    pub fn to_event_json(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("event".to_string(), "vol_regime".into());
+        }
+        value.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bar {
+    index: u64,
+    high: f64,
+    low: f64,
+    close: f64,
+}
+
+#[derive(Debug, Default)]
+struct SymbolVol {
+    open_bar: Option<Bar>,
+    bars: VecDeque<Bar>,
+    /// `(ts, squared log return)` per trade.
+    ticks: VecDeque<(u64, f64)>,
+    last_price: Option<f64>,
+    klines: VecDeque<Bar>,
+    kline_ms: u64,
+    regime: VolRegime,
+    pending: Option<(VolRegime, u32)>,
+}
+
+/// Per-symbol realized volatility from trades (bars and ticks) and closed klines, with a
+/// regime relative to the symbol's own longer-run baseline.
+#[derive(Debug)]
+pub struct VolatilityTracker {
+    bar_ms: u64,
+    window_bars: usize,
+    baseline_bars: usize,
+    scale_thresholds: bool,
+    symbols: HashMap<String, SymbolVol>,
+}
+
+impl VolatilityTracker {
+    pub fn new(config: &VolatilityConfig) -> Self {
+        let window_bars = config.window_bars.max(2);
+        Self {
+            bar_ms: config.bar_secs.max(1) * 1_000,
+            window_bars,
+            baseline_bars: config.baseline_bars.max(window_bars * 2),
+            scale_thresholds: config.scale_thresholds,
+            symbols: HashMap::new(),
+        }
+    }
+
+    /// Records a trade; returns a regime change confirmed on the bar this trade closes.
+    pub fn record_trade(
+        &mut self,
+        symbol: &str,
+        ts_ms: u64,
+        price: f64,
+    ) -> Option<VolRegimeChange> {
+        if !price.is_finite() || price <= 0.0 {
+            return None;
+        }
+        let symbol = symbol.to_lowercase();
+        let window_ms = self.window_ms();
+        let state = self.symbols.entry(symbol.clone()).or_default();
+
+        if let Some(prev) = state.last_price {
+            state.ticks.push_back((ts_ms, (price / prev).ln().powi(2)));
+        }
+        state.last_price = Some(price);
+        let cutoff = ts_ms.saturating_sub(window_ms);
+        while state.ticks.front().is_some_and(|(ts, _)| *ts < cutoff) {
+            state.ticks.pop_front();
+        }
+
+        let index = ts_ms / self.bar_ms;
+        let closed = match state.open_bar.as_mut() {
+            Some(bar) if bar.index == index => {
+                bar.high = bar.high.max(price);
+                bar.low = bar.low.min(price);
+                bar.close = price;
+                None
+            }
+            Some(bar) if bar.index > index => None,
+            _ => state.open_bar.replace(Bar {
+                index,
+                high: price,
+                low: price,
+                close: price,
+            }),
+        };
+        let bar = closed?;
+        state.bars.push_back(bar);
+        while state.bars.len() > self.baseline_bars {
+            state.bars.pop_front();
+        }
+
+        let estimate = self.estimate(&symbol)?;
+        let ratio = estimate.ratio?;
+        let state = self.symbols.get_mut(&symbol)?;
+        let candidate = VolRegime::from_ratio(ratio);
+        if candidate == state.regime {
+            state.pending = None;
+            return None;
+        }
+        let confirmations = match state.pending {
+            Some((pending, count)) if pending == candidate => count + 1,
+            _ => 1,
+        };
+        if confirmations < CONFIRM_BARS {
+            state.pending = Some((candidate, confirmations));
+            return None;
+        }
+        let from = std::mem::replace(&mut state.regime, candidate);
+        state.pending = None;
+        Some(VolRegimeChange {
+            symbol,
+            timestamp_ms: ts_ms,
+            from,
+            to: candidate,
+            estimate,
+        })
+    }
+
+    /// Records a closed kline from the kline stream.
+    pub fn record_kline(
+        &mut self,
+        symbol: &str,
+        open_ms: u64,
+        close_ms: u64,
+        high: f64,
+        low: f64,
+        close: f64,
+    ) {
+        if low <= 0.0 || high < low || close <= 0.0 {
+            return;
+        }
+        let window_bars = self.window_bars;
+        let state = self.symbols.entry(symbol.to_lowercase()).or_default();
+        state.kline_ms = close_ms.saturating_sub(open_ms) + 1;
+        if state.klines.back().is_some_and(|k| k.index >= open_ms) {
+            return;
+        }
+        state.klines.push_back(Bar {
+            index: open_ms,
+            high,
+            low,
+            close,
+        });
+        while state.klines.len() > window_bars + 1 {
+            state.klines.pop_front();
+        }
+    }
+
+    pub fn estimate(&self, symbol: &str) -> Option<VolEstimate> {
+        let state = self.symbols.get(&symbol.to_lowercase())?;
+        let bars: Vec<Bar> = state.bars.iter().copied().collect();
+        let recent = &bars[bars.len().saturating_sub(self.window_bars + 1)..];
+        let close_to_close_pct = close_to_close(recent, self.bar_ms as f64);
+        let parkinson_pct = parkinson(&recent[recent.len().min(1)..], self.bar_ms as f64);
+
+        let tick_pct = (state.ticks.len() >= 2).then(|| {
+            let variance: f64 = state.ticks.iter().map(|(_, sq)| sq).sum();
+            annualize(variance, self.window_ms() as f64)
+        });
+
+        let klines: Vec<Bar> = state.klines.iter().copied().collect();
+        let kline_ms = state.kline_ms.max(1) as f64;
+        let kline_close_to_close_pct = close_to_close(&klines, kline_ms);
+        let kline_parkinson_pct = parkinson(&klines, kline_ms);
+
+        // The baseline only means something once it spans well beyond the current window.
+        let baseline_pct = (bars.len() >= self.window_bars * 2)
+            .then(|| parkinson(&bars, self.bar_ms as f64))
+            .flatten();
+        let ratio = match (parkinson_pct, baseline_pct) {
+            (Some(current), Some(baseline)) if baseline > 0.0 => Some(current / baseline),
+            _ => None,
+        };
+
+        Some(VolEstimate {
+            close_to_close_pct,
+            parkinson_pct,
+            tick_pct,
+            kline_close_to_close_pct,
+            kline_parkinson_pct,
+            baseline_pct,
+            ratio,
+        })
+    }
+
+    pub fn regime(&self, symbol: &str) -> VolRegime {
+        self.symbols
+            .get(&symbol.to_lowercase())
+            .map(|s| s.regime)
+            .unwrap_or_default()
+    }
+
+    /// Threshold multiplier for the symbol's regime, or 1.0 when scaling is off.
+    pub fn threshold_scale(&self, symbol: &str) -> f64 {
+        if self.scale_thresholds {
+            self.regime(symbol).threshold_scale()
+        } else {
+            1.0
+        }
+    }
+
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        self.symbols.remove(&symbol.to_lowercase());
+    }
+
+    fn window_ms(&self) -> u64 {
+        self.bar_ms * self.window_bars as u64
+    }
+}
+
+/// Annualized volatility in percent from a variance accumulated over `span_ms`.
+fn annualize(variance: f64, span_ms: f64) -> f64 {
+    (variance * YEAR_MS / span_ms).sqrt() * 100.0
+}
+
+fn close_to_close(bars: &[Bar], bar_ms: f64) -> Option<f64> {
+    if bars.len() < 3 {
+        return None;
+    }
+    let returns = bars.len() - 1;
+    let sum_sq: f64 = bars
+        .windows(2)
+        .map(|pair| (pair[1].close / pair[0].close).ln().powi(2))
+        .sum();
+    Some(annualize(sum_sq / returns as f64, bar_ms))
+}
+
+fn parkinson(bars: &[Bar], bar_ms: f64) -> Option<f64> {
+    if bars.len() < 2 {
+        return None;
+    }
+    let mean_sq = bars
+        .iter()
+        .map(|bar| (bar.high / bar.low).ln().powi(2))
+        .sum::<f64>()
+        / bars.len() as f64;
+    Some(annualize(mean_sq / (4.0 * std::f64::consts::LN_2), bar_ms))
+}
+
+pub fn format_vol_regime_log(change: &VolRegimeChange) -> String {
+    let pct = |v: Option<f64>| {
+        v.map(|v| format!("{v:.0}%"))
+            .unwrap_or_else(|| "n/a".to_string())
+    };
+    format!(
+        "[VOL_REGIME] {} {} -> {} parkinson={} baseline={} ratio={:.2} c2c={} tick={} kline={}",
+        change.symbol.to_uppercase(),
+        change.from.as_str(),
+        change.to.as_str(),
+        pct(change.estimate.parkinson_pct),
+        pct(change.estimate.baseline_pct),
+        change.estimate.ratio.unwrap_or(0.0),
+        pct(change.estimate.close_to_close_pct),
+        pct(change.estimate.tick_pct),
+        pct(change.estimate.kline_parkinson_pct),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(scale_thresholds: bool) -> VolatilityConfig {
+        VolatilityConfig {
+            enabled: true,
+            bar_secs: 60,
+            window_bars: 5,
+            baseline_bars: 40,
+            scale_thresholds,
+        }
+    }
+
+    /// Two trades per one-minute bar: `close * (1 + range)` then `close`.
+    fn feed(
+        tracker: &mut VolatilityTracker,
+        from_bar: u64,
+        bars: u64,
+        range: f64,
+    ) -> Vec<VolRegimeChange> {
+        let mut changes = Vec::new();
+        for bar in from_bar..from_bar + bars {
+            let ts = bar * 60_000;
+            let close = if bar % 2 == 0 {
+                100.0
+            } else {
+                100.0 * (1.0 + range)
+            };
+            changes.extend(tracker.record_trade("BTCUSDT", ts, close * (1.0 + range)));
+            changes.extend(tracker.record_trade("BTCUSDT", ts + 30_000, close));
+        }
+        changes
+    }
+
+    #[test]
+    fn regime_moves_with_volatility_relative_to_baseline() {
+        let mut tracker = VolatilityTracker::new(&config(true));
+        assert!(feed(&mut tracker, 0, 30, 0.001).is_empty());
+        assert_eq!(tracker.regime("btcusdt"), VolRegime::Normal);
+        assert_eq!(tracker.threshold_scale("btcusdt"), 1.0);
+
+        let changes = feed(&mut tracker, 30, 6, 0.01);
+        let change = changes.first().expect("regime change");
+        assert_eq!(change.from, VolRegime::Normal);
+        assert!(matches!(
+            change.to,
+            VolRegime::Elevated | VolRegime::Extreme
+        ));
+        assert!(change.estimate.ratio.unwrap() > ELEVATED_FROM_RATIO);
+        assert!(tracker.threshold_scale("btcusdt") > 1.0);
+        assert!(format_vol_regime_log(change).starts_with("[VOL_REGIME] BTCUSDT normal -> "));
+        assert!(change.to_event_json().contains("\"event\":\"vol_regime\""));
+    }
+
+    #[test]
+    fn estimators_agree_on_scale_and_klines_are_tracked() {
+        let mut tracker = VolatilityTracker::new(&config(false));
+        feed(&mut tracker, 0, 12, 0.002);
+        for i in 0..6u64 {
+            let open = i * 14_400_000;
+            tracker.record_kline(
+                "btcusdt",
+                open,
+                open + 14_399_999,
+                102.0,
+                98.0,
+                100.0 + i as f64,
+            );
+        }
+        let estimate = tracker.estimate("BTCUSDT").expect("estimate");
+        let c2c = estimate.close_to_close_pct.unwrap();
+        let tick = estimate.tick_pct.unwrap();
+        assert!(c2c > 0.0 && tick > 0.0);
+        // Ticks see the intrabar swing too, so they read at least as high as closes.
+        assert!(tick >= c2c);
+        assert!(estimate.kline_parkinson_pct.unwrap() > 0.0);
+        assert!(estimate.kline_close_to_close_pct.unwrap() > 0.0);
+        assert_eq!(tracker.threshold_scale("btcusdt"), 1.0);
+    }
+
+    #[test]
+    fn regime_changes_only_after_consecutive_confirming_bars() {
+        let mut tracker = VolatilityTracker::new(&config(true));
+        feed(&mut tracker, 0, 30, 0.001);
+
+        // A bar closes on the next bar's first trade, so bar 31 closes the first wide bar and
+        // that one elevated close is only pending.
+        assert!(feed(&mut tracker, 30, 1, 0.01).is_empty());
+        assert!(feed(&mut tracker, 31, 1, 0.01).is_empty());
+        assert_eq!(tracker.regime("btcusdt"), VolRegime::Normal);
+
+        let changes = feed(&mut tracker, 32, 1, 0.01);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].from, VolRegime::Normal);
+        assert_eq!(changes[0].to, VolRegime::Elevated);
+        assert_eq!(tracker.regime("btcusdt"), VolRegime::Elevated);
+    }
+
+    #[test]
+    fn flat_baseline_has_no_ratio_and_keeps_the_regime() {
+        let mut tracker = VolatilityTracker::new(&config(true));
+        assert!(feed(&mut tracker, 0, 30, 0.0).is_empty());
+
+        let estimate = tracker.estimate("btcusdt").expect("estimate");
+        assert_eq!(estimate.baseline_pct, Some(0.0));
+        assert_eq!(estimate.parkinson_pct, Some(0.0));
+        assert_eq!(estimate.ratio, None);
+        assert_eq!(tracker.regime("btcusdt"), VolRegime::Normal);
+        assert_eq!(tracker.threshold_scale("btcusdt"), 1.0);
+    }
+}
@@ -0,0 +1,445 @@
+use std::collections::HashMap;
+
+use crate::decimal::{Decimal, SymbolPrecision};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TakerSide {
+    Buy,
+    Sell,
+}
+
+impl TakerSide {
+    /// `buyer_maker == true` means the seller was the aggressor.
+    pub fn from_buyer_maker(buyer_maker: bool) -> Self {
+        if buyer_maker { Self::Sell } else { Self::Buy }
+    }
+
+    pub fn as_label(self) -> &'static str {
+        match self {
+            Self::Buy => "BUY",
+            Self::Sell => "SELL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeCluster {
+    pub symbol: String,
+    pub side: TakerSide,
+    pub start_ms: u64,
+    pub last_ms: u64,
+    pub last_price: f64,
+    pub min_price: f64,
+    pub max_price: f64,
+    pub qty: f64,
+    pub notional: f64,
+    pub trade_count: usize,
+    emitted: bool,
+}
+
+impl TradeCluster {
+    pub fn span_ms(&self) -> u64 {
+        self.last_ms.saturating_sub(self.start_ms)
+    }
+}
+
+/// Sums same-side aggressive trades printed at the same or adjacent prices within a short
+/// window, so a whale splitting an order into many child trades is seen as one cluster.
+#[derive(Debug)]
+pub struct WhaleClusterDetector {
+    window_ms: u64,
+    adjacent_bps: f64,
+    open: HashMap<String, TradeCluster>,
+}
+
+impl WhaleClusterDetector {
+    pub fn new(window_ms: u64, adjacent_bps: f64) -> Self {
+        Self {
+            window_ms,
+            adjacent_bps: adjacent_bps.max(0.0),
+            open: HashMap::new(),
+        }
+    }
+
+    /// Drops all state for a symbol that left the watched universe.
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        self.open.remove(&symbol.to_lowercase());
+    }
+
+    /// Adds a trade and returns the cluster the first time its notional reaches
+    /// `threshold_notional`. A cluster alerts at most once.
+    pub fn push(
+        &mut self,
+        symbol: &str,
+        timestamp_ms: u64,
+        price: f64,
+        qty: f64,
+        buyer_maker: bool,
+        threshold_notional: f64,
+    ) -> Option<TradeCluster> {
+        if !price.is_finite() || !qty.is_finite() || price <= 0.0 || qty <= 0.0 {
+            return None;
+        }
+
+        let side = TakerSide::from_buyer_maker(buyer_maker);
+        let key = symbol.to_lowercase();
+        let extends = self.open.get(&key).is_some_and(|cluster| {
+            cluster.side == side
+                && timestamp_ms.saturating_sub(cluster.start_ms) <= self.window_ms
+                && ((price - cluster.last_price).abs() / cluster.last_price) * 10_000.0
+                    <= self.adjacent_bps
+        });
+
+        if !extends {
+            self.open.insert(
+                key.clone(),
+                TradeCluster {
+                    symbol: key.clone(),
+                    side,
+                    start_ms: timestamp_ms,
+                    last_ms: timestamp_ms,
+                    last_price: price,
+                    min_price: price,
+                    max_price: price,
+                    qty: 0.0,
+                    notional: 0.0,
+                    trade_count: 0,
+                    emitted: false,
+                },
+            );
+        }
+
+        let cluster = self.open.get_mut(&key)?;
+        cluster.last_ms = timestamp_ms;
+        cluster.last_price = price;
+        cluster.min_price = cluster.min_price.min(price);
+        cluster.max_price = cluster.max_price.max(price);
+        cluster.qty += qty;
+        cluster.notional += price * qty;
+        cluster.trade_count += 1;
+
+        if threshold_notional > 0.0 && !cluster.emitted && cluster.notional >= threshold_notional {
+            cluster.emitted = true;
+            return Some(cluster.clone());
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+impl BookSide {
+    pub fn as_label(self) -> &'static str {
+        match self {
+            Self::Bid => "BID",
+            Self::Ask => "ASK",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcebergSignal {
+    pub symbol: String,
+    pub side: BookSide,
+    /// The level exactly as quoted in the book.
+    pub price: Decimal,
+    pub refills: usize,
+    pub peak_qty: f64,
+    pub current_qty: f64,
+    pub first_refill_ms: u64,
+    pub last_refill_ms: u64,
+}
+
+#[derive(Debug)]
+struct LevelState {
+    qty: f64,
+    peak_qty: f64,
+    depleted: bool,
+    refills: Vec<u64>,
+    last_seen_ms: u64,
+    flagged_at_ms: Option<u64>,
+}
+
+/// Watches diff-depth updates for a price level that keeps getting consumed and then
+/// replenished to a similar size, a typical iceberg/hidden-size footprint.
+#[derive(Debug)]
+pub struct IcebergDetector {
+    window_ms: u64,
+    min_refills: usize,
+    /// A drop below `(1 - refill_ratio) * peak` counts as depleted; a return to
+    /// `refill_ratio * peak` afterwards counts as a refill.
+    refill_ratio: f64,
+    levels: HashMap<(String, BookSide, Decimal), LevelState>,
+}
+
+const MAX_TRACKED_LEVELS: usize = 20_000;
+
+impl IcebergDetector {
+    pub fn new(window_secs: u64, min_refills: usize, refill_ratio: f64) -> Self {
+        Self {
+            window_ms: window_secs.max(1).saturating_mul(1_000),
+            min_refills: min_refills.max(1),
+            refill_ratio: refill_ratio.clamp(0.05, 1.0),
+            levels: HashMap::new(),
+        }
+    }
+
+    /// Drops all tracked levels for a symbol that left the watched universe.
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        let symbol = symbol.to_lowercase();
+        self.levels
+            .retain(|(level_symbol, _, _), _| *level_symbol != symbol);
+    }
+
+    pub fn on_depth_levels(
+        &mut self,
+        symbol: &str,
+        side: BookSide,
+        levels: &[[Decimal; 2]],
+        event_ms: u64,
+    ) -> Vec<IcebergSignal> {
+        let mut signals = Vec::new();
+        let symbol = symbol.to_lowercase();
+
+        for level in levels {
+            let (price, qty) = (level[0].to_f64(), level[1].to_f64());
+            if !price.is_finite() || !qty.is_finite() || price <= 0.0 || qty < 0.0 {
+                continue;
+            }
+
+            let key = (symbol.clone(), side, level[0]);
+            // A removal of a level never seen says nothing about how much it held.
+            if qty == 0.0 && !self.levels.contains_key(&key) {
+                continue;
+            }
+            let state = self.levels.entry(key).or_insert(LevelState {
+                qty,
+                peak_qty: qty,
+                depleted: false,
+                refills: Vec::new(),
+                last_seen_ms: event_ms,
+                flagged_at_ms: None,
+            });
+
+            state.last_seen_ms = event_ms;
+            state
+                .refills
+                .retain(|ts| event_ms.saturating_sub(*ts) <= self.window_ms);
+
+            if !state.depleted && qty <= state.peak_qty * (1.0 - self.refill_ratio) {
+                state.depleted = true;
+            } else if state.depleted && state.peak_qty > 0.0 {
+                if qty >= state.peak_qty * self.refill_ratio {
+                    state.depleted = false;
+                    state.refills.push(event_ms);
+                }
+            } else if qty > state.peak_qty {
+                state.peak_qty = qty;
+            }
+            state.qty = qty;
+
+            let cooled_down = state
+                .flagged_at_ms
+                .is_none_or(|ts| event_ms.saturating_sub(ts) > self.window_ms);
+            if state.refills.len() >= self.min_refills && cooled_down {
+                state.flagged_at_ms = Some(event_ms);
+                signals.push(IcebergSignal {
+                    symbol: symbol.clone(),
+                    side,
+                    price: level[0],
+                    refills: state.refills.len(),
+                    peak_qty: state.peak_qty,
+                    current_qty: qty,
+                    first_refill_ms: state.refills.first().copied().unwrap_or(event_ms),
+                    last_refill_ms: event_ms,
+                });
+            }
+        }
+
+        if self.levels.len() > MAX_TRACKED_LEVELS {
+            let window_ms = self.window_ms;
+            self.levels
+                .retain(|_, state| event_ms.saturating_sub(state.last_seen_ms) <= window_ms);
+        }
+
+        signals
+    }
+}
+
+pub fn format_whale_log(
+    cluster: &TradeCluster,
+    threshold_notional: f64,
+    precision: &SymbolPrecision,
+) -> String {
+    format!(
+        "[WHALE] {} {} cluster notional={:.0} qty={} trades={} price={}..{} span={}ms threshold={:.0}",
+        cluster.symbol.to_uppercase(),
+        cluster.side.as_label(),
+        cluster.notional,
+        precision.format_qty(cluster.qty),
+        cluster.trade_count,
+        precision.format_price(cluster.min_price),
+        precision.format_price(cluster.max_price),
+        cluster.span_ms(),
+        threshold_notional
+    )
+}
+
+pub fn format_iceberg_log(signal: &IcebergSignal, precision: &SymbolPrecision) -> String {
+    format!(
+        "[ICEBERG] {} {} level={} refills={} peak_qty={} qty={} span={}ms",
+        signal.symbol.to_uppercase(),
+        signal.side.as_label(),
+        signal.price,
+        signal.refills,
+        precision.format_qty(signal.peak_qty),
+        precision.format_qty(signal.current_qty),
+        signal.last_refill_ms.saturating_sub(signal.first_refill_ms)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clusters_split_same_side_trades_and_alerts_once() {
+        let mut detector = WhaleClusterDetector::new(5_000, 5.0);
+        let mut alerts = Vec::new();
+        for i in 0..10_u64 {
+            let price = 100.0 + (i as f64) * 0.01;
+            if let Some(cluster) = detector.push("btcusdt", i * 100, price, 10.0, false, 5_000.0) {
+                alerts.push(cluster);
+            }
+        }
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].side, TakerSide::Buy);
+        assert_eq!(alerts[0].trade_count, 5);
+        assert!(alerts[0].notional >= 5_000.0);
+        assert!(
+            format_whale_log(&alerts[0], 5_000.0, &SymbolPrecision::default())
+                .starts_with("[WHALE] BTCUSDT BUY")
+        );
+    }
+
+    #[test]
+    fn side_flip_gap_or_price_jump_starts_new_cluster() {
+        let mut detector = WhaleClusterDetector::new(1_000, 5.0);
+        assert!(
+            detector
+                .push("ethusdt", 0, 100.0, 30.0, true, 5_000.0)
+                .is_none()
+        );
+        // Opposite side resets.
+        assert!(
+            detector
+                .push("ethusdt", 10, 100.0, 30.0, false, 5_000.0)
+                .is_none()
+        );
+        // Price jump of 100bps resets.
+        assert!(
+            detector
+                .push("ethusdt", 20, 101.0, 30.0, false, 5_000.0)
+                .is_none()
+        );
+        // Outside the window resets.
+        assert!(
+            detector
+                .push("ethusdt", 2_000, 101.0, 30.0, false, 5_000.0)
+                .is_none()
+        );
+        let cluster = detector
+            .push("ethusdt", 2_100, 101.0, 30.0, false, 5_000.0)
+            .expect("cluster crosses threshold");
+        assert_eq!(cluster.trade_count, 2);
+    }
+
+    fn level(price: &str, qty: &str) -> [Decimal; 2] {
+        [price.parse().unwrap(), qty.parse().unwrap()]
+    }
+
+    #[test]
+    fn flags_level_that_keeps_refilling() {
+        let mut detector = IcebergDetector::new(60, 3, 0.8);
+        let mut signals = Vec::new();
+        let mut ts = 0;
+        signals.extend(detector.on_depth_levels(
+            "btcusdt",
+            BookSide::Bid,
+            &[level("100.00", "10")],
+            ts,
+        ));
+        for _ in 0..3 {
+            ts += 1_000;
+            signals.extend(detector.on_depth_levels(
+                "btcusdt",
+                BookSide::Bid,
+                &[level("100.00", "1")],
+                ts,
+            ));
+            ts += 1_000;
+            signals.extend(detector.on_depth_levels(
+                "btcusdt",
+                BookSide::Bid,
+                &[level("100.00", "9.5")],
+                ts,
+            ));
+        }
+
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].refills, 3);
+        assert_eq!(signals[0].peak_qty, 10.0);
+        assert!(
+            format_iceberg_log(&signals[0], &SymbolPrecision::default())
+                .starts_with("[ICEBERG] BTCUSDT BID level=100.00 ")
+        );
+    }
+
+    #[test]
+    fn partial_refills_do_not_count() {
+        let mut detector = IcebergDetector::new(60, 2, 0.8);
+        let mut signals = Vec::new();
+        for (i, qty) in ["10", "1", "5", "1", "5"].iter().enumerate() {
+            signals.extend(detector.on_depth_levels(
+                "btcusdt",
+                BookSide::Ask,
+                &[level("101.00", qty)],
+                i as u64 * 1_000,
+            ));
+        }
+        assert!(signals.is_empty());
+    }
+
+    #[test]
+    fn removal_of_an_unseen_level_is_not_a_depletion() {
+        let mut detector = IcebergDetector::new(60, 1, 0.8);
+        let mut signals = Vec::new();
+        for (i, qty) in ["0", "10", "9"].iter().enumerate() {
+            signals.extend(detector.on_depth_levels(
+                "btcusdt",
+                BookSide::Bid,
+                &[level("100.00", qty)],
+                i as u64 * 1_000,
+            ));
+        }
+        assert!(signals.is_empty());
+
+        // A real drain and refill of the same level still counts.
+        for (i, qty) in ["0", "9.5"].iter().enumerate() {
+            signals.extend(detector.on_depth_levels(
+                "btcusdt",
+                BookSide::Bid,
+                &[level("100.00", qty)],
+                3_000 + i as u64 * 1_000,
+            ));
+        }
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].peak_qty, 10.0);
+    }
+}
@@ -4,6 +4,8 @@ use tokio::sync::{broadcast, oneshot};
 use tokio::time::{Duration, Instant, interval};
 use warp::ws::{Message, WebSocket};
 
+use crate::shutdown::Shutdown;
+
 pub type BroadcastRx = broadcast::Receiver<String>;
 pub type WsTx = futures_util::stream::SplitSink<WebSocket, Message>;
 
@@ -16,6 +18,7 @@ pub enum DisconnectReason {
     BroadcastForwardFailed,
     ReceiveError(String),
     ClientStreamEnded,
+    ServerShutdown,
 }
 
 impl DisconnectReason {
@@ -33,6 +36,7 @@ impl DisconnectReason {
             }
             Self::ReceiveError(err) => format!("error reading from websocket client: {err}"),
             Self::ClientStreamEnded => "websocket stream ended".to_string(),
+            Self::ServerShutdown => "server shutting down".to_string(),
         }
     }
 }
@@ -89,20 +93,69 @@ pub async fn handle_client_with_notifier(
     .await;
 }
 
+/// Like `handle_client`, but sends the client a `1001 Going Away` close frame once
+/// `shutdown` is triggered.
+pub async fn handle_client_with_shutdown(
+    ws: WebSocket,
+    tx: broadcast::Sender<String>,
+    shutdown: Shutdown,
+) {
+    run_client(
+        ws,
+        tx,
+        None,
+        Duration::from_secs(15),
+        Duration::from_secs(45),
+        Some(shutdown),
+    )
+    .await;
+}
+
 pub async fn handle_client_with_settings(
     ws: WebSocket,
     tx: broadcast::Sender<String>,
     disconnect_notifier: Option<oneshot::Sender<String>>,
     heartbeat_interval: Duration,
     pong_timeout: Duration,
+) {
+    run_client(
+        ws,
+        tx,
+        disconnect_notifier,
+        heartbeat_interval,
+        pong_timeout,
+        None,
+    )
+    .await;
+}
+
+async fn run_client(
+    ws: WebSocket,
+    tx: broadcast::Sender<String>,
+    disconnect_notifier: Option<oneshot::Sender<String>>,
+    heartbeat_interval: Duration,
+    pong_timeout: Duration,
+    shutdown: Option<Shutdown>,
 ) {
     let (mut ws_tx, mut ws_rx) = ws.split();
+    let shutdown_requested = async {
+        match &shutdown {
+            Some(shutdown) => shutdown.wait().await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(shutdown_requested);
     let mut rx = tx.subscribe();
     let mut heartbeat = interval(heartbeat_interval);
     let mut last_pong = Instant::now();
 
     let disconnect_reason = loop {
         tokio::select! {
+            _ = &mut shutdown_requested => {
+                let reason = DisconnectReason::ServerShutdown;
+                let _ = ws_tx.send(Message::close_with(1001u16, reason.describe())).await;
+                break reason;
+            }
             _ = heartbeat.tick() => {
                 if send_heartbeat(&mut ws_tx).await.is_err() {
                     break DisconnectReason::HeartbeatSendFailed;
@@ -142,7 +195,7 @@ pub async fn handle_client_with_settings(
     };
 
     let reason_text = disconnect_reason.describe();
-    println!("Client disconnected: {reason_text}");
+    log::info!(target: "ws", "client disconnected: {reason_text}");
     if let Some(notifier) = disconnect_notifier {
         let _ = notifier.send(reason_text);
     }
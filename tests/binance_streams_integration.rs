@@ -13,8 +13,8 @@ fn parse_agg_trade_valid_payload() {
     let trade = parse_agg_trade(payload).expect("expected a parsed aggTrade payload");
 
     assert_eq!(trade.s, "BTCUSDT");
-    assert_eq!(trade.p, "43000.50");
-    assert_eq!(trade.q, "0.1200");
+    assert_eq!(trade.p.to_string(), "43000.50");
+    assert_eq!(trade.q.to_string(), "0.1200");
     assert_eq!(trade.t, 1710000000010);
     assert!(trade.m);
 }
@@ -38,12 +38,12 @@ fn parse_funding_rate_valid_payload() {
 
     assert_eq!(event.symbol, "BTCUSDT");
     assert_eq!(event.event_time, 1710000000100);
-    assert_eq!(event.funding_rate, "0.00120000");
+    assert_eq!(event.funding_rate.to_string(), "0.00120000");
 }
 
 #[test]
 fn high_funding_rate_threshold_is_absolute() {
-    let pct = funding_rate_pct("-0.0025").expect("percentage conversion");
+    let pct = funding_rate_pct(&"-0.0025".parse().expect("valid decimal"));
     assert_eq!(pct, -0.25);
     assert!(is_high_funding_rate(pct, 0.10));
     assert!(!is_high_funding_rate(0.02, 0.10));
@@ -130,8 +130,8 @@ fn depth_big_levels_detected_and_formatted() {
     let asks = collect_big_levels(&depth.asks, 5.0, 3);
 
     assert!(is_big_depth_update(&bids, &asks));
-    assert_eq!(format_depth_levels(&bids), "24100.10 x 12.5000");
-    assert_eq!(format_depth_levels(&asks), "24100.20 x 10.0000");
+    assert_eq!(format_depth_levels(&bids), "24100.10 x 12.5");
+    assert_eq!(format_depth_levels(&asks), "24100.20 x 10.0");
 }
 
 #[test]
@@ -4,6 +4,8 @@ use feeder_service::news::store::NewsStore;
 use feeder_service::news::tagging::tag_symbols;
 use feeder_service::news::types::NewsItem;
 use feeder_service::refactor::AppState;
+use feeder_service::refactor::big_move_detector::BigMoveProfile;
+use feeder_service::threshold::BigTradeRule;
 use tokio::sync::broadcast;
 
 fn test_db_path(name: &str) -> String {
@@ -67,40 +69,32 @@ async fn correlates_real_tagged_news_for_app_state_signals() {
         symbols: vec![SymbolConfig {
             symbol: "btcusdt".to_string(),
             big_trade_qty: 0.1,
-            spike_pct: 0.0,
+            whale_notional: 1_000_000.0,
+            big_trade_rule: BigTradeRule::FixedQty,
+            big_move_profiles: vec![BigMoveProfile::default()],
+            ..Default::default()
         }],
         port: 9001,
         broadcast_capacity: 32,
-        big_depth_min_qty: 0.0,
-        big_depth_min_notional: 0.0,
-        big_depth_min_pressure_pct: 0.0,
-        disable_depth_stream: false,
-        enable_funding_rate: false,
+        spike_windows_secs: vec![10, 60, 300],
         funding_rate_alert_pct: 0.1,
         funding_rate_cooldown_secs: 300,
         corr_min_move_pct: 0.25,
         corr_max_lag_seconds: 300,
         corr_min_confidence: 0.6,
-        news_streams: vec![],
         news: NewsConfig {
             enabled: true,
             db_path: db_path.clone(),
             poll_interval_secs: 60,
             retention_hours: 24,
-            finnhub_api_key: None,
-            newsapi_api_key: None,
+            ..Default::default()
         },
         telegram: TelegramConfig {
-            enabled: false,
-            bot_token: None,
-            chat_id: None,
-            thread_id: None,
-            include_bigmove: false,
             debounce_window_secs: 45,
-            min_correlation_score: 0.0,
-            rate_limit_interval_secs: 0,
             api_base_url: "https://api.telegram.org".to_string(),
+            ..Default::default()
         },
+        ..Default::default()
     };
 
     let mut app_state = AppState::new(config);
@@ -110,8 +104,8 @@ async fn correlates_real_tagged_news_for_app_state_signals() {
         .process_agg_trade(
             &AggTrade {
                 s: "BTCUSDT".to_string(),
-                p: "43000.0".to_string(),
-                q: "0.25".to_string(),
+                p: "43000.0".parse().unwrap(),
+                q: "0.25".parse().unwrap(),
                 t: 1_710_000_001_000,
                 m: false,
             },
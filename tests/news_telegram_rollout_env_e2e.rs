@@ -1,4 +1,5 @@
 use feeder_service::config::Config;
+use feeder_service::symbols::RegistryMode;
 
 fn clear_env(keys: &[&str]) {
     for key in keys {
@@ -16,6 +17,8 @@ fn rollout_flags_and_thresholds_have_safe_defaults_and_expected_overrides() {
         "TELEGRAM_MIN_CORRELATION_SCORE",
         "TELEGRAM_RATE_LIMIT_INTERVAL_SECS",
         "NEWS_DB_PATH",
+        "ENABLE_TRADE_FLOW",
+        "SYMBOL_REGISTRY_MODE",
     ];
 
     clear_env(&keys);
@@ -27,6 +30,8 @@ fn rollout_flags_and_thresholds_have_safe_defaults_and_expected_overrides() {
     assert_eq!(defaults.telegram.min_correlation_score, 0.0);
     assert_eq!(defaults.telegram.rate_limit_interval_secs, 30);
     assert_eq!(defaults.news.db_path, "news.sqlite");
+    assert!(!defaults.flow.enabled);
+    assert_eq!(defaults.registry.mode, RegistryMode::Off);
 
     unsafe { std::env::set_var("SYMBOLS", "btcusdt") };
     unsafe { std::env::set_var("ENABLE_NEWS_INGEST", "true") };
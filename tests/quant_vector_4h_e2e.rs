@@ -2,15 +2,16 @@ use feeder_service::{
     binance_depth::DepthUpdate,
     binance_kline::parse_kline_event,
     config::{Config, NewsConfig, SymbolConfig, TelegramConfig},
-    refactor::AppState,
+    refactor::{AppState, big_move_detector::BigMoveProfile},
+    threshold::BigTradeRule,
 };
 use tokio::sync::broadcast;
 
 fn depth(symbol: &str, event_time: u64, bid_qty: &str, ask_qty: &str) -> DepthUpdate {
     DepthUpdate {
         symbol: symbol.to_string(),
-        bids: vec![["100.0".to_string(), bid_qty.to_string()]],
-        asks: vec![["100.0".to_string(), ask_qty.to_string()]],
+        bids: vec![["100.0".parse().unwrap(), bid_qty.parse().unwrap()]],
+        asks: vec![["100.0".parse().unwrap(), ask_qty.parse().unwrap()]],
         event_time,
         first_update_id: 1,
         final_update_id: 2,
@@ -24,39 +25,31 @@ async fn quant_vector_uses_closed_4h_kline_and_stays_separate_from_depth() {
             symbol: "btcusdt".to_string(),
             big_trade_qty: 1.0,
             spike_pct: 0.4,
+            whale_notional: 1_000_000.0,
+            big_trade_rule: BigTradeRule::FixedQty,
+            big_move_profiles: vec![BigMoveProfile::default()],
+            ..Default::default()
         }],
         port: 9001,
         broadcast_capacity: 64,
-        big_depth_min_qty: 0.0,
-        big_depth_min_notional: 0.0,
-        big_depth_min_pressure_pct: 0.0,
-        disable_depth_stream: false,
-        enable_funding_rate: false,
+        spike_windows_secs: vec![10, 60, 300],
         funding_rate_alert_pct: 0.1,
         funding_rate_cooldown_secs: 300,
         corr_min_move_pct: 0.25,
         corr_max_lag_seconds: 300,
         corr_min_confidence: 0.6,
-        news_streams: vec![],
         news: NewsConfig {
-            enabled: false,
             db_path: "news.sqlite".to_string(),
             poll_interval_secs: 300,
             retention_hours: 168,
-            finnhub_api_key: None,
-            newsapi_api_key: None,
+            ..Default::default()
         },
         telegram: TelegramConfig {
-            enabled: false,
-            bot_token: None,
-            chat_id: None,
-            thread_id: None,
-            include_bigmove: false,
             debounce_window_secs: 45,
-            min_correlation_score: 0.0,
-            rate_limit_interval_secs: 0,
             api_base_url: "https://api.telegram.org".to_string(),
+            ..Default::default()
         },
+        ..Default::default()
     };
 
     let mut app = AppState::new(config);
@@ -1,7 +1,8 @@
 use feeder_service::{
     binance_kline::parse_kline_event,
     config::{Config, NewsConfig, SymbolConfig, TelegramConfig},
-    refactor::AppState,
+    refactor::{AppState, big_move_detector::BigMoveProfile},
+    threshold::BigTradeRule,
 };
 use tokio::sync::broadcast;
 
@@ -12,42 +13,34 @@ async fn quant_vector_ignores_open_4h_kline_events() {
             symbol: "btcusdt".to_string(),
             big_trade_qty: 1.0,
             spike_pct: 0.4,
+            whale_notional: 1_000_000.0,
+            big_trade_rule: BigTradeRule::FixedQty,
+            big_move_profiles: vec![BigMoveProfile::default()],
+            ..Default::default()
         }],
         port: 9001,
         broadcast_capacity: 64,
-        big_depth_min_qty: 0.0,
-        big_depth_min_notional: 0.0,
-        big_depth_min_pressure_pct: 0.0,
-        disable_depth_stream: false,
-        enable_funding_rate: false,
+        spike_windows_secs: vec![10, 60, 300],
         funding_rate_alert_pct: 0.1,
         funding_rate_cooldown_secs: 300,
         corr_min_move_pct: 0.25,
         corr_max_lag_seconds: 300,
         corr_min_confidence: 0.6,
-        news_streams: vec![],
         news: NewsConfig {
-            enabled: false,
             db_path: "news.sqlite".to_string(),
             poll_interval_secs: 300,
             retention_hours: 168,
-            finnhub_api_key: None,
-            newsapi_api_key: None,
+            ..Default::default()
         },
         telegram: TelegramConfig {
-            enabled: false,
-            bot_token: None,
-            chat_id: None,
-            thread_id: None,
-            include_bigmove: false,
             debounce_window_secs: 45,
-            min_correlation_score: 0.0,
-            rate_limit_interval_secs: 0,
             api_base_url: "https://api.telegram.org".to_string(),
+            ..Default::default()
         },
+        ..Default::default()
     };
 
-    let app = AppState::new(config);
+    let mut app = AppState::new(config);
     let (tx, mut rx) = broadcast::channel(64);
 
     let payload = r#"{
@@ -1,5 +1,8 @@
-use feeder_service::ws_helpers::{handle_client_with_notifier, handle_client_with_settings};
-use futures_util::SinkExt;
+use feeder_service::shutdown::Shutdown;
+use feeder_service::ws_helpers::{
+    handle_client_with_notifier, handle_client_with_settings, handle_client_with_shutdown,
+};
+use futures_util::{SinkExt, StreamExt};
 use std::net::TcpListener;
 use std::sync::Arc;
 use tokio::sync::{Mutex, broadcast, oneshot};
@@ -122,3 +125,59 @@ async fn reports_heartbeat_timeout_when_client_does_not_pong() {
 
     assert!(disconnect_reason.contains("heartbeat timed out"));
 }
+
+#[tokio::test]
+async fn sends_going_away_close_frame_on_server_shutdown() {
+    let (broadcast_tx, _broadcast_rx) = broadcast::channel::<String>(8);
+    let shutdown = Shutdown::new();
+
+    let ws_route = warp::path("aggTrade").and(warp::ws()).map({
+        let tx = broadcast_tx.clone();
+        let shutdown = shutdown.clone();
+        move |ws: warp::ws::Ws| {
+            let tx_inner = tx.clone();
+            let shutdown = shutdown.clone();
+            ws.on_upgrade(move |socket| handle_client_with_shutdown(socket, tx_inner, shutdown))
+        }
+    });
+
+    let std_listener = TcpListener::bind("127.0.0.1:0").expect("bind ephemeral port");
+    let addr = std_listener.local_addr().expect("read local addr");
+    drop(std_listener);
+
+    tokio::task::spawn(warp::serve(ws_route).run(([127, 0, 0, 1], addr.port())));
+
+    let ws_url =
+        Url::parse(&format!("ws://127.0.0.1:{}/aggTrade", addr.port())).expect("build ws url");
+
+    let mut connected = None;
+    for _ in 0..20 {
+        match connect_async(ws_url.clone()).await {
+            Ok(client) => {
+                connected = Some(client);
+                break;
+            }
+            Err(_) => tokio::time::sleep(std::time::Duration::from_millis(50)).await,
+        }
+    }
+
+    let (mut client, _) = connected.expect("connect websocket");
+    shutdown.trigger();
+
+    let frame = loop {
+        let msg = tokio::time::timeout(std::time::Duration::from_secs(3), client.next())
+            .await
+            .expect("close timeout")
+            .expect("stream open")
+            .expect("read frame");
+        if let tokio_tungstenite::tungstenite::Message::Close(frame) = msg {
+            break frame.expect("close frame with reason");
+        }
+    };
+
+    assert_eq!(
+        frame.code,
+        tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode::Away
+    );
+    assert_eq!(frame.reason, "server shutting down");
+}